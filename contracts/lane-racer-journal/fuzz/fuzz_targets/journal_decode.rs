@@ -0,0 +1,48 @@
+//! Journals of any length and content through `GameJournal::decode`.
+//!
+//! The journal comes from the proof's submitter, so decoding must never panic, and each
+//! malformed journal must be reported with the error for the first check it fails.
+
+#![no_main]
+
+use lane_racer_journal::{Difficulty, GameJournal, JournalError, JOURNAL_LEN};
+use libfuzzer_sys::fuzz_target;
+use soroban_sdk::{Bytes, Env};
+
+const SCORE_AT: usize = 152;
+const LANES_AT: usize = 188;
+const DIFFICULTY_AT: usize = 189;
+const FLAGS_AT: usize = 190;
+const TICKS_AT: usize = 199;
+
+fuzz_target!(|bytes: &[u8]| {
+    let env = Env::default();
+    let actual = GameJournal::decode(&Bytes::from_slice(&env, bytes));
+
+    if bytes.len() != JOURNAL_LEN as usize {
+        assert_eq!(actual, Err(JournalError::Length));
+        return;
+    }
+    let difficulty = match bytes[DIFFICULTY_AT] {
+        0 => Difficulty::Easy,
+        1 => Difficulty::Normal,
+        2 => Difficulty::Hard,
+        _ => return assert_eq!(actual, Err(JournalError::Difficulty)),
+    };
+    if bytes[FLAGS_AT] > 3 {
+        return assert_eq!(actual, Err(JournalError::Flags));
+    }
+    if !(3..=5).contains(&bytes[LANES_AT]) {
+        return assert_eq!(actual, Err(JournalError::Lanes));
+    }
+
+    let journal = actual.expect("well-formed journal decodes");
+    assert_eq!(journal.sim_version.to_le_bytes(), bytes[..4]);
+    assert_eq!(journal.player.to_array(), bytes[4..36]);
+    assert_eq!(journal.score.to_le_bytes(), bytes[SCORE_AT..SCORE_AT + 4]);
+    assert_eq!(journal.lanes, u32::from(bytes[LANES_AT]));
+    assert_eq!(journal.difficulty, difficulty);
+    assert_eq!(journal.collision_occurred, bytes[FLAGS_AT] & 1 != 0);
+    assert_eq!(journal.empty_trace, bytes[FLAGS_AT] & 2 != 0);
+    assert_eq!(journal.ticks.to_le_bytes(), bytes[TICKS_AT..TICKS_AT + 4]);
+});
@@ -0,0 +1,186 @@
+//! Decodes the journal committed by the Lane Racer guest inside Soroban contracts.
+//!
+//! The layout is the fixed one written by `shared::journal` in the prover workspace:
+//! little-endian integers at fixed offsets, no length prefixes. It is repeated here as
+//! offsets so a contract can depend on this crate without pulling in the guest's code.
+
+#![no_std]
+
+use soroban_sdk::{contracttype, Bytes, BytesN};
+
+/// Length of an encoded journal in bytes.
+pub const JOURNAL_LEN: u32 = 203;
+
+/// `SIM_VERSION`s whose journals have the layout decoded here. A guest release that changes
+/// the layout must raise `MIN_SIM_VERSION` past the versions it no longer matches. Version 8
+/// journals share the layout but never set the empty-trace flag, so an empty v8 run would
+/// pass for a played one.
+pub const MIN_SIM_VERSION: u32 = 9;
+pub const MAX_SIM_VERSION: u32 = 9;
+
+/// `config_hash` of the official rules, `GameConfig::default()` in the prover's sim. The guest
+/// fixture's run is played under them.
+pub const OFFICIAL_CONFIG_HASH: [u8; 32] = [
+    0x4b, 0x29, 0x51, 0x84, 0xed, 0x31, 0xdb, 0x86,
+    0x5f, 0xb0, 0x25, 0x6a, 0xa6, 0x8c, 0xb7, 0xe1,
+    0xad, 0x75, 0x42, 0x9c, 0x05, 0x8b, 0x5f, 0x78,
+    0xac, 0x07, 0xc5, 0x0e, 0x36, 0xf1, 0x03, 0x37,
+];
+
+/// Lane counts the guest accepts.
+pub const MIN_LANES: u32 = 3;
+pub const MAX_LANES: u32 = 5;
+
+const FLAG_COLLISION: u8 = 1;
+const FLAG_EMPTY_TRACE: u8 = 2;
+
+/// Difficulty tier, as committed by the guest.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Difficulty {
+    Easy = 0,
+    Normal = 1,
+    Hard = 2,
+}
+
+/// Public outputs of one game.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameJournal {
+    /// `SIM_VERSION` of the guest that produced the journal.
+    pub sim_version: u32,
+    /// Raw ed25519 public key of the player's account.
+    pub player: BytesN<32>,
+    pub game_id: u64,
+    pub session_id: u32,
+    /// ID of the contract the proof was made for.
+    pub contract_id: BytesN<32>,
+    pub seed: u64,
+    pub actions_hash: BytesN<32>,
+    pub config_hash: BytesN<32>,
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub speed_reached: u32,
+    pub shields_collected: u32,
+    pub shields_used: u32,
+    pub magnets_collected: u32,
+    pub near_misses: u32,
+    pub best_combo: u32,
+    pub lanes: u32,
+    pub difficulty: Difficulty,
+    pub collision_occurred: bool,
+    /// The action trace had no ticks: the run never started and scored nothing.
+    pub empty_trace: bool,
+    /// Obstacles and gems that spawned during the run, bounding `obstacles_dodged` and
+    /// `gems_collected`.
+    pub obstacles_spawned: u32,
+    pub gems_spawned: u32,
+    /// Ticks played, pauses excluded.
+    pub ticks: u32,
+}
+
+/// Why a journal could not be decoded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JournalError {
+    /// Not `JOURNAL_LEN` bytes.
+    Length,
+    /// A lane count outside `MIN_LANES..=MAX_LANES`.
+    Lanes,
+    /// An unknown difficulty code.
+    Difficulty,
+    /// Flag bits other than the collision and empty-trace flags.
+    Flags,
+}
+
+/// Reads fields in layout order.
+struct Reader<'a> {
+    bytes: &'a Bytes,
+    at: u32,
+}
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        self.bytes.slice(self.at..self.at + N as u32).copy_into_slice(&mut out);
+        self.at += N as u32;
+        out
+    }
+
+    fn u8(&mut self) -> u8 {
+        let [value] = self.array();
+        value
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.array())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.array())
+    }
+
+    fn bytes32(&mut self) -> BytesN<32> {
+        BytesN::from_array(self.bytes.env(), &self.array())
+    }
+}
+
+impl GameJournal {
+    /// Decodes a journal. The version is not checked here; callers compare `sim_version`
+    /// against the versions they accept.
+    pub fn decode(bytes: &Bytes) -> Result<Self, JournalError> {
+        if bytes.len() != JOURNAL_LEN {
+            return Err(JournalError::Length);
+        }
+        let mut r = Reader { bytes, at: 0 };
+        let mut journal = Self {
+            sim_version: r.u32(),
+            player: r.bytes32(),
+            game_id: r.u64(),
+            session_id: r.u32(),
+            contract_id: r.bytes32(),
+            seed: r.u64(),
+            actions_hash: r.bytes32(),
+            config_hash: r.bytes32(),
+            score: r.u32(),
+            obstacles_dodged: r.u32(),
+            gems_collected: r.u32(),
+            speed_reached: r.u32(),
+            shields_collected: r.u32(),
+            shields_used: r.u32(),
+            magnets_collected: r.u32(),
+            near_misses: r.u32(),
+            best_combo: r.u32(),
+            lanes: r.u8() as u32,
+            difficulty: Difficulty::Normal,
+            collision_occurred: false,
+            empty_trace: false,
+            obstacles_spawned: 0,
+            gems_spawned: 0,
+            ticks: 0,
+        };
+        journal.difficulty = match r.u8() {
+            0 => Difficulty::Easy,
+            1 => Difficulty::Normal,
+            2 => Difficulty::Hard,
+            _ => return Err(JournalError::Difficulty),
+        };
+        let flags = r.u8();
+        if flags & !(FLAG_COLLISION | FLAG_EMPTY_TRACE) != 0 {
+            return Err(JournalError::Flags);
+        }
+        if !(MIN_LANES..=MAX_LANES).contains(&journal.lanes) {
+            return Err(JournalError::Lanes);
+        }
+        journal.collision_occurred = flags & FLAG_COLLISION != 0;
+        journal.empty_trace = flags & FLAG_EMPTY_TRACE != 0;
+        journal.obstacles_spawned = r.u32();
+        journal.gems_spawned = r.u32();
+        journal.ticks = r.u32();
+        Ok(journal)
+    }
+}
+
+#[cfg(test)]
+mod test;
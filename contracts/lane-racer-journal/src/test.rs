@@ -0,0 +1,124 @@
+extern crate std;
+
+use soroban_sdk::{Bytes, BytesN, Env};
+use std::vec::Vec;
+
+use crate::{Difficulty, GameJournal, JournalError, JOURNAL_LEN, MAX_SIM_VERSION, MIN_SIM_VERSION, OFFICIAL_CONFIG_HASH};
+
+/// Journal of the run in `fixtures/trace.txt`. The prover's `guest_matches_native` tests
+/// check that the guest commits exactly these bytes, so the two crates cannot drift apart.
+const FIXTURE_JOURNAL: &str = include_str!("../fixtures/journal.hex");
+
+fn hex(s: &str) -> Vec<u8> {
+    let s = s.trim();
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+fn fixture() -> Vec<u8> {
+    let bytes = hex(FIXTURE_JOURNAL);
+    assert_eq!(bytes.len() as u32, JOURNAL_LEN);
+    bytes
+}
+
+fn decode(env: &Env, bytes: &[u8]) -> Result<GameJournal, JournalError> {
+    GameJournal::decode(&Bytes::from_slice(env, bytes))
+}
+
+#[test]
+fn test_decodes_every_field_of_the_guest_fixture() {
+    let env = Env::default();
+    let journal = decode(&env, &fixture()).unwrap();
+    let player: [u8; 32] = hex("3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a").try_into().unwrap();
+    let actions_hash: [u8; 32] = hex("c4ef2b226053dfd52f9923ea7a80b9a2389b1a65ed188c0d6a285b32b6ca7d6f").try_into().unwrap();
+    let config_hash: [u8; 32] = hex("4b295184ed31db865fb0256aa68cb7e1ad75429c058b5f78ac07c50e36f10337").try_into().unwrap();
+    assert_eq!(
+        journal,
+        GameJournal {
+            sim_version: 9,
+            player: BytesN::from_array(&env, &player),
+            game_id: 7,
+            session_id: 7,
+            contract_id: BytesN::from_array(&env, &[0xab; 32]),
+            seed: 42,
+            actions_hash: BytesN::from_array(&env, &actions_hash),
+            config_hash: BytesN::from_array(&env, &config_hash),
+            score: 109,
+            obstacles_dodged: 17,
+            gems_collected: 6,
+            speed_reached: 125,
+            shields_collected: 0,
+            shields_used: 0,
+            magnets_collected: 1,
+            near_misses: 15,
+            best_combo: 3,
+            lanes: 3,
+            difficulty: Difficulty::Hard,
+            collision_occurred: false,
+            empty_trace: false,
+            obstacles_spawned: 18,
+            gems_spawned: 13,
+            ticks: 1500,
+        }
+    );
+}
+
+#[test]
+fn test_the_guest_fixture_is_played_under_the_official_rules() {
+    let env = Env::default();
+    let journal = decode(&env, &fixture()).unwrap();
+    assert_eq!(journal.config_hash.to_array(), OFFICIAL_CONFIG_HASH);
+}
+
+#[test]
+fn test_the_guest_fixture_has_a_supported_sim_version() {
+    let env = Env::default();
+    let journal = decode(&env, &fixture()).unwrap();
+    assert!((MIN_SIM_VERSION..=MAX_SIM_VERSION).contains(&journal.sim_version));
+}
+
+#[test]
+fn test_decodes_the_collision_flag_and_each_difficulty() {
+    let env = Env::default();
+    let mut bytes = fixture();
+    bytes[190] = 1;
+    assert!(decode(&env, &bytes).unwrap().collision_occurred);
+    bytes[190] = 2;
+    let empty = decode(&env, &bytes).unwrap();
+    assert!(empty.empty_trace && !empty.collision_occurred);
+    for (code, difficulty) in [(0, Difficulty::Easy), (1, Difficulty::Normal), (2, Difficulty::Hard)] {
+        bytes[189] = code;
+        assert_eq!(decode(&env, &bytes).unwrap().difficulty, difficulty);
+    }
+    for lanes in 3..=5 {
+        bytes[188] = lanes;
+        assert_eq!(decode(&env, &bytes).unwrap().lanes, lanes as u32);
+    }
+}
+
+#[test]
+fn test_rejects_wrong_lengths() {
+    let env = Env::default();
+    let bytes = fixture();
+    assert_eq!(decode(&env, &bytes[..bytes.len() - 1]), Err(JournalError::Length));
+    assert_eq!(decode(&env, &[bytes.as_slice(), &[0]].concat()), Err(JournalError::Length));
+    assert_eq!(decode(&env, &[]), Err(JournalError::Length));
+}
+
+#[test]
+fn test_rejects_out_of_range_enums_and_flags() {
+    let env = Env::default();
+    let bytes = fixture();
+    let cases = [
+        (188, 2, JournalError::Lanes),
+        (188, 6, JournalError::Lanes),
+        (189, 3, JournalError::Difficulty),
+        (189, 0xff, JournalError::Difficulty),
+        (190, 4, JournalError::Flags),
+        (190, 0x81, JournalError::Flags),
+    ];
+    for (offset, value, error) in cases {
+        let mut bad = bytes.clone();
+        bad[offset] = value;
+        assert_eq!(decode(&env, &bad), Err(error), "byte {} = {}", offset, value);
+    }
+}
@@ -1,18 +1,98 @@
 #![no_std]
+use lane_racer_journal::{Difficulty, GameJournal, MAX_SIM_VERSION, MIN_SIM_VERSION, OFFICIAL_CONFIG_HASH};
+use risc0_interface::RiscZeroVerifierClient;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, contracterror,
-    Env, Address, Vec, BytesN
+    address_payload::AddressPayload, contract, token, contractevent, contractimpl, contracttype, contracterror,
+    Env, Address, Vec, Bytes, BytesN
 };
+use stellar_access::ownable::{Ownable, set_owner};
+use stellar_macros::only_owner;
 
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     GameSession(u32),
     Leaderboard,
-    Admin,
     GameHub,
+    /// Verifier (usually the RISC Zero router) that checks score proofs.
+    Verifier,
+    /// Image ID of the Lane Racer guest whose proofs are accepted.
+    ImageId,
+    /// Seed published for a day of the daily challenge, in persistent storage.
+    DailySeed(u32),
+    /// Best `RANKING_SIZE` daily-challenge players of a day, highest score first, in persistent
+    /// storage.
+    DailyLeaderboard(u32),
+    /// A page of IDs of the sessions in a status, oldest first, in persistent storage. Each page
+    /// gets `STATUS_PAGE_SIZE` sessions in turn, which leave it as they move on. Completed
+    /// sessions are only counted.
+    SessionsByStatus(SessionStatus, u32),
+    /// Sessions that ever entered a status's index, which places the next one on its page.
+    StatusSlots(SessionStatus),
+    /// First page of a status's index that may still hold sessions; the ones before it emptied.
+    StatusHead(SessionStatus),
+    /// Number of sessions in a status.
+    SessionCount(SessionStatus),
+    /// `VersionRange` of journal `sim_version`s accepted by `submit_score`.
+    JournalVersions,
+    /// `EntryRequirement` for the prize-eligible leaderboard.
+    EntryRequirement,
+    /// Best `RANKING_SIZE` players who met the entry requirement when they submitted, highest
+    /// score first.
+    EligibleLeaderboard,
+    /// Marker for an allowlisted player, in persistent storage.
+    Allowed(Address),
+    /// A player's `Stake`, in persistent storage.
+    Stake(Address),
+    /// Sum of every player's stake in a token.
+    TotalStaked(Address),
+    /// Whether stakes can be withdrawn, i.e. the season is over.
+    StakesUnlocked,
+    /// `Settlement` of a session with the game hub, in persistent storage.
+    Settlement(u32),
+    /// Last ledger of the commit phase, which ranked sessions started before it take on as
+    /// their `commit_deadline`.
+    CommitDeadline,
+    /// Hash a player committed for a session's run, in persistent storage.
+    Commitment(u32),
+    /// `VestingPolicy` for prizes.
+    VestingPolicy,
+    /// A player's prizes still vesting, as a `Vec<Vesting>` in persistent storage.
+    Vesting(Address),
+    /// Part of every vesting prize in a token not claimed yet.
+    TotalUnvested(Address),
+    /// `SubmissionWindow` scores must arrive in.
+    SubmissionWindow,
+    /// Current season, counted from 0.
+    Season,
+    /// Best ranked score of a player in a season, in persistent storage.
+    PlayerBest(u32, Address),
+    /// A page of up to `SEASON_PAGE_SIZE` players with a best score in a season, in the order
+    /// they first scored, in persistent storage.
+    SeasonPlayers(u32, u32),
+    /// Number of players with a best score in a season, in persistent storage.
+    SeasonPlayerCount(u32),
+    /// `Rules` of a season's ranked runs, if not the official ones, in persistent storage.
+    SeasonRules(u32),
+    /// Best `RANKING_SIZE` players, highest score first. Derived from the `PlayerBest`s and
+    /// rebuilt from them by `rebuild_leaderboard`.
+    Ranking,
 }
 
+const DAY_IN_SECONDS: u64 = 86_400;
+const DAY_IN_LEDGERS: u32 = 17_280;
+const PERSISTENT_EXTEND_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const PERSISTENT_TTL_THRESHOLD: u32 = PERSISTENT_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+/// Players kept on the ranking.
+const RANKING_SIZE: u32 = 100;
+/// Sessions per page of a status index.
+const STATUS_PAGE_SIZE: u32 = 50;
+/// Most session IDs `get_sessions_by_status` returns at once.
+const MAX_SESSIONS_PER_QUERY: u32 = 100;
+/// Players per page of a season's player list, and so per `rebuild_leaderboard` call, which
+/// reads each one's best and must stay within an invocation's ledger-entry footprint.
+const SEASON_PAGE_SIZE: u32 = 50;
+
 #[contracterror]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(u32)]
@@ -22,6 +102,173 @@ pub enum Error {
     SessionNotFound = 3,
     NotAuthorized = 4,
     InvalidProof = 5,
+    /// The journal is not a Lane Racer journal.
+    InvalidJournal = 6,
+    /// The journal is for another player, session, contract, seed or score.
+    JournalMismatch = 7,
+    /// No daily seed has been published for today.
+    NoDailySeed = 8,
+    /// The day already has a seed; daily seeds cannot be changed once published.
+    DailySeedExists = 9,
+    /// The session is not in a status that allows this call.
+    SessionNotActive = 10,
+    /// Completed sessions are counted but not indexed.
+    StatusNotIndexed = 11,
+    /// The journal's `sim_version` is outside the accepted range.
+    UnsupportedJournalVersion = 12,
+    /// An empty range, or one reaching versions whose layout this contract cannot decode.
+    InvalidVersionRange = 13,
+    /// The entry requirement is not a stake.
+    NoStakeRequired = 14,
+    /// Stakes stay locked until the admin unlocks them at the end of the season.
+    StakeLocked = 15,
+    /// The player has nothing staked.
+    NothingStaked = 16,
+    /// The player's stake is in another token than the requirement; unstake it first.
+    StakeMismatch = 17,
+    /// The session has no failed settlement to retry.
+    NothingToSettle = 18,
+    /// The journal is of an empty action trace, which only practice sessions accept.
+    EmptyRun = 19,
+    /// Ranked scores are committed and revealed while a commit phase is set.
+    CommitRequired = 20,
+    /// The session was not started in a commit phase.
+    NoCommitPhase = 21,
+    /// The commit phase is over.
+    CommitPhaseClosed = 22,
+    /// Scores are revealed only once the commit phase is over.
+    RevealNotOpen = 23,
+    /// Nothing was committed for the session, or the journal and salt do not hash to the commitment.
+    CommitmentMismatch = 24,
+    /// Prizes must vest over at least one ledger, from a non-negative threshold.
+    InvalidVestingPolicy = 25,
+    /// Prizes must be positive.
+    InvalidPrize = 26,
+    /// None of the player's vesting prizes has anything left to claim yet.
+    NothingToClaim = 27,
+    /// The session's submission window has passed.
+    SubmissionExpired = 28,
+    /// The journal has more ticks than the submission window allows.
+    RunTooLong = 29,
+    /// The season is not the current one.
+    InvalidSeason = 30,
+    /// The contract's balance of the token, less stakes and unclaimed vesting prizes, is
+    /// smaller than the prize.
+    InsufficientPrizeFunds = 31,
+    /// The journal's config hash or difficulty is not the season's rules.
+    WrongRules = 32,
+}
+
+/// A stake requirement: at least `amount` of `token`, locked for the season.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeRequirement {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// What a player needs before their scores count for prizes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EntryRequirement {
+    /// Every score counts.
+    None,
+    /// Only allowlisted players' scores count.
+    Allowlist,
+    /// Only scores of players with a large enough stake count.
+    Stake(StakeRequirement),
+}
+
+/// Tokens a player has locked with the contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stake {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Prizes above `threshold` vest linearly over `ledgers` ledgers instead of being paid at once,
+/// so a leaderboard exploit found after a season can still be stopped with `cancel_vesting`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingPolicy {
+    pub threshold: i128,
+    pub ledgers: u32,
+}
+
+/// A prize of `amount` of `token` vesting linearly over `ledgers` ledgers from `start`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vesting {
+    pub token: Address,
+    pub amount: i128,
+    /// Part of `amount` already paid to the player.
+    pub claimed: i128,
+    pub start: u32,
+    pub ledgers: u32,
+}
+
+/// How long after `start_game` a score can be submitted, in ledgers, and how many played
+/// ticks its journal can have, so proofs of long-dead sessions cannot turn up later.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmissionWindow {
+    pub ledgers: u32,
+    pub max_ticks: u32,
+}
+
+/// What ranked runs of a season are played under: the `GameConfig`, by the hash the guest
+/// commits, and the difficulty tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rules {
+    pub config_hash: BytesN<32>,
+    pub difficulty: Difficulty,
+}
+
+/// An inclusive range of journal `sim_version`s.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Where a session is in its lifecycle.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SessionStatus {
+    /// Started, waiting for a score.
+    Active = 0,
+    /// A proven score was recorded.
+    Completed = 1,
+    /// Closed by the admin without a score.
+    Expired = 2,
+    /// Flagged by the admin for review.
+    Disputed = 3,
+}
+
+/// Outcome of reporting a session's result to the game hub.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SettlementStatus {
+    /// The hub acknowledged `end_game`.
+    Settled = 0,
+    /// The hub's `end_game` failed; the admin can retry it.
+    Failed = 1,
+}
+
+/// The last attempt at settling a session with the game hub.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub player_won: bool,
+    pub status: SettlementStatus,
+    /// Ledger sequence of the last attempt.
+    pub ledger: u32,
+    pub attempts: u32,
 }
 
 #[contracttype]
@@ -30,7 +277,24 @@ pub struct GameSession {
     pub session_id: u32,
     pub player: Address,
     pub score: u32,
-    pub active: bool,
+    pub status: SessionStatus,
+    /// Image ID the score's proof was verified against, set once a score is submitted.
+    pub image_id: Option<BytesN<32>>,
+    /// Seed the run must use: the day's published seed for daily-challenge sessions, and one
+    /// drawn by the contract at start for other ranked sessions. Practice runs pick their own.
+    pub seed: Option<u64>,
+    /// Day (see `current_day`) of the daily challenge, whose leaderboard the score goes on.
+    pub daily_day: Option<u32>,
+    /// Practice sessions are unranked: they never reach the game hub or the leaderboards,
+    /// and accept runs with an empty action trace.
+    pub practice: bool,
+    /// Ledger sequence the session started at.
+    pub started_at: u32,
+    /// Page of its status's index the session is on.
+    pub status_page: u32,
+    /// Last ledger of the commit phase the session was started in. Its score is then committed
+    /// with `commit_score` and revealed with `reveal_score` instead of going through `submit_score`.
+    pub commit_deadline: Option<u32>,
 }
 
 #[contracttype]
@@ -38,13 +302,426 @@ pub struct GameSession {
 pub struct ScoreEntry {
     pub player: Address,
     pub score: u32,
+    /// Image ID the score's proof was verified against, i.e. the simulation that produced it.
+    pub image_id: BytesN<32>,
+}
+
+/// Emitted when a proven score is recorded.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreSubmitted {
+    #[topic]
+    pub session_id: u32,
+    #[topic]
+    pub player: Address,
+    pub score: u32,
+    pub image_id: BytesN<32>,
+}
+
+/// Emitted when the admin switches the accepted guest image.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImageIdUpdated {
+    pub previous: BytesN<32>,
+    pub image_id: BytesN<32>,
 }
 
+/// A proof of one run, as the prover returns it.
 #[contracttype]
 #[derive(Clone)]
 pub struct ZKProof {
-    pub seal: BytesN<64>,
-    pub journal: BytesN<32>,
+    /// Seal in the verifier's format: `selector || proof`.
+    pub seal: Bytes,
+    /// Raw journal committed by the guest. The contract hashes it for the verifier and
+    /// decodes it to check the run is this session's.
+    pub journal: Bytes,
+}
+
+/// Emitted whenever a session enters a status, including `Active` when it starts.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionStatusChanged {
+    #[topic]
+    pub session_id: u32,
+    pub status: SessionStatus,
+}
+
+/// Emitted on every attempt at settling a session with the game hub.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementRecorded {
+    #[topic]
+    pub session_id: u32,
+    pub player_won: bool,
+    pub status: SettlementStatus,
+}
+
+/// Emitted when the admin changes the accepted journal versions.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JournalVersionsUpdated {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Emitted when the admin changes the entry requirement.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntryRequirementUpdated {
+    pub requirement: EntryRequirement,
+}
+
+/// Emitted when the admin adds a player to or removes one from the allowlist.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistUpdated {
+    #[topic]
+    pub player: Address,
+    pub allowed: bool,
+}
+
+/// Emitted when a player stakes or withdraws; `amount` is the stake afterwards.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeUpdated {
+    #[topic]
+    pub player: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emitted when a player commits to a run's journal during the commit phase.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreCommitted {
+    #[topic]
+    pub session_id: u32,
+    #[topic]
+    pub player: Address,
+    pub commitment: BytesN<32>,
+}
+
+/// Emitted when the admin awards a prize; `vesting_ledgers` is 0 for prizes paid at once.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrizeAwarded {
+    #[topic]
+    pub player: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub vesting_ledgers: u32,
+}
+
+/// Emitted for every vested amount a player claims.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestedClaimed {
+    #[topic]
+    pub player: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emitted when the admin stops a player's vesting prizes.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingCancelled {
+    #[topic]
+    pub player: Address,
+}
+
+/// Emitted when the admin starts a season, which clears the ranking.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeasonStarted {
+    #[topic]
+    pub season: u32,
+}
+
+/// Emitted for each page of players the admin ranks again; `players` is how many are on the
+/// ranking afterwards.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardRebuilt {
+    pub season: u32,
+    pub page: u32,
+    pub players: u32,
+}
+
+/// Emitted when the admin publishes the seed of a daily challenge.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DailySeedPublished {
+    #[topic]
+    pub day: u32,
+    pub seed: u64,
+}
+
+/// Day of the daily challenge the ledger is in, counted in UTC days since the Unix epoch.
+fn today(env: &Env) -> u32 {
+    (env.ledger().timestamp() / DAY_IN_SECONDS) as u32
+}
+
+fn read_persistent<V: soroban_sdk::TryFromVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_EXTEND_AMOUNT);
+    }
+    value
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_EXTEND_AMOUNT);
+}
+
+fn status_head(env: &Env, status: SessionStatus) -> u32 {
+    env.storage().instance().get(&DataKey::StatusHead(status)).unwrap_or(0)
+}
+
+/// Moves the head of a status's index past its emptied pages, so queries stop reading them.
+/// The last page still takes new sessions, so the head never passes it.
+fn advance_status_head(env: &Env, status: SessionStatus) {
+    let slots: u32 = env.storage().instance().get(&DataKey::StatusSlots(status)).unwrap_or(0);
+    let pages = slots.div_ceil(STATUS_PAGE_SIZE);
+    let mut head = status_head(env, status);
+    while head + 1 < pages
+        && read_persistent::<Vec<u32>>(env, &DataKey::SessionsByStatus(status, head)).is_none_or(|ids| ids.is_empty())
+    {
+        head += 1;
+    }
+    env.storage().instance().set(&DataKey::StatusHead(status), &head);
+}
+
+/// Moves `session` to `status`, keeping the per-status indexes and counts in step, and stores it.
+fn set_status(env: &Env, session: &mut GameSession, status: SessionStatus, is_new: bool) {
+    if !is_new {
+        let previous = session.status;
+        let count: u32 = env.storage().instance().get(&DataKey::SessionCount(previous)).unwrap_or(0);
+        env.storage().instance().set(&DataKey::SessionCount(previous), &count.saturating_sub(1));
+        if previous != SessionStatus::Completed {
+            let key = DataKey::SessionsByStatus(previous, session.status_page);
+            if let Some(mut ids) = read_persistent::<Vec<u32>>(env, &key) {
+                if let Some(at) = ids.first_index_of(session.session_id) {
+                    ids.remove(at);
+                }
+                write_persistent(env, &key, &ids);
+                if ids.is_empty() && session.status_page == status_head(env, previous) {
+                    advance_status_head(env, previous);
+                }
+            }
+        }
+    }
+    let count: u32 = env.storage().instance().get(&DataKey::SessionCount(status)).unwrap_or(0);
+    env.storage().instance().set(&DataKey::SessionCount(status), &(count + 1));
+    if status != SessionStatus::Completed {
+        let slots: u32 = env.storage().instance().get(&DataKey::StatusSlots(status)).unwrap_or(0);
+        env.storage().instance().set(&DataKey::StatusSlots(status), &(slots + 1));
+        session.status_page = slots / STATUS_PAGE_SIZE;
+        let key = DataKey::SessionsByStatus(status, session.status_page);
+        let mut ids: Vec<u32> = read_persistent(env, &key).unwrap_or(Vec::new(env));
+        ids.push_back(session.session_id);
+        write_persistent(env, &key, &ids);
+    }
+    session.status = status;
+    env.storage().instance().set(&DataKey::GameSession(session.session_id), session);
+    SessionStatusChanged { session_id: session.session_id, status }.publish(env);
+}
+
+/// Accepted journal versions; every version the decoder supports until the admin narrows it.
+/// A range stored before the decoder dropped a version is narrowed to the ones it still reads.
+fn journal_versions(env: &Env) -> VersionRange {
+    let stored: Option<VersionRange> = env.storage().instance().get(&DataKey::JournalVersions);
+    match stored {
+        Some(range) => VersionRange { min: range.min.max(MIN_SIM_VERSION), max: range.max.min(MAX_SIM_VERSION) },
+        None => VersionRange { min: MIN_SIM_VERSION, max: MAX_SIM_VERSION },
+    }
+}
+
+fn entry_requirement(env: &Env) -> EntryRequirement {
+    env.storage().instance().get(&DataKey::EntryRequirement).unwrap_or(EntryRequirement::None)
+}
+
+/// Whether `player`'s scores currently count for prizes.
+fn is_eligible(env: &Env, player: &Address) -> bool {
+    match entry_requirement(env) {
+        EntryRequirement::None => true,
+        EntryRequirement::Allowlist => env.storage().persistent().has(&DataKey::Allowed(player.clone())),
+        EntryRequirement::Stake(required) => read_persistent::<Stake>(env, &DataKey::Stake(player.clone()))
+            .is_some_and(|stake| stake.token == required.token && stake.amount >= required.amount),
+    }
+}
+
+/// Reads a session that must be in one of `statuses`.
+fn session_in(env: &Env, session_id: u32, statuses: &[SessionStatus]) -> Result<GameSession, Error> {
+    let session: GameSession = env
+        .storage()
+        .instance()
+        .get(&DataKey::GameSession(session_id))
+        .ok_or(Error::SessionNotFound)?;
+    if !statuses.contains(&session.status) {
+        return Err(Error::SessionNotActive);
+    }
+    Ok(session)
+}
+
+/// Reports a session's result to the game hub and records the settlement. A failing hub does
+/// not fail the call, so the game's own state still moves on and the admin can retry later.
+fn end_game(env: &Env, session_id: u32, player_won: bool) -> Result<Settlement, Error> {
+    let game_hub: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::GameHub)
+        .ok_or(Error::NotInitialized)?;
+    let result = env.try_invoke_contract::<(), soroban_sdk::InvokeError>(
+        &game_hub,
+        &soroban_sdk::symbol_short!("end_game"),
+        soroban_sdk::vec![
+            env,
+            soroban_sdk::IntoVal::into_val(&session_id, env),
+            soroban_sdk::IntoVal::into_val(&player_won, env),
+        ],
+    );
+    let status = match result {
+        Ok(Ok(())) => SettlementStatus::Settled,
+        _ => SettlementStatus::Failed,
+    };
+    let key = DataKey::Settlement(session_id);
+    let attempts = read_persistent::<Settlement>(env, &key).map_or(0, |previous| previous.attempts);
+    let settlement = Settlement { player_won, status, ledger: env.ledger().sequence(), attempts: attempts + 1 };
+    write_persistent(env, &key, &settlement);
+    SettlementRecorded { session_id, player_won, status }.publish(env);
+    Ok(settlement)
+}
+
+fn submission_window(env: &Env) -> Option<SubmissionWindow> {
+    env.storage().instance().get(&DataKey::SubmissionWindow)
+}
+
+/// Fails once the session's submission window, if any, has passed. The window counts from the
+/// session's start, or for committed sessions from the end of their commit phase, when
+/// reveals open.
+fn check_submission_deadline(env: &Env, session: &GameSession) -> Result<(), Error> {
+    let opened = session.commit_deadline.unwrap_or(session.started_at);
+    match submission_window(env) {
+        Some(window) if env.ledger().sequence() > opened.saturating_add(window.ledgers) => {
+            Err(Error::SubmissionExpired)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn current_season(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::Season).unwrap_or(0)
+}
+
+/// The season's rules; the official config at normal difficulty unless the admin set others.
+fn season_rules(env: &Env, season: u32) -> Rules {
+    read_persistent(env, &DataKey::SeasonRules(season)).unwrap_or(Rules {
+        config_hash: BytesN::from_array(env, &OFFICIAL_CONFIG_HASH),
+        difficulty: Difficulty::Normal,
+    })
+}
+
+/// Puts `entry` on `ranking` unless its player already ranks as high, keeping the best
+/// `RANKING_SIZE` players highest first. Ties keep the earlier entry ahead.
+fn rank(ranking: &mut Vec<ScoreEntry>, entry: ScoreEntry) {
+    if let Some(at) = ranking.iter().position(|ranked| ranked.player == entry.player) {
+        if ranking.get_unchecked(at as u32).score >= entry.score {
+            return;
+        }
+        ranking.remove(at as u32);
+    }
+    let at = ranking.iter().position(|ranked| ranked.score < entry.score).map_or(ranking.len(), |at| at as u32);
+    if at < RANKING_SIZE {
+        ranking.insert(at, entry);
+        while ranking.len() > RANKING_SIZE {
+            ranking.pop_back();
+        }
+    }
+}
+
+/// Records `entry` as its player's best of the current season if it beats their previous one,
+/// and ranks it.
+fn record_best(env: &Env, entry: &ScoreEntry) {
+    let season = current_season(env);
+    let key = DataKey::PlayerBest(season, entry.player.clone());
+    let best: Option<ScoreEntry> = read_persistent(env, &key);
+    if best.as_ref().is_some_and(|best| best.score >= entry.score) {
+        return;
+    }
+    if best.is_none() {
+        let count_key = DataKey::SeasonPlayerCount(season);
+        let count: u32 = read_persistent(env, &count_key).unwrap_or(0);
+        write_persistent(env, &count_key, &(count + 1));
+        let players_key = DataKey::SeasonPlayers(season, count / SEASON_PAGE_SIZE);
+        let mut players: Vec<Address> = read_persistent(env, &players_key).unwrap_or(Vec::new(env));
+        players.push_back(entry.player.clone());
+        write_persistent(env, &players_key, &players);
+    }
+    write_persistent(env, &key, entry);
+    let mut ranking: Vec<ScoreEntry> = env.storage().instance().get(&DataKey::Ranking).unwrap_or(Vec::new(env));
+    rank(&mut ranking, entry.clone());
+    env.storage().instance().set(&DataKey::Ranking, &ranking);
+}
+
+/// Ranks `entry` on the eligible leaderboard, which like the ranking keeps each player's best.
+fn record_eligible(env: &Env, entry: &ScoreEntry) {
+    let mut eligible: Vec<ScoreEntry> =
+        env.storage().instance().get(&DataKey::EligibleLeaderboard).unwrap_or(Vec::new(env));
+    rank(&mut eligible, entry.clone());
+    env.storage().instance().set(&DataKey::EligibleLeaderboard, &eligible);
+}
+
+/// Part of `vesting` unlocked at `ledger`.
+fn vested(vesting: &Vesting, ledger: u32) -> i128 {
+    let elapsed = i128::from(ledger.saturating_sub(vesting.start).min(vesting.ledgers));
+    let ledgers = i128::from(vesting.ledgers);
+    match vesting.amount.checked_mul(elapsed) {
+        Some(product) => product / ledgers,
+        // Scaling the quotient and remainder separately gives the same result without overflowing.
+        None => vesting.amount / ledgers * elapsed + vesting.amount % ledgers * elapsed / ledgers,
+    }
+}
+
+/// Adds `delta` to a per-token total such as `TotalStaked`.
+fn add_to_total(env: &Env, key: &DataKey, delta: i128) {
+    let total: i128 = env.storage().instance().get(key).unwrap_or(0);
+    env.storage().instance().set(key, &(total + delta));
+}
+
+/// The contract's balance of `token` that is neither staked nor owed to vesting prizes.
+fn free_balance(env: &Env, token: &Address) -> i128 {
+    let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+    let staked: i128 = env.storage().instance().get(&DataKey::TotalStaked(token.clone())).unwrap_or(0);
+    let unvested: i128 = env.storage().instance().get(&DataKey::TotalUnvested(token.clone())).unwrap_or(0);
+    balance - staked - unvested
+}
+
+/// Hash a player commits to before revealing a run: `sha256(journal || salt)`.
+fn commitment(env: &Env, journal: &Bytes, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = journal.clone();
+    preimage.append(&Bytes::from_array(env, &salt.to_array()));
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Checks the seal against the configured verifier and image ID, returning the image ID.
+fn verify_proof(env: &Env, proof: &ZKProof) -> Result<BytesN<32>, Error> {
+    let verifier: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Verifier)
+        .ok_or(Error::NotInitialized)?;
+    let image_id: BytesN<32> = env
+        .storage()
+        .instance()
+        .get(&DataKey::ImageId)
+        .ok_or(Error::NotInitialized)?;
+    let journal_digest: BytesN<32> = env.crypto().sha256(&proof.journal).into();
+    match RiscZeroVerifierClient::new(env, &verifier).try_verify(&proof.seal, &image_id, &journal_digest) {
+        Ok(Ok(())) => Ok(image_id),
+        _ => Err(Error::InvalidProof),
+    }
 }
 
 #[contract]
@@ -52,19 +729,95 @@ pub struct LaneRacerContract;
 
 #[contractimpl]
 impl LaneRacerContract {
-    pub fn init(env: Env, admin: Address, game_hub: Address) {
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::Admin, &admin);
+    /// Sets up the game at deployment, with `admin` as the owner that manages it.
+    pub fn __constructor(env: Env, admin: Address, game_hub: Address, verifier: Address, image_id: BytesN<32>) {
+        set_owner(&env, &admin);
         env.storage().instance().set(&DataKey::GameHub, &game_hub);
+        env.storage().instance().set(&DataKey::Verifier, &verifier);
+        env.storage().instance().set(&DataKey::ImageId, &image_id);
         let empty: Vec<ScoreEntry> = Vec::new(&env);
         env.storage().instance().set(&DataKey::Leaderboard, &empty);
     }
 
+    /// Accepts proofs of a new guest image from now on, e.g. after a simulation change.
+    #[only_owner]
+    pub fn set_image_id(env: Env, image_id: BytesN<32>) -> Result<(), Error> {
+        let previous: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ImageId)
+            .ok_or(Error::NotInitialized)?;
+        env.storage().instance().set(&DataKey::ImageId, &image_id);
+        ImageIdUpdated { previous, image_id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_image_id(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::ImageId)
+    }
+
+    /// Accepts journals with a `sim_version` in `min..=max`, e.g. to stop taking proofs from
+    /// an outdated guest. The range must lie within the versions `lane-racer-journal` decodes.
+    #[only_owner]
+    pub fn set_journal_versions(env: Env, min: u32, max: u32) -> Result<(), Error> {
+        if min > max || min < MIN_SIM_VERSION || max > MAX_SIM_VERSION {
+            return Err(Error::InvalidVersionRange);
+        }
+        env.storage().instance().set(&DataKey::JournalVersions, &VersionRange { min, max });
+        JournalVersionsUpdated { min, max }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_journal_versions(env: Env) -> VersionRange {
+        journal_versions(&env)
+    }
+
+    /// Publishes the seed every daily-challenge run on `day` (see `current_day`) must use.
+    #[only_owner]
+    pub fn set_daily_seed(env: Env, day: u32, seed: u64) -> Result<(), Error> {
+        let key = DataKey::DailySeed(day);
+        if env.storage().persistent().has(&key) {
+            return Err(Error::DailySeedExists);
+        }
+        write_persistent(&env, &key, &seed);
+        DailySeedPublished { day, seed }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_daily_seed(env: Env, day: u32) -> Option<u64> {
+        read_persistent(&env, &DataKey::DailySeed(day))
+    }
+
+    /// The day `start_daily_game` plays, in UTC days since the Unix epoch.
+    pub fn current_day(env: Env) -> u32 {
+        today(&env)
+    }
+
+    /// Starts a ranked session. The run must use the seed drawn for it, which `get_session`
+    /// returns.
     pub fn start_game(
         env: Env,
         session_id: u32,
         player: Address,
     ) -> Result<(), Error> {
+        Self::start(env, session_id, player, None, false)
+    }
+
+    /// Starts an unranked practice session, which the game hub never hears about and
+    /// whose score stays off the leaderboards.
+    pub fn start_practice_game(env: Env, session_id: u32, player: Address) -> Result<(), Error> {
+        Self::start(env, session_id, player, None, true)
+    }
+
+    /// Starts a daily-challenge session: the run must use today's seed, and its score also
+    /// goes on today's leaderboard.
+    pub fn start_daily_game(env: Env, session_id: u32, player: Address) -> Result<(), Error> {
+        let day = today(&env);
+        let seed = read_persistent(&env, &DataKey::DailySeed(day)).ok_or(Error::NoDailySeed)?;
+        Self::start(env, session_id, player, Some((day, seed)), false)
+    }
+
+    fn start(env: Env, session_id: u32, player: Address, daily: Option<(u32, u64)>, practice: bool) -> Result<(), Error> {
         player.require_auth();
 
         let game_hub: Address = env
@@ -79,27 +832,43 @@ impl LaneRacerContract {
         }
 
         // Call game hub start_game
-        env.invoke_contract::<()>(
-            &game_hub,
-            &soroban_sdk::Symbol::new(&env, "start_game"),
-            soroban_sdk::vec![
-                &env,
-                soroban_sdk::IntoVal::into_val(&env.current_contract_address(), &env),
-                soroban_sdk::IntoVal::into_val(&session_id, &env),
-                soroban_sdk::IntoVal::into_val(&player, &env),
-                soroban_sdk::IntoVal::into_val(&player, &env),
-                soroban_sdk::IntoVal::into_val(&1000i128, &env),
-                soroban_sdk::IntoVal::into_val(&1000i128, &env),
-            ],
-        );
-
-        let session = GameSession {
+        if !practice {
+            env.invoke_contract::<()>(
+                &game_hub,
+                &soroban_sdk::Symbol::new(&env, "start_game"),
+                soroban_sdk::vec![
+                    &env,
+                    soroban_sdk::IntoVal::into_val(&env.current_contract_address(), &env),
+                    soroban_sdk::IntoVal::into_val(&session_id, &env),
+                    soroban_sdk::IntoVal::into_val(&player, &env),
+                    soroban_sdk::IntoVal::into_val(&player, &env),
+                    soroban_sdk::IntoVal::into_val(&1000i128, &env),
+                    soroban_sdk::IntoVal::into_val(&1000i128, &env),
+                ],
+            );
+        }
+
+        let mut session = GameSession {
             session_id,
             player,
             score: 0,
-            active: true,
+            status: SessionStatus::Active,
+            image_id: None,
+            seed: None,
+            daily_day: daily.map(|(day, _)| day),
+            practice,
+            started_at: env.ledger().sequence(),
+            status_page: 0,
+            commit_deadline: None,
         };
-        env.storage().instance().set(&session_key, &session);
+        if !practice {
+            // Drawn from the network's randomness when the transaction runs, so the player can
+            // neither pick the seed nor know it before the session exists
+            session.seed = Some(daily.map_or_else(|| env.prng().gen(), |(_, seed)| seed));
+            let deadline: Option<u32> = env.storage().instance().get(&DataKey::CommitDeadline);
+            session.commit_deadline = deadline.filter(|deadline| *deadline >= session.started_at);
+        }
+        set_status(&env, &mut session, SessionStatus::Active, true);
         Ok(())
     }
 
@@ -108,8 +877,86 @@ impl LaneRacerContract {
         session_id: u32,
         player: Address,
         score: u32,
-        _proof: ZKProof,
+        proof: ZKProof,
+    ) -> Result<(), Error> {
+        Self::submit(env, session_id, player, score, proof, false)
+    }
+
+    /// Sets the last ledger of a commit phase, or clears it with `None`. Ranked sessions started
+    /// up to `deadline` commit to their run with `commit_score` until then and reveal it with
+    /// `reveal_score` afterwards, so no one sees the scores before deciding to submit. Sessions
+    /// keep the phase they were started in; those started later submit as usual.
+    #[only_owner]
+    pub fn set_commit_deadline(env: Env, deadline: Option<u32>) -> Result<(), Error> {
+        match deadline {
+            Some(deadline) => env.storage().instance().set(&DataKey::CommitDeadline, &deadline),
+            None => env.storage().instance().remove(&DataKey::CommitDeadline),
+        }
+        Ok(())
+    }
+
+    pub fn get_commit_deadline(env: Env) -> Option<u32> {
+        env.storage().instance().get(&DataKey::CommitDeadline)
+    }
+
+    /// Requires scores within `window.ledgers` of their session's start, with at most
+    /// `window.max_ticks` played ticks, or lifts the limit with `None`.
+    #[only_owner]
+    pub fn set_submission_window(env: Env, window: Option<SubmissionWindow>) -> Result<(), Error> {
+        match window {
+            Some(window) => env.storage().instance().set(&DataKey::SubmissionWindow, &window),
+            None => env.storage().instance().remove(&DataKey::SubmissionWindow),
+        }
+        Ok(())
+    }
+
+    pub fn get_submission_window(env: Env) -> Option<SubmissionWindow> {
+        submission_window(&env)
+    }
+
+    /// Commits to the journal of an active session's run, as `sha256(journal || salt)`.
+    /// Committing again before the deadline replaces the commitment.
+    pub fn commit_score(env: Env, session_id: u32, player: Address, commitment: BytesN<32>) -> Result<(), Error> {
+        player.require_auth();
+        let session = session_in(&env, session_id, &[SessionStatus::Active])?;
+        if session.player != player {
+            return Err(Error::NotAuthorized);
+        }
+        let deadline = session.commit_deadline.ok_or(Error::NoCommitPhase)?;
+        if env.ledger().sequence() > deadline {
+            return Err(Error::CommitPhaseClosed);
+        }
+        write_persistent(&env, &DataKey::Commitment(session_id), &commitment);
+        ScoreCommitted { session_id, player, commitment }.publish(&env);
+        Ok(())
+    }
+
+    /// Submits a committed run once the session's commit phase is over, within the submission
+    /// window. The proof's journal and `salt` must hash to the session's commitment.
+    pub fn reveal_score(
+        env: Env,
+        session_id: u32,
+        player: Address,
+        score: u32,
+        proof: ZKProof,
+        salt: BytesN<32>,
     ) -> Result<(), Error> {
+        let session = session_in(&env, session_id, &[SessionStatus::Active])?;
+        let deadline = session.commit_deadline.ok_or(Error::NoCommitPhase)?;
+        if env.ledger().sequence() <= deadline {
+            return Err(Error::RevealNotOpen);
+        }
+        let key = DataKey::Commitment(session_id);
+        let committed: BytesN<32> = read_persistent(&env, &key).ok_or(Error::CommitmentMismatch)?;
+        if commitment(&env, &proof.journal, &salt) != committed {
+            return Err(Error::CommitmentMismatch);
+        }
+        Self::submit(env.clone(), session_id, player, score, proof, true)?;
+        env.storage().persistent().remove(&key);
+        Ok(())
+    }
+
+    fn submit(env: Env, session_id: u32, player: Address, score: u32, proof: ZKProof, revealed: bool) -> Result<(), Error> {
         player.require_auth();
 
         let session_key = DataKey::GameSession(session_id);
@@ -122,28 +969,62 @@ impl LaneRacerContract {
         if session.player != player {
             return Err(Error::NotAuthorized);
         }
+        if session.status != SessionStatus::Active {
+            return Err(Error::SessionNotActive);
+        }
+        if !revealed && session.commit_deadline.is_some() {
+            return Err(Error::CommitRequired);
+        }
+        check_submission_deadline(&env, &session)?;
 
-        let game_hub: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::GameHub)
-            .ok_or(Error::NotInitialized)?;
+        let image_id = verify_proof(&env, &proof)?;
+        let journal = GameJournal::decode(&proof.journal).map_err(|_| Error::InvalidJournal)?;
+        let versions = journal_versions(&env);
+        if !(versions.min..=versions.max).contains(&journal.sim_version) {
+            return Err(Error::UnsupportedJournalVersion);
+        }
+        let player_key = match player.to_payload() {
+            Some(AddressPayload::AccountIdPublicKeyEd25519(key)) => key,
+            _ => return Err(Error::JournalMismatch),
+        };
+        let contract_id = match env.current_contract_address().to_payload() {
+            Some(AddressPayload::ContractIdHash(hash)) => hash,
+            _ => return Err(Error::JournalMismatch),
+        };
+        if journal.player != player_key
+            || journal.session_id != session_id
+            || journal.contract_id != contract_id
+            || journal.score != score
+            || session.seed.is_some_and(|seed| journal.seed != seed)
+        {
+            return Err(Error::JournalMismatch);
+        }
+        if journal.empty_trace && !session.practice {
+            return Err(Error::EmptyRun);
+        }
+        // Practice runs may try out any rules; ranked ones compete under the season's
+        let rules = season_rules(&env, current_season(&env));
+        if !session.practice && (journal.config_hash != rules.config_hash || journal.difficulty != rules.difficulty) {
+            return Err(Error::WrongRules);
+        }
+        if submission_window(&env).is_some_and(|window| journal.ticks > window.max_ticks) {
+            return Err(Error::RunTooLong);
+        }
 
-        // Call game hub end_game
-        env.invoke_contract::<()>(
-            &game_hub,
-            &soroban_sdk::symbol_short!("end_game"),
-            soroban_sdk::vec![
-                &env,
-                soroban_sdk::IntoVal::into_val(&session_id, &env),
-                soroban_sdk::IntoVal::into_val(&true, &env),
-            ],
-        );
+        if !session.practice {
+            end_game(&env, session_id, true)?;
+        }
 
         // Update session
         session.score = score;
-        session.active = false;
-        env.storage().instance().set(&session_key, &session);
+        session.image_id = Some(image_id.clone());
+        set_status(&env, &mut session, SessionStatus::Completed, false);
+
+        // Practice scores stay off the leaderboards
+        if session.practice {
+            ScoreSubmitted { session_id, player, score, image_id }.publish(&env);
+            return Ok(());
+        }
 
         // Update leaderboard
         let mut leaderboard: Vec<ScoreEntry> = env
@@ -152,8 +1033,23 @@ impl LaneRacerContract {
             .get(&DataKey::Leaderboard)
             .unwrap_or(Vec::new(&env));
 
-        leaderboard.push_back(ScoreEntry { player, score });
+        let entry = ScoreEntry { player: player.clone(), score, image_id: image_id.clone() };
+        leaderboard.push_back(entry.clone());
         env.storage().instance().set(&DataKey::Leaderboard, &leaderboard);
+        record_best(&env, &entry);
+
+        if is_eligible(&env, &player) {
+            record_eligible(&env, &entry);
+        }
+
+        if let Some(day) = session.daily_day {
+            let key = DataKey::DailyLeaderboard(day);
+            let mut daily: Vec<ScoreEntry> = read_persistent(&env, &key).unwrap_or(Vec::new(&env));
+            rank(&mut daily, entry);
+            write_persistent(&env, &key, &daily);
+        }
+
+        ScoreSubmitted { session_id, player, score, image_id }.publish(&env);
 
         Ok(())
     }
@@ -165,7 +1061,362 @@ impl LaneRacerContract {
             .unwrap_or(Vec::new(&env))
     }
 
+    /// Best `RANKING_SIZE` players of the season, one entry each, highest score first.
+    pub fn get_ranking(env: Env) -> Vec<ScoreEntry> {
+        env.storage().instance().get(&DataKey::Ranking).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_season(env: Env) -> u32 {
+        current_season(&env)
+    }
+
+    pub fn get_player_best(env: Env, season: u32, player: Address) -> Option<ScoreEntry> {
+        read_persistent(&env, &DataKey::PlayerBest(season, player))
+    }
+
+    /// Sets the rules ranked runs of `season` must be played under, e.g. an event's variant,
+    /// or restores the official ones with `None`. Scores already recorded stay.
+    #[only_owner]
+    pub fn set_season_rules(env: Env, season: u32, rules: Option<Rules>) -> Result<(), Error> {
+        let key = DataKey::SeasonRules(season);
+        match rules {
+            Some(rules) => write_persistent(&env, &key, &rules),
+            None => env.storage().persistent().remove(&key),
+        }
+        Ok(())
+    }
+
+    pub fn get_season_rules(env: Env, season: u32) -> Rules {
+        season_rules(&env, season)
+    }
+
+    /// Starts the next season with an empty ranking. Bests of past seasons stay stored.
+    #[only_owner]
+    pub fn start_season(env: Env) -> Result<u32, Error> {
+        let season = current_season(&env) + 1;
+        env.storage().instance().set(&DataKey::Season, &season);
+        env.storage().instance().remove(&DataKey::Ranking);
+        SeasonStarted { season }.publish(&env);
+        Ok(season)
+    }
+
+    /// Rebuilds the current season's ranking from the stored per-player bests, e.g. after a
+    /// storage migration or an invalidation sweep left it wrong, one page of players (see
+    /// `get_season_pages`) per call. Page 0 starts over from an empty ranking, so call it for
+    /// pages 0, 1, ... in turn. `season` must be the current one, which keeps a rebuild from
+    /// straddling `start_season`. Returns how many players are on the ranking.
+    #[only_owner]
+    pub fn rebuild_leaderboard(env: Env, season: u32, page: u32) -> Result<u32, Error> {
+        if season != current_season(&env) {
+            return Err(Error::InvalidSeason);
+        }
+        let mut ranking = if page == 0 {
+            Vec::new(&env)
+        } else {
+            env.storage().instance().get(&DataKey::Ranking).unwrap_or(Vec::new(&env))
+        };
+        let players: Vec<Address> =
+            read_persistent(&env, &DataKey::SeasonPlayers(season, page)).unwrap_or(Vec::new(&env));
+        for player in players.iter() {
+            if let Some(best) = read_persistent(&env, &DataKey::PlayerBest(season, player)) {
+                rank(&mut ranking, best);
+            }
+        }
+        env.storage().instance().set(&DataKey::Ranking, &ranking);
+        let players = ranking.len();
+        LeaderboardRebuilt { season, page, players }.publish(&env);
+        Ok(players)
+    }
+
+    /// Number of pages of players with a best score in `season`.
+    pub fn get_season_pages(env: Env, season: u32) -> u32 {
+        let count: u32 = read_persistent(&env, &DataKey::SeasonPlayerCount(season)).unwrap_or(0);
+        count.div_ceil(SEASON_PAGE_SIZE)
+    }
+
+    /// Best `RANKING_SIZE` players who met the entry requirement when they submitted, one
+    /// entry each, highest score first, i.e. the ones prizes are paid from.
+    pub fn get_eligible_leaderboard(env: Env) -> Vec<ScoreEntry> {
+        env.storage().instance().get(&DataKey::EligibleLeaderboard).unwrap_or(Vec::new(&env))
+    }
+
+    /// Sets what players need before their scores reach the eligible leaderboard. Scores
+    /// already on it stay.
+    #[only_owner]
+    pub fn set_entry_requirement(env: Env, requirement: EntryRequirement) -> Result<(), Error> {
+        env.storage().instance().set(&DataKey::EntryRequirement, &requirement);
+        EntryRequirementUpdated { requirement }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_entry_requirement(env: Env) -> EntryRequirement {
+        entry_requirement(&env)
+    }
+
+    #[only_owner]
+    pub fn set_allowed(env: Env, player: Address, allowed: bool) -> Result<(), Error> {
+        let key = DataKey::Allowed(player.clone());
+        if allowed {
+            write_persistent(&env, &key, &());
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        AllowlistUpdated { player, allowed }.publish(&env);
+        Ok(())
+    }
+
+    pub fn is_eligible(env: Env, player: Address) -> bool {
+        is_eligible(&env, &player)
+    }
+
+    /// Tops the player's stake up to the required amount.
+    pub fn stake(env: Env, player: Address) -> Result<(), Error> {
+        player.require_auth();
+        let EntryRequirement::Stake(required) = entry_requirement(&env) else {
+            return Err(Error::NoStakeRequired);
+        };
+        let key = DataKey::Stake(player.clone());
+        let mut stake = read_persistent(&env, &key).unwrap_or(Stake { token: required.token.clone(), amount: 0 });
+        if stake.token != required.token {
+            return Err(Error::StakeMismatch);
+        }
+        if stake.amount < required.amount {
+            let top_up = required.amount - stake.amount;
+            token::Client::new(&env, &required.token).transfer(&player, env.current_contract_address(), &top_up);
+            add_to_total(&env, &DataKey::TotalStaked(required.token.clone()), top_up);
+            stake.amount = required.amount;
+            write_persistent(&env, &key, &stake);
+            StakeUpdated { player, token: stake.token, amount: stake.amount }.publish(&env);
+        }
+        Ok(())
+    }
+
+    /// Returns the player's whole stake, once the admin has unlocked stakes.
+    pub fn unstake(env: Env, player: Address) -> Result<(), Error> {
+        player.require_auth();
+        if !env.storage().instance().get(&DataKey::StakesUnlocked).unwrap_or(false) {
+            return Err(Error::StakeLocked);
+        }
+        let key = DataKey::Stake(player.clone());
+        let stake: Stake = read_persistent(&env, &key).ok_or(Error::NothingStaked)?;
+        env.storage().persistent().remove(&key);
+        add_to_total(&env, &DataKey::TotalStaked(stake.token.clone()), -stake.amount);
+        token::Client::new(&env, &stake.token).transfer(&env.current_contract_address(), &player, &stake.amount);
+        StakeUpdated { player, token: stake.token, amount: 0 }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_stake(env: Env, player: Address) -> Option<Stake> {
+        read_persistent(&env, &DataKey::Stake(player))
+    }
+
+    /// Lets players withdraw their stakes (at the end of a season) or locks them again.
+    #[only_owner]
+    pub fn set_stakes_unlocked(env: Env, unlocked: bool) -> Result<(), Error> {
+        env.storage().instance().set(&DataKey::StakesUnlocked, &unlocked);
+        Ok(())
+    }
+
+    /// Sets how large prizes vest, or pays every prize at once with `None`. Prizes already
+    /// vesting keep their schedule.
+    #[only_owner]
+    pub fn set_vesting_policy(env: Env, policy: Option<VestingPolicy>) -> Result<(), Error> {
+        match policy {
+            Some(policy) if policy.ledgers == 0 || policy.threshold < 0 => return Err(Error::InvalidVestingPolicy),
+            Some(policy) => env.storage().instance().set(&DataKey::VestingPolicy, &policy),
+            None => env.storage().instance().remove(&DataKey::VestingPolicy),
+        }
+        Ok(())
+    }
+
+    pub fn get_vesting_policy(env: Env) -> Option<VestingPolicy> {
+        env.storage().instance().get(&DataKey::VestingPolicy)
+    }
+
+    /// Pays `player` a prize from the contract's balance of `token`, at once or, above the
+    /// vesting threshold, linearly through `claim_vested`. Stakes and prizes still vesting
+    /// are not available for prizes.
+    #[only_owner]
+    pub fn award_prize(env: Env, player: Address, token: Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidPrize);
+        }
+        if amount > free_balance(&env, &token) {
+            return Err(Error::InsufficientPrizeFunds);
+        }
+        let policy: Option<VestingPolicy> = env.storage().instance().get(&DataKey::VestingPolicy);
+        let vesting_ledgers = match policy {
+            Some(policy) if amount > policy.threshold => {
+                let key = DataKey::Vesting(player.clone());
+                let mut schedules: Vec<Vesting> = read_persistent(&env, &key).unwrap_or(Vec::new(&env));
+                schedules.push_back(Vesting {
+                    token: token.clone(),
+                    amount,
+                    claimed: 0,
+                    start: env.ledger().sequence(),
+                    ledgers: policy.ledgers,
+                });
+                write_persistent(&env, &key, &schedules);
+                add_to_total(&env, &DataKey::TotalUnvested(token.clone()), amount);
+                policy.ledgers
+            }
+            _ => {
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &player, &amount);
+                0
+            }
+        };
+        PrizeAwarded { player, token, amount, vesting_ledgers }.publish(&env);
+        Ok(())
+    }
+
+    /// Pays out what has vested of the player's prizes since their last claim.
+    pub fn claim_vested(env: Env, player: Address) -> Result<(), Error> {
+        player.require_auth();
+        let key = DataKey::Vesting(player.clone());
+        let schedules: Vec<Vesting> = read_persistent(&env, &key).ok_or(Error::NothingToClaim)?;
+        let ledger = env.ledger().sequence();
+        let mut remaining = Vec::new(&env);
+        let mut claimed_any = false;
+        for mut vesting in schedules.iter() {
+            let unlocked = vested(&vesting, ledger);
+            let due = unlocked - vesting.claimed;
+            if due > 0 {
+                token::Client::new(&env, &vesting.token).transfer(&env.current_contract_address(), &player, &due);
+                add_to_total(&env, &DataKey::TotalUnvested(vesting.token.clone()), -due);
+                VestedClaimed { player: player.clone(), token: vesting.token.clone(), amount: due }.publish(&env);
+                vesting.claimed = unlocked;
+                claimed_any = true;
+            }
+            if vesting.claimed < vesting.amount {
+                remaining.push_back(vesting);
+            }
+        }
+        if !claimed_any {
+            return Err(Error::NothingToClaim);
+        }
+        if remaining.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            write_persistent(&env, &key, &remaining);
+        }
+        Ok(())
+    }
+
+    /// Prizes still vesting for the player.
+    pub fn get_vesting(env: Env, player: Address) -> Vec<Vesting> {
+        read_persistent(&env, &DataKey::Vesting(player)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Stops the player's vesting prizes, e.g. after finding the scores that won them were
+    /// exploited. Whatever was not claimed yet stays with the contract, free for other prizes.
+    #[only_owner]
+    pub fn cancel_vesting(env: Env, player: Address) -> Result<(), Error> {
+        let key = DataKey::Vesting(player.clone());
+        let schedules: Vec<Vesting> = read_persistent(&env, &key).unwrap_or(Vec::new(&env));
+        for vesting in schedules.iter() {
+            add_to_total(&env, &DataKey::TotalUnvested(vesting.token), vesting.claimed - vesting.amount);
+        }
+        env.storage().persistent().remove(&key);
+        VestingCancelled { player }.publish(&env);
+        Ok(())
+    }
+
+    /// Best `RANKING_SIZE` daily-challenge players of `day`, one entry each, highest score first.
+    pub fn get_daily_leaderboard(env: Env, day: u32) -> Vec<ScoreEntry> {
+        read_persistent(&env, &DataKey::DailyLeaderboard(day)).unwrap_or(Vec::new(&env))
+    }
+
     pub fn get_session(env: Env, session_id: u32) -> Option<GameSession> {
         env.storage().instance().get(&DataKey::GameSession(session_id))
     }
-}
\ No newline at end of file
+
+    /// Closes an active session that will never get a score, ending it as a loss on the hub
+    /// unless it is a practice session.
+    #[only_owner]
+    pub fn expire_session(env: Env, session_id: u32) -> Result<(), Error> {
+        let mut session = session_in(&env, session_id, &[SessionStatus::Active])?;
+        if !session.practice {
+            end_game(&env, session_id, false)?;
+        }
+        set_status(&env, &mut session, SessionStatus::Expired, false);
+        Ok(())
+    }
+
+    /// Flags an active or completed session for review.
+    #[only_owner]
+    pub fn dispute_session(env: Env, session_id: u32) -> Result<(), Error> {
+        let mut session =
+            session_in(&env, session_id, &[SessionStatus::Active, SessionStatus::Completed])?;
+        set_status(&env, &mut session, SessionStatus::Disputed, false);
+        Ok(())
+    }
+
+    /// Closes a review: the session becomes completed if it has a score and expired if not.
+    #[only_owner]
+    pub fn resolve_dispute(env: Env, session_id: u32) -> Result<(), Error> {
+        let mut session = session_in(&env, session_id, &[SessionStatus::Disputed])?;
+        if session.image_id.is_some() {
+            set_status(&env, &mut session, SessionStatus::Completed, false);
+        } else {
+            if !session.practice {
+                end_game(&env, session_id, false)?;
+            }
+            set_status(&env, &mut session, SessionStatus::Expired, false);
+        }
+        Ok(())
+    }
+
+    /// Reports a session whose settlement failed to the game hub again, with the same result.
+    #[only_owner]
+    pub fn retry_settlement(env: Env, session_id: u32) -> Result<Settlement, Error> {
+        let settlement: Settlement = read_persistent(&env, &DataKey::Settlement(session_id))
+            .filter(|settlement: &Settlement| settlement.status == SettlementStatus::Failed)
+            .ok_or(Error::NothingToSettle)?;
+        end_game(&env, session_id, settlement.player_won)
+    }
+
+    /// How the session's result was settled with the game hub, once it has ended.
+    pub fn get_settlement(env: Env, session_id: u32) -> Option<Settlement> {
+        read_persistent(&env, &DataKey::Settlement(session_id))
+    }
+
+    /// IDs of up to `limit` sessions in `status`, oldest first, skipping the first `offset`.
+    /// `limit` is capped at `MAX_SESSIONS_PER_QUERY`; `get_session_count` tells how many
+    /// sessions there are.
+    pub fn get_sessions_by_status(env: Env, status: SessionStatus, offset: u32, limit: u32) -> Result<Vec<u32>, Error> {
+        if status == SessionStatus::Completed {
+            return Err(Error::StatusNotIndexed);
+        }
+        let limit = limit.min(MAX_SESSIONS_PER_QUERY);
+        let slots: u32 = env.storage().instance().get(&DataKey::StatusSlots(status)).unwrap_or(0);
+        let mut ids = Vec::new(&env);
+        let mut skip = offset;
+        for page in status_head(&env, status)..slots.div_ceil(STATUS_PAGE_SIZE) {
+            if ids.len() == limit {
+                break;
+            }
+            let on_page: Vec<u32> =
+                read_persistent(&env, &DataKey::SessionsByStatus(status, page)).unwrap_or(Vec::new(&env));
+            if skip >= on_page.len() {
+                skip -= on_page.len();
+                continue;
+            }
+            for id in on_page.slice(skip..).iter().take((limit - ids.len()) as usize) {
+                ids.push_back(id);
+            }
+            skip = 0;
+        }
+        Ok(ids)
+    }
+
+    pub fn get_session_count(env: Env, status: SessionStatus) -> u32 {
+        env.storage().instance().get(&DataKey::SessionCount(status)).unwrap_or(0)
+    }
+
+}
+
+#[contractimpl(contracttrait)]
+impl Ownable for LaneRacerContract {}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,888 @@
+extern crate std;
+
+use lane_racer_journal::{Difficulty, JOURNAL_LEN, MAX_SIM_VERSION, MIN_SIM_VERSION, OFFICIAL_CONFIG_HASH};
+use risc0_interface::VerifierError;
+use soroban_sdk::{
+    address_payload::AddressPayload,
+    contract, contractimpl, symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger as _},
+    Address, Bytes, BytesN, Env, Event, String,
+};
+use std::vec::Vec;
+
+use crate::{
+    AllowlistUpdated, DailySeedPublished, DataKey, EntryRequirement, Error, ImageIdUpdated, JournalVersionsUpdated,
+    LaneRacerContract, LaneRacerContractClient, LeaderboardRebuilt, ScoreCommitted, ScoreEntry, ScoreSubmitted,
+    Rules, SessionStatus, SessionStatusChanged, Settlement, SettlementRecorded, SettlementStatus, Stake, StakeRequirement,
+    StakeUpdated, SubmissionWindow, VersionRange, VestedClaimed, Vesting, VestingPolicy, ZKProof,
+};
+
+const PLAYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+const SESSION_ID: u32 = 7;
+
+/// Journal of the run in `lane-racer-journal/fixtures/trace.txt`, for `PLAYER`.
+const FIXTURE_JOURNAL: &str = include_str!("../../lane-racer-journal/fixtures/journal.hex");
+
+const VERSION_AT: usize = 0;
+const SESSION_AT: usize = 44;
+const CONTRACT_AT: usize = 48;
+const SEED_AT: usize = 80;
+const FIXTURE_SEED: u64 = 42;
+const CONFIG_AT: usize = 120;
+const SCORE_AT: usize = 152;
+const DIFFICULTY_AT: usize = 189;
+const FLAGS_AT: usize = 190;
+const FLAG_EMPTY_TRACE: u8 = 2;
+const TICKS_AT: usize = 199;
+
+/// Accepts the game hub calls lane-racer makes, or fails `end_game` once told to.
+#[contract]
+struct StubGameHub;
+
+#[contractimpl]
+impl StubGameHub {
+    pub fn start_game(
+        _env: Env,
+        _game_id: Address,
+        _session_id: u32,
+        _player1: Address,
+        _player2: Address,
+        _player1_points: i128,
+        _player2_points: i128,
+    ) {
+    }
+
+    pub fn end_game(env: Env, _session_id: u32, _player1_won: bool) {
+        if env.storage().instance().get(&symbol_short!("failing")).unwrap_or(false) {
+            panic!("hub is down");
+        }
+    }
+
+    pub fn set_failing(env: Env, failing: bool) {
+        env.storage().instance().set(&symbol_short!("failing"), &failing);
+    }
+}
+
+/// Accepts every non-empty seal, so tests can focus on the journal checks.
+#[contract]
+struct StubVerifier;
+
+#[contractimpl]
+impl StubVerifier {
+    pub fn verify(_env: Env, seal: Bytes, _image_id: BytesN<32>, _journal: BytesN<32>) -> Result<(), VerifierError> {
+        if seal.is_empty() {
+            return Err(VerifierError::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+/// Just enough of a token for stakes: balances, minting and transfers.
+#[contract]
+struct StubToken;
+
+#[contractimpl]
+impl StubToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let balance = Self::balance(env.clone(), from.clone());
+        assert!(balance >= amount, "insufficient balance");
+        env.storage().instance().set(&from, &(balance - amount));
+        Self::mint(env, to, amount);
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    game: LaneRacerContractClient<'a>,
+    hub: StubGameHubClient<'a>,
+    player: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let hub_id = env.register(StubGameHub, ());
+    let verifier_id = env.register(StubVerifier, ());
+    let game_id = env.register(LaneRacerContract, (&admin, &hub_id, &verifier_id, image_id(&env, 1)));
+    let game = LaneRacerContractClient::new(&env, &game_id);
+    let player = Address::from_string(&String::from_str(&env, PLAYER));
+    let hub = StubGameHubClient::new(&env, &hub_id);
+    Setup { env, game, hub, player }
+}
+
+fn image_id(env: &Env, n: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[n; 32])
+}
+
+/// The fixture journal, re-targeted at `session_id` on `game` with `score`, using the
+/// session's seed if it has one, at normal difficulty as the official rules require.
+fn journal(game: &Address, session_id: u32, score: u32) -> Vec<u8> {
+    let hex = FIXTURE_JOURNAL.trim();
+    let mut bytes: Vec<u8> =
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+    assert_eq!(bytes.len() as u32, JOURNAL_LEN);
+    let Some(AddressPayload::ContractIdHash(contract_id)) = game.to_payload() else {
+        unreachable!("contracts have contract addresses")
+    };
+    bytes[SESSION_AT..SESSION_AT + 4].copy_from_slice(&session_id.to_le_bytes());
+    bytes[CONTRACT_AT..CONTRACT_AT + 32].copy_from_slice(&contract_id.to_array());
+    bytes[SCORE_AT..SCORE_AT + 4].copy_from_slice(&score.to_le_bytes());
+    bytes[DIFFICULTY_AT] = Difficulty::Normal as u8;
+    if let Ok(Ok(Some(session))) = LaneRacerContractClient::new(game.env(), game).try_get_session(&session_id) {
+        bytes = with_seed(bytes, session.seed.unwrap_or(FIXTURE_SEED));
+    }
+    bytes
+}
+
+fn with_rules(mut journal: Vec<u8>, config_hash: [u8; 32], difficulty: Difficulty) -> Vec<u8> {
+    journal[CONFIG_AT..CONFIG_AT + 32].copy_from_slice(&config_hash);
+    journal[DIFFICULTY_AT] = difficulty as u8;
+    journal
+}
+
+fn with_seed(mut journal: Vec<u8>, seed: u64) -> Vec<u8> {
+    journal[SEED_AT..SEED_AT + 8].copy_from_slice(&seed.to_le_bytes());
+    journal
+}
+
+fn proof(env: &Env, journal: &[u8]) -> ZKProof {
+    ZKProof { seal: Bytes::from_array(env, &[0xff; 4]), journal: Bytes::from_slice(env, journal) }
+}
+
+/// Starts `session_id` and submits a proven `score` for it.
+fn play(setup: &Setup, session_id: u32, score: u32) {
+    let Setup { env, game, player, .. } = setup;
+    game.start_game(&session_id, player);
+    game.submit_score(&session_id, player, &score, &proof(env, &journal(&game.address, session_id, score)));
+}
+
+#[test]
+fn test_submit_score_records_the_session_and_leaderboard() {
+    let setup = setup();
+    play(&setup, SESSION_ID, 109);
+
+    let session = setup.game.get_session(&SESSION_ID).unwrap();
+    assert_eq!(session.score, 109);
+    assert_eq!(session.status, SessionStatus::Completed);
+    let leaderboard = setup.game.get_leaderboard();
+    assert_eq!(leaderboard.len(), 1);
+    assert_eq!(leaderboard.get(0).unwrap().player, setup.player);
+    assert_eq!(leaderboard.get(0).unwrap().score, 109);
+}
+
+#[test]
+fn test_submit_score_rejects_a_journal_for_another_run() {
+    let Setup { env, game, player, .. } = setup();
+    game.start_game(&SESSION_ID, &player);
+
+    let seed = game.get_session(&SESSION_ID).unwrap().seed.unwrap();
+    let mismatched = [
+        journal(&game.address, SESSION_ID + 1, 109),
+        journal(&game.address, SESSION_ID, 110),
+        journal(&Address::generate(&env), SESSION_ID, 109),
+        with_seed(journal(&game.address, SESSION_ID, 109), seed ^ 1),
+    ];
+    for bytes in mismatched {
+        assert_eq!(
+            game.try_submit_score(&SESSION_ID, &player, &109, &proof(&env, &bytes)),
+            Err(Ok(Error::JournalMismatch))
+        );
+    }
+    let unproven = ZKProof { seal: Bytes::new(&env), ..proof(&env, &journal(&game.address, SESSION_ID, 109)) };
+    assert_eq!(game.try_submit_score(&SESSION_ID, &player, &109, &unproven), Err(Ok(Error::InvalidProof)));
+    assert_eq!(
+        game.try_submit_score(&SESSION_ID, &player, &109, &proof(&env, &[0; 4])),
+        Err(Ok(Error::InvalidJournal))
+    );
+}
+
+#[test]
+fn test_ranked_sessions_draw_the_seed_their_run_must_use() {
+    let Setup { env, game, player, .. } = setup();
+    game.start_game(&1, &player);
+    game.start_game(&2, &player);
+    game.start_practice_game(&3, &player);
+    let seed = |session_id| game.get_session(&session_id).unwrap().seed;
+    assert!(seed(1).is_some() && seed(2).is_some());
+    assert_ne!(seed(1), seed(2));
+    assert_eq!(seed(3), None);
+
+    // A run played on a seed of the player's choosing does not count
+    let chosen = proof(&env, &with_seed(journal(&game.address, 1, 109), FIXTURE_SEED));
+    assert_eq!(game.try_submit_score(&1, &player, &109, &chosen), Err(Ok(Error::JournalMismatch)));
+    game.submit_score(&1, &player, &109, &proof(&env, &journal(&game.address, 1, 109)));
+    // while practice runs pick their own
+    game.submit_score(&3, &player, &109, &proof(&env, &with_seed(journal(&game.address, 3, 109), 7)));
+}
+
+#[test]
+fn test_ranked_runs_must_follow_the_season_rules() {
+    let Setup { env, game, player, .. } = setup();
+    let official = Rules { config_hash: BytesN::from_array(&env, &OFFICIAL_CONFIG_HASH), difficulty: Difficulty::Normal };
+    assert_eq!(game.get_season_rules(&0), official);
+    let run = |session_id, config_hash, difficulty| {
+        proof(&env, &with_rules(journal(&game.address, session_id, 109), config_hash, difficulty))
+    };
+
+    // A foreign config or another difficulty does not rank
+    game.start_game(&1, &player);
+    for foreign in [run(1, [7; 32], Difficulty::Normal), run(1, OFFICIAL_CONFIG_HASH, Difficulty::Easy)] {
+        assert_eq!(game.try_submit_score(&1, &player, &109, &foreign), Err(Ok(Error::WrongRules)));
+    }
+    // but practice runs may use any rules
+    game.start_practice_game(&2, &player);
+    game.submit_score(&2, &player, &109, &run(2, [7; 32], Difficulty::Easy));
+
+    // An event season swaps in its own rules, which then replace the official ones
+    let event = Rules { config_hash: BytesN::from_array(&env, &[7; 32]), difficulty: Difficulty::Hard };
+    game.set_season_rules(&0, &Some(event.clone()));
+    assert_eq!(game.get_season_rules(&0), event);
+    assert_eq!(game.get_season_rules(&1), official);
+    assert_eq!(
+        game.try_submit_score(&1, &player, &109, &run(1, OFFICIAL_CONFIG_HASH, Difficulty::Normal)),
+        Err(Ok(Error::WrongRules))
+    );
+    game.submit_score(&1, &player, &109, &run(1, [7; 32], Difficulty::Hard));
+
+    game.set_season_rules(&0, &None);
+    assert_eq!(game.get_season_rules(&0), official);
+    game.start_game(&3, &player);
+    game.submit_score(&3, &player, &109, &run(3, OFFICIAL_CONFIG_HASH, Difficulty::Normal));
+    assert_eq!(game.get_leaderboard().len(), 2);
+}
+
+#[test]
+fn test_empty_runs_only_count_in_practice_sessions() {
+    let Setup { env, game, hub, player } = setup();
+    let empty = |session_id| {
+        let mut bytes = journal(&game.address, session_id, 0);
+        bytes[FLAGS_AT] = FLAG_EMPTY_TRACE;
+        proof(&env, &bytes)
+    };
+
+    game.start_game(&1, &player);
+    assert_eq!(game.try_submit_score(&1, &player, &0, &empty(1)), Err(Ok(Error::EmptyRun)));
+    assert_eq!(game.get_session(&1).unwrap().status, SessionStatus::Active);
+
+    // Practice sessions take the empty run, without settling or ranking it
+    hub.set_failing(&true);
+    game.start_practice_game(&2, &player);
+    game.submit_score(&2, &player, &0, &empty(2));
+    let session = game.get_session(&2).unwrap();
+    assert!(session.practice);
+    assert_eq!((session.status, session.score), (SessionStatus::Completed, 0));
+    assert!(game.get_settlement(&2).is_none());
+    assert_eq!(game.get_leaderboard().len(), 0);
+
+    // and played runs too, still off the leaderboards
+    game.start_practice_game(&3, &player);
+    game.submit_score(&3, &player, &109, &proof(&env, &journal(&game.address, 3, 109)));
+    assert_eq!(game.get_session(&3).unwrap().score, 109);
+    assert_eq!(game.get_leaderboard().len(), 0);
+    assert_eq!(game.try_start_practice_game(&3, &player), Err(Ok(Error::SessionExists)));
+}
+
+#[test]
+fn test_ranked_scores_are_committed_then_revealed_during_a_commit_phase() {
+    let Setup { env, game, player, .. } = setup();
+    let salt = BytesN::from_array(&env, &[9; 32]);
+    let commitment_of = |run: &ZKProof| -> BytesN<32> {
+        let mut preimage = run.journal.clone();
+        preimage.append(&Bytes::from_array(&env, &salt.to_array()));
+        env.crypto().sha256(&preimage).into()
+    };
+
+    game.start_game(&1, &player);
+    let early = commitment_of(&proof(&env, &journal(&game.address, 1, 109)));
+    assert_eq!(game.try_commit_score(&1, &player, &early), Err(Ok(Error::NoCommitPhase)));
+    let deadline = env.ledger().sequence() + 100;
+    game.set_commit_deadline(&Some(deadline));
+    assert_eq!(game.get_commit_deadline(), Some(deadline));
+    assert_eq!(game.try_commit_score(&SESSION_ID, &player, &early), Err(Ok(Error::SessionNotFound)));
+
+    // Sessions keep the phase they were started in
+    game.start_game(&SESSION_ID, &player);
+    let run = proof(&env, &journal(&game.address, SESSION_ID, 109));
+    let commitment = commitment_of(&run);
+    assert_eq!(game.get_session(&SESSION_ID).unwrap().commit_deadline, Some(deadline));
+    assert_eq!(game.try_commit_score(&1, &player, &early), Err(Ok(Error::NoCommitPhase)));
+    game.submit_score(&1, &player, &109, &proof(&env, &journal(&game.address, 1, 109)));
+    assert_eq!(game.try_submit_score(&SESSION_ID, &player, &109, &run), Err(Ok(Error::CommitRequired)));
+    game.commit_score(&SESSION_ID, &player, &commitment);
+    assert_eq!(
+        env.events().all().filter_by_contract(&game.address),
+        std::vec![ScoreCommitted { session_id: SESSION_ID, player: player.clone(), commitment: commitment.clone() }
+            .to_xdr(&env, &game.address)]
+    );
+    assert_eq!(game.try_reveal_score(&SESSION_ID, &player, &109, &run, &salt), Err(Ok(Error::RevealNotOpen)));
+
+    env.ledger().set_sequence_number(deadline + 1);
+    assert_eq!(game.try_commit_score(&SESSION_ID, &player, &commitment), Err(Ok(Error::CommitPhaseClosed)));
+    let other_salt = BytesN::from_array(&env, &[8; 32]);
+    assert_eq!(
+        game.try_reveal_score(&SESSION_ID, &player, &109, &run, &other_salt),
+        Err(Ok(Error::CommitmentMismatch))
+    );
+    game.reveal_score(&SESSION_ID, &player, &109, &run, &salt);
+    assert_eq!(game.get_session(&SESSION_ID).unwrap().status, SessionStatus::Completed);
+    assert_eq!(game.get_leaderboard().len(), 2);
+
+    // Practice sessions skip the commit phase, and ranked ones started after it submit as usual
+    game.start_practice_game(&2, &player);
+    game.submit_score(&2, &player, &109, &proof(&env, &journal(&game.address, 2, 109)));
+    game.start_game(&3, &player);
+    assert_eq!(game.get_session(&3).unwrap().commit_deadline, None);
+    game.submit_score(&3, &player, &109, &proof(&env, &journal(&game.address, 3, 109)));
+
+    game.set_commit_deadline(&None);
+    assert_eq!(game.get_commit_deadline(), None);
+}
+
+#[test]
+fn test_scores_must_arrive_within_the_submission_window() {
+    let Setup { env, game, player, .. } = setup();
+    let start = env.ledger().sequence();
+    game.set_submission_window(&Some(SubmissionWindow { ledgers: 100, max_ticks: 1_500 }));
+    game.start_game(&1, &player);
+    game.start_game(&2, &player);
+    assert_eq!(game.get_session(&1).unwrap().started_at, start);
+
+    // The fixture run is 1500 ticks long
+    let mut long = journal(&game.address, 1, 109);
+    long[TICKS_AT..TICKS_AT + 4].copy_from_slice(&1_501u32.to_le_bytes());
+    assert_eq!(game.try_submit_score(&1, &player, &109, &proof(&env, &long)), Err(Ok(Error::RunTooLong)));
+
+    env.ledger().set_sequence_number(start + 100);
+    game.submit_score(&1, &player, &109, &proof(&env, &journal(&game.address, 1, 109)));
+    env.ledger().set_sequence_number(start + 101);
+    assert_eq!(
+        game.try_submit_score(&2, &player, &109, &proof(&env, &journal(&game.address, 2, 109))),
+        Err(Ok(Error::SubmissionExpired))
+    );
+
+    game.set_submission_window(&None);
+    assert_eq!(game.get_submission_window(), None);
+    game.submit_score(&2, &player, &109, &proof(&env, &journal(&game.address, 2, 109)));
+}
+
+#[test]
+fn test_reveals_must_arrive_within_the_submission_window() {
+    let Setup { env, game, player, .. } = setup();
+    let salt = BytesN::from_array(&env, &[9; 32]);
+    let deadline = env.ledger().sequence() + 1_000;
+    game.set_commit_deadline(&Some(deadline));
+    game.set_submission_window(&Some(SubmissionWindow { ledgers: 100, max_ticks: 1_500 }));
+
+    let mut runs = Vec::new();
+    for session_id in [1, 2] {
+        game.start_game(&session_id, &player);
+        let run = proof(&env, &journal(&game.address, session_id, 109));
+        let mut preimage = run.journal.clone();
+        preimage.append(&Bytes::from_array(&env, &salt.to_array()));
+        // Committing is open the whole phase, however long it outlasts the window
+        env.ledger().set_sequence_number(deadline);
+        game.commit_score(&session_id, &player, &env.crypto().sha256(&preimage).into());
+        runs.push(run);
+    }
+
+    // The window opens with the reveals
+    env.ledger().set_sequence_number(deadline + 100);
+    game.reveal_score(&1, &player, &109, &runs[0], &salt);
+    env.ledger().set_sequence_number(deadline + 101);
+    assert_eq!(game.try_reveal_score(&2, &player, &109, &runs[1], &salt), Err(Ok(Error::SubmissionExpired)));
+}
+
+#[test]
+fn test_the_ranking_keeps_player_bests_and_can_be_rebuilt() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    let scores = |ranking: soroban_sdk::Vec<ScoreEntry>| ranking.iter().map(|e| e.score).collect::<Vec<_>>();
+
+    play(&setup, 1, 50);
+    play(&setup, 2, 30);
+    assert_eq!(scores(game.get_ranking()), [50]);
+    assert_eq!(game.get_player_best(&0, player).unwrap().score, 50);
+
+    assert_eq!(game.start_season(), 1);
+    assert_eq!(game.get_ranking().len(), 0);
+    play(&setup, 3, 40);
+    assert_eq!(scores(game.get_ranking()), [40]);
+
+    // The derived ranking gets corrupted, e.g. by a migration
+    env.as_contract(&game.address, || env.storage().instance().remove(&DataKey::Ranking));
+    assert_eq!(game.get_season_pages(&1), 1);
+    assert_eq!(game.rebuild_leaderboard(&1, &0), 1);
+    assert_eq!(
+        env.events().all().filter_by_contract(&game.address),
+        std::vec![LeaderboardRebuilt { season: 1, page: 0, players: 1 }.to_xdr(env, &game.address)]
+    );
+    assert_eq!(scores(game.get_ranking()), [40]);
+    // Past seasons' bests stay out of the current ranking
+    assert_eq!(game.try_rebuild_leaderboard(&0, &0), Err(Ok(Error::InvalidSeason)));
+    assert_eq!(game.try_rebuild_leaderboard(&2, &0), Err(Ok(Error::InvalidSeason)));
+    // Later pages add to the ranking instead of starting over
+    assert_eq!(game.rebuild_leaderboard(&1, &1), 1);
+    assert_eq!(scores(game.get_ranking()), [40]);
+}
+
+#[test]
+fn test_season_players_are_paged_for_rebuilds() {
+    let Setup { env, game, .. } = setup();
+    for player in 0..=crate::SEASON_PAGE_SIZE {
+        let best = ScoreEntry { player: Address::generate(&env), score: player, image_id: image_id(&env, 1) };
+        env.as_contract(&game.address, || crate::record_best(&env, &best));
+    }
+    assert_eq!(game.get_season_pages(&0), 2);
+
+    // The best player first scored last, so leads again only once the second page is ranked
+    assert_eq!(game.rebuild_leaderboard(&0, &0), crate::SEASON_PAGE_SIZE);
+    assert_eq!(game.get_ranking().get(0).unwrap().score, crate::SEASON_PAGE_SIZE - 1);
+    assert_eq!(game.rebuild_leaderboard(&0, &1), crate::SEASON_PAGE_SIZE + 1);
+    assert_eq!(game.get_ranking().get(0).unwrap().score, crate::SEASON_PAGE_SIZE);
+}
+
+#[test]
+fn test_scores_keep_the_image_id_they_were_verified_against() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    play(&setup, 1, 50);
+    assert_eq!(
+        env.events().all(),
+        std::vec![
+            SettlementRecorded { session_id: 1, player_won: true, status: SettlementStatus::Settled }
+                .to_xdr(env, &game.address),
+            SessionStatusChanged { session_id: 1, status: SessionStatus::Completed }.to_xdr(env, &game.address),
+            ScoreSubmitted { session_id: 1, player: player.clone(), score: 50, image_id: image_id(env, 1) }
+                .to_xdr(env, &game.address),
+        ]
+    );
+
+    game.set_image_id(&image_id(env, 2));
+    assert_eq!(
+        env.events().all(),
+        std::vec![ImageIdUpdated { previous: image_id(env, 1), image_id: image_id(env, 2) }.to_xdr(env, &game.address)]
+    );
+    play(&setup, 2, 60);
+
+    assert_eq!(game.get_session(&1).unwrap().image_id, Some(image_id(env, 1)));
+    assert_eq!(game.get_session(&2).unwrap().image_id, Some(image_id(env, 2)));
+    let leaderboard = game.get_leaderboard();
+    assert_eq!(leaderboard.get(0).unwrap().image_id, image_id(env, 1));
+    assert_eq!(leaderboard.get(1).unwrap().image_id, image_id(env, 2));
+    assert_eq!(game.get_image_id(), Some(image_id(env, 2)));
+
+    game.start_game(&3, player);
+    assert_eq!(game.get_session(&3).unwrap().image_id, None);
+}
+
+#[test]
+fn test_daily_runs_must_use_the_published_seed() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    env.ledger().set_timestamp(20_000 * 86_400 + 3_600);
+    assert_eq!(game.current_day(), 20_000);
+    assert_eq!(game.try_start_daily_game(&1, player), Err(Ok(Error::NoDailySeed)));
+
+    game.set_daily_seed(&20_000, &1234);
+    assert_eq!(
+        env.events().all(),
+        std::vec![DailySeedPublished { day: 20_000, seed: 1234 }.to_xdr(env, &game.address)]
+    );
+    assert_eq!(game.try_set_daily_seed(&20_000, &99), Err(Ok(Error::DailySeedExists)));
+    assert_eq!(game.get_daily_seed(&20_000), Some(1234));
+
+    game.start_daily_game(&1, player);
+    assert_eq!(game.get_session(&1).unwrap().seed, Some(1234));
+    assert_eq!(game.get_session(&1).unwrap().daily_day, Some(20_000));
+    let fixture_seed = proof(env, &with_seed(journal(&game.address, 1, 80), FIXTURE_SEED));
+    assert_eq!(game.try_submit_score(&1, player, &80, &fixture_seed), Err(Ok(Error::JournalMismatch)));
+    game.submit_score(&1, player, &80, &proof(env, &journal(&game.address, 1, 80)));
+
+    // Ordinary runs stay off the daily leaderboard, and tomorrow needs a new seed
+    play(&setup, 2, 90);
+    let daily = game.get_daily_leaderboard(&20_000);
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily.get(0).unwrap().score, 80);
+    assert_eq!(game.get_leaderboard().len(), 2);
+
+    // A worse run keeps the player's best, and the day's board outlives the seed's reuse
+    game.start_daily_game(&3, player);
+    game.submit_score(&3, player, &70, &proof(env, &journal(&game.address, 3, 70)));
+    let daily = game.get_daily_leaderboard(&20_000);
+    assert_eq!(daily.len(), 1);
+    assert_eq!(daily.get(0).unwrap().score, 80);
+
+    env.ledger().set_timestamp(20_001 * 86_400);
+    assert_eq!(game.try_start_daily_game(&4, player), Err(Ok(Error::NoDailySeed)));
+    game.set_daily_seed(&20_001, &1234);
+    assert_eq!(game.get_daily_leaderboard(&20_001).len(), 0);
+}
+
+#[test]
+fn test_sessions_are_indexed_by_status() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    for session_id in 1..=5 {
+        game.start_game(&session_id, player);
+    }
+    play(&setup, 6, 70);
+    assert_eq!(
+        env.events().all(),
+        std::vec![
+            SettlementRecorded { session_id: 6, player_won: true, status: SettlementStatus::Settled }
+                .to_xdr(env, &game.address),
+            SessionStatusChanged { session_id: 6, status: SessionStatus::Completed }.to_xdr(env, &game.address),
+            ScoreSubmitted { session_id: 6, player: player.clone(), score: 70, image_id: image_id(env, 1) }
+                .to_xdr(env, &game.address),
+        ]
+    );
+
+    game.expire_session(&2);
+    assert_eq!(
+        env.events().all(),
+        std::vec![
+            SettlementRecorded { session_id: 2, player_won: false, status: SettlementStatus::Settled }
+                .to_xdr(env, &game.address),
+            SessionStatusChanged { session_id: 2, status: SessionStatus::Expired }.to_xdr(env, &game.address),
+        ]
+    );
+    game.dispute_session(&4);
+    game.dispute_session(&6);
+    let by_status = |status| game.get_sessions_by_status(&status, &0, &10);
+    assert_eq!(by_status(SessionStatus::Active), soroban_sdk::vec![env, 1, 3, 5]);
+    assert_eq!(by_status(SessionStatus::Expired), soroban_sdk::vec![env, 2]);
+    assert_eq!(by_status(SessionStatus::Disputed), soroban_sdk::vec![env, 4, 6]);
+    assert_eq!(game.get_sessions_by_status(&SessionStatus::Active, &1, &1), soroban_sdk::vec![env, 3]);
+    assert_eq!(game.get_sessions_by_status(&SessionStatus::Active, &3, &10), soroban_sdk::vec![env]);
+    assert_eq!(game.get_sessions_by_status(&SessionStatus::Active, &0, &0), soroban_sdk::vec![env]);
+    assert_eq!(
+        game.try_get_sessions_by_status(&SessionStatus::Completed, &0, &10),
+        Err(Ok(Error::StatusNotIndexed))
+    );
+
+    // A dispute ends as completed with a score and expired without one
+    game.resolve_dispute(&4);
+    game.resolve_dispute(&6);
+    assert_eq!(game.get_session(&4).unwrap().status, SessionStatus::Expired);
+    assert_eq!(game.get_session(&6).unwrap().status, SessionStatus::Completed);
+    assert_eq!(by_status(SessionStatus::Expired), soroban_sdk::vec![env, 2, 4]);
+    assert_eq!(by_status(SessionStatus::Disputed), soroban_sdk::vec![env]);
+    let counts = [SessionStatus::Active, SessionStatus::Completed, SessionStatus::Expired, SessionStatus::Disputed]
+        .map(|status| game.get_session_count(&status));
+    assert_eq!(counts, [3, 1, 2, 0]);
+
+    // Closed sessions take no more scores or transitions
+    let late = proof(env, &journal(&game.address, 2, 10));
+    assert_eq!(game.try_submit_score(&2, player, &10, &late), Err(Ok(Error::SessionNotActive)));
+    let replayed = proof(env, &journal(&game.address, 6, 70));
+    assert_eq!(game.try_submit_score(&6, player, &70, &replayed), Err(Ok(Error::SessionNotActive)));
+    assert_eq!(game.try_expire_session(&6), Err(Ok(Error::SessionNotActive)));
+    assert_eq!(game.try_resolve_dispute(&1), Err(Ok(Error::SessionNotActive)));
+    assert_eq!(game.try_expire_session(&99), Err(Ok(Error::SessionNotFound)));
+}
+
+#[test]
+fn test_status_queries_page_through_the_index() {
+    let Setup { env, game, player, .. } = setup();
+    let sessions = crate::MAX_SESSIONS_PER_QUERY + 1;
+    for session_id in 0..sessions {
+        game.start_practice_game(&session_id, &player);
+    }
+    let active = |offset, limit| game.get_sessions_by_status(&SessionStatus::Active, &offset, &limit);
+    // Queries run across pages and stop at the cap
+    assert_eq!(active(0, u32::MAX).len(), crate::MAX_SESSIONS_PER_QUERY);
+    assert_eq!(active(0, u32::MAX).last(), Some(crate::MAX_SESSIONS_PER_QUERY - 1));
+    assert_eq!(active(crate::STATUS_PAGE_SIZE - 1, 2), soroban_sdk::vec![&env, 49, 50]);
+    assert_eq!(active(sessions - 1, 10), soroban_sdk::vec![&env, sessions - 1]);
+    assert_eq!(active(sessions, 10), soroban_sdk::vec![&env]);
+    assert_eq!(active(u32::MAX, 10), soroban_sdk::vec![&env]);
+    assert_eq!(active(0, 0), soroban_sdk::vec![&env]);
+
+    // Sessions that leave a status drop out of its offsets
+    game.expire_session(&3);
+    assert_eq!(active(2, 2), soroban_sdk::vec![&env, 2, 4]);
+    assert_eq!(game.get_session(&3).unwrap().status_page, 0);
+    let expired = game.get_sessions_by_status(&SessionStatus::Expired, &0, &10);
+    assert_eq!(expired, soroban_sdk::vec![&env, 3]);
+
+    // Emptied pages are skipped from then on
+    for session_id in 0..crate::STATUS_PAGE_SIZE {
+        if session_id != 3 {
+            game.expire_session(&session_id);
+        }
+    }
+    let head = env.as_contract(&game.address, || crate::status_head(&env, SessionStatus::Active));
+    assert_eq!(head, 1);
+    assert_eq!(active(0, 1), soroban_sdk::vec![&env, crate::STATUS_PAGE_SIZE]);
+}
+
+#[test]
+fn test_journal_versions_outside_the_accepted_range_are_rejected() {
+    let Setup { env, game, player, .. } = setup();
+    let supported = VersionRange { min: MIN_SIM_VERSION, max: MAX_SIM_VERSION };
+    assert_eq!(game.get_journal_versions(), supported);
+    game.start_game(&SESSION_ID, &player);
+
+    for version in [0, MIN_SIM_VERSION - 1, MAX_SIM_VERSION + 1, u32::MAX] {
+        let mut bytes = journal(&game.address, SESSION_ID, 109);
+        bytes[VERSION_AT..VERSION_AT + 4].copy_from_slice(&version.to_le_bytes());
+        assert_eq!(
+            game.try_submit_score(&SESSION_ID, &player, &109, &proof(&env, &bytes)),
+            Err(Ok(Error::UnsupportedJournalVersion)),
+            "sim_version {version}"
+        );
+    }
+
+    // The range can only narrow to versions the decoder reads
+    let invalid = [
+        (MAX_SIM_VERSION, MIN_SIM_VERSION - 1),
+        (MIN_SIM_VERSION - 1, MAX_SIM_VERSION),
+        (MIN_SIM_VERSION, MAX_SIM_VERSION + 1),
+    ];
+    for (min, max) in invalid {
+        assert_eq!(game.try_set_journal_versions(&min, &max), Err(Ok(Error::InvalidVersionRange)));
+    }
+    game.set_journal_versions(&MAX_SIM_VERSION, &MAX_SIM_VERSION);
+    assert_eq!(
+        env.events().all(),
+        std::vec![JournalVersionsUpdated { min: MAX_SIM_VERSION, max: MAX_SIM_VERSION }.to_xdr(&env, &game.address)]
+    );
+    assert_eq!(game.get_journal_versions(), VersionRange { min: MAX_SIM_VERSION, max: MAX_SIM_VERSION });
+    game.submit_score(&SESSION_ID, &player, &109, &proof(&env, &journal(&game.address, SESSION_ID, 109)));
+}
+
+#[test]
+fn test_only_allowlisted_players_reach_the_eligible_leaderboard() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    play(&setup, 1, 10);
+    assert!(game.is_eligible(player));
+    assert_eq!(game.get_eligible_leaderboard().len(), 1);
+
+    game.set_entry_requirement(&EntryRequirement::Allowlist);
+    assert!(!game.is_eligible(player));
+    play(&setup, 2, 20);
+    game.set_allowed(player, &true);
+    assert_eq!(
+        env.events().all(),
+        std::vec![AllowlistUpdated { player: player.clone(), allowed: true }.to_xdr(env, &game.address)]
+    );
+    play(&setup, 3, 30);
+    game.set_allowed(player, &false);
+    play(&setup, 4, 40);
+
+    let scores =
+        |leaderboard: soroban_sdk::Vec<crate::ScoreEntry>| leaderboard.iter().map(|e| e.score).collect::<Vec<_>>();
+    assert_eq!(scores(game.get_leaderboard()), [10, 20, 30, 40]);
+    assert_eq!(scores(game.get_eligible_leaderboard()), [30]);
+}
+
+#[test]
+fn test_the_eligible_leaderboard_keeps_the_best_players_only() {
+    let Setup { env, game, .. } = setup();
+    for score in 0..=crate::RANKING_SIZE {
+        let entry = ScoreEntry { player: Address::generate(&env), score, image_id: image_id(&env, 1) };
+        env.as_contract(&game.address, || crate::record_eligible(&env, &entry));
+    }
+    let eligible = game.get_eligible_leaderboard();
+    assert_eq!(eligible.len(), crate::RANKING_SIZE);
+    assert_eq!(eligible.first().unwrap().score, crate::RANKING_SIZE);
+    assert_eq!(eligible.last().unwrap().score, 1);
+}
+
+#[test]
+fn test_stakes_gate_eligibility_and_stay_locked_for_the_season() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    let token_id = env.register(StubToken, ());
+    let token = StubTokenClient::new(env, &token_id);
+    token.mint(player, &1_000);
+
+    assert_eq!(game.try_stake(player), Err(Ok(Error::NoStakeRequired)));
+    let requirement = StakeRequirement { token: token_id.clone(), amount: 300 };
+    game.set_entry_requirement(&EntryRequirement::Stake(requirement.clone()));
+    assert_eq!(game.get_entry_requirement(), EntryRequirement::Stake(requirement));
+    play(&setup, 1, 10);
+
+    game.stake(player);
+    assert_eq!(
+        env.events().all().filter_by_contract(&game.address),
+        std::vec![
+            StakeUpdated { player: player.clone(), token: token_id.clone(), amount: 300 }.to_xdr(env, &game.address)
+        ]
+    );
+    assert_eq!(token.balance(player), 700);
+    assert!(game.is_eligible(player));
+    play(&setup, 2, 20);
+
+    // Raising the requirement only takes the difference
+    game.set_entry_requirement(&EntryRequirement::Stake(StakeRequirement { token: token_id.clone(), amount: 500 }));
+    assert!(!game.is_eligible(player));
+    game.stake(player);
+    assert_eq!(token.balance(player), 500);
+    assert_eq!(game.get_stake(player), Some(Stake { token: token_id.clone(), amount: 500 }));
+
+    assert_eq!(game.try_unstake(player), Err(Ok(Error::StakeLocked)));
+    game.set_stakes_unlocked(&true);
+    game.unstake(player);
+    assert_eq!(token.balance(player), 1_000);
+    assert_eq!(game.get_stake(player), None);
+    assert_eq!(game.try_unstake(player), Err(Ok(Error::NothingStaked)));
+    assert_eq!(game.get_eligible_leaderboard().len(), 1);
+}
+
+#[test]
+fn test_large_prizes_vest_linearly_until_cancelled() {
+    let Setup { env, game, player, .. } = setup();
+    let token_id = env.register(StubToken, ());
+    let token = StubTokenClient::new(&env, &token_id);
+    token.mint(&game.address, &10_000);
+
+    assert_eq!(
+        game.try_set_vesting_policy(&Some(VestingPolicy { threshold: 100, ledgers: 0 })),
+        Err(Ok(Error::InvalidVestingPolicy))
+    );
+    game.set_vesting_policy(&Some(VestingPolicy { threshold: 100, ledgers: 1_000 }));
+    assert_eq!(game.try_award_prize(&player, &token_id, &0), Err(Ok(Error::InvalidPrize)));
+
+    // Prizes up to the threshold are paid at once
+    game.award_prize(&player, &token_id, &100);
+    assert_eq!(token.balance(&player), 100);
+    assert_eq!(game.get_vesting(&player).len(), 0);
+
+    let start = env.ledger().sequence();
+    game.award_prize(&player, &token_id, &4_000);
+    assert_eq!(token.balance(&player), 100);
+    assert_eq!(
+        game.get_vesting(&player).get(0),
+        Some(Vesting { token: token_id.clone(), amount: 4_000, claimed: 0, start, ledgers: 1_000 })
+    );
+    assert_eq!(game.try_claim_vested(&player), Err(Ok(Error::NothingToClaim)));
+
+    env.ledger().set_sequence_number(start + 250);
+    game.claim_vested(&player);
+    assert_eq!(
+        env.events().all().filter_by_contract(&game.address),
+        std::vec![
+            VestedClaimed { player: player.clone(), token: token_id.clone(), amount: 1_000 }.to_xdr(&env, &game.address)
+        ]
+    );
+    assert_eq!(token.balance(&player), 1_100);
+    assert_eq!(game.get_vesting(&player).get(0).unwrap().claimed, 1_000);
+
+    // An exploit turns up: the rest stays with the contract
+    game.cancel_vesting(&player);
+    env.ledger().set_sequence_number(start + 2_000);
+    assert_eq!(game.try_claim_vested(&player), Err(Ok(Error::NothingToClaim)));
+    assert_eq!(token.balance(&game.address), 8_900);
+
+    // Without a policy everything is paid at once
+    game.set_vesting_policy(&None);
+    game.award_prize(&player, &token_id, &4_000);
+    assert_eq!(token.balance(&player), 5_100);
+}
+
+#[test]
+fn test_prizes_cannot_spend_stakes_or_vesting_prizes() {
+    let setup = setup();
+    let Setup { env, game, player, .. } = &setup;
+    let token_id = env.register(StubToken, ());
+    let token = StubTokenClient::new(env, &token_id);
+    token.mint(player, &1_000);
+    token.mint(&game.address, &500);
+    game.set_entry_requirement(&EntryRequirement::Stake(StakeRequirement { token: token_id.clone(), amount: 1_000 }));
+    game.stake(player);
+
+    // 1,500 held, 1,000 of it staked
+    let winner = Address::generate(env);
+    assert_eq!(game.try_award_prize(&winner, &token_id, &501), Err(Ok(Error::InsufficientPrizeFunds)));
+    game.set_vesting_policy(&Some(VestingPolicy { threshold: 100, ledgers: 100 }));
+    game.award_prize(&winner, &token_id, &400);
+    assert_eq!(game.try_award_prize(&winner, &token_id, &101), Err(Ok(Error::InsufficientPrizeFunds)));
+
+    // Claims pay out of the reserve, cancelling frees what is left of it
+    env.ledger().set_sequence_number(env.ledger().sequence() + 50);
+    game.claim_vested(&winner);
+    assert_eq!(token.balance(&winner), 200);
+    assert_eq!(game.try_award_prize(&winner, &token_id, &101), Err(Ok(Error::InsufficientPrizeFunds)));
+    game.cancel_vesting(&winner);
+    game.set_vesting_policy(&None);
+    game.award_prize(&winner, &token_id, &300);
+    assert_eq!(game.try_award_prize(&winner, &token_id, &1), Err(Ok(Error::InsufficientPrizeFunds)));
+
+    // Unstaking takes the stake out of the reserve along with the balance
+    game.set_stakes_unlocked(&true);
+    game.unstake(player);
+    assert_eq!(token.balance(player), 1_000);
+    assert_eq!(token.balance(&game.address), 0);
+}
+
+#[test]
+fn test_vesting_large_prizes_does_not_overflow() {
+    let env = Env::default();
+    let vesting = Vesting { token: Address::generate(&env), amount: i128::MAX, claimed: 0, start: 0, ledgers: 3 };
+    assert_eq!(crate::vested(&vesting, 1), i128::MAX / 3);
+    assert_eq!(crate::vested(&vesting, 3), i128::MAX);
+}
+
+#[test]
+fn test_admin_calls_follow_two_step_ownership_transfers() {
+    let Setup { env, game, .. } = setup();
+    let owner = game.get_owner().unwrap();
+    let next = Address::generate(&env);
+
+    game.transfer_ownership(&next, &(env.ledger().sequence() + 100));
+    assert_eq!(game.get_owner(), Some(owner));
+    game.accept_ownership();
+    assert_eq!(game.get_owner(), Some(next.clone()));
+
+    game.set_stakes_unlocked(&true);
+    assert_eq!(env.auths()[0].0, next);
+}
+
+#[test]
+fn test_hub_failures_are_recorded_and_can_be_retried() {
+    let setup = setup();
+    let Setup { env, game, hub, .. } = &setup;
+    assert_eq!(game.get_settlement(&SESSION_ID), None);
+
+    // The score is recorded even though the hub is down
+    hub.set_failing(&true);
+    play(&setup, SESSION_ID, 109);
+    assert_eq!(game.get_session(&SESSION_ID).unwrap().status, SessionStatus::Completed);
+    let ledger = env.ledger().sequence();
+    assert_eq!(
+        game.get_settlement(&SESSION_ID),
+        Some(Settlement { player_won: true, status: SettlementStatus::Failed, ledger, attempts: 1 })
+    );
+
+    hub.set_failing(&false);
+    env.ledger().set_sequence_number(ledger + 10);
+    let settled = Settlement { player_won: true, status: SettlementStatus::Settled, ledger: ledger + 10, attempts: 2 };
+    assert_eq!(game.retry_settlement(&SESSION_ID), settled);
+    assert_eq!(
+        env.events().all(),
+        std::vec![SettlementRecorded { session_id: SESSION_ID, player_won: true, status: SettlementStatus::Settled }
+            .to_xdr(env, &game.address)]
+    );
+    assert_eq!(game.get_settlement(&SESSION_ID), Some(settled));
+    // Settlements live in persistent storage, kept alive as they are read
+    let ttl = env.as_contract(&game.address, || {
+        assert!(!env.storage().instance().has(&DataKey::Settlement(SESSION_ID)));
+        env.storage().persistent().get_ttl(&DataKey::Settlement(SESSION_ID))
+    });
+    assert!(ttl >= crate::PERSISTENT_TTL_THRESHOLD);
+    assert_eq!(game.try_retry_settlement(&SESSION_ID), Err(Ok(Error::NothingToSettle)));
+    assert_eq!(game.try_retry_settlement(&99), Err(Ok(Error::NothingToSettle)));
+}
@@ -0,0 +1,194 @@
+//! Deploys and administers the Lane Racer contracts: the RISC Zero router, a verifier,
+//! and the game, wired together in the order they depend on each other. Every call goes
+//! through the `stellar` CLI; `--dry-run` prints the commands instead.
+
+mod stellar;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use stellar::Stellar;
+use std::path::PathBuf;
+
+/// Build outputs of `stellar contract build`, relative to the repository root.
+const ROUTER_WASM: &str = "contracts/stellar-risc0-verifier/target/wasm32v1-none/release/risc0_router.wasm";
+const GROTH16_WASM: &str = "contracts/stellar-risc0-verifier/target/wasm32v1-none/release/groth16_verifier.wasm";
+const MOCK_WASM: &str = "contracts/stellar-risc0-verifier/target/wasm32v1-none/release/mock_verifier.wasm";
+const GAME_WASM: &str = "target/wasm32v1-none/release/lane_racer.wasm";
+
+#[derive(Parser)]
+#[command(name = "lane-racer-cli", about = "Deploy and administer the Lane Racer contracts")]
+struct Cli {
+    #[command(flatten)]
+    stellar: Stellar,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum VerifierKind {
+    /// The Groth16 verifier, for real proofs.
+    Groth16,
+    /// The mock verifier, for dev-mode proofs. Accepts seals that prove nothing.
+    Mock,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy a router and a verifier, register the verifier's selector, and deploy the
+    /// game pointed at the router. Prints the contract IDs as JSON.
+    Deploy {
+        /// Owner of the router and mock verifier, and admin of the game.
+        #[arg(long)]
+        owner: String,
+        /// Game hub contract the game reports sessions to.
+        #[arg(long)]
+        game_hub: String,
+        /// Image ID of the guest whose proofs the game accepts, as written to
+        /// `lane_racer_prover_image_id.txt` by the `methods` build.
+        #[arg(long, value_parser = hex_bytes::<32>)]
+        image_id: String,
+        #[arg(long, value_enum, default_value_t = VerifierKind::Groth16)]
+        verifier: VerifierKind,
+        /// Selector for the mock verifier; the Groth16 verifier reports its own.
+        #[arg(long, value_parser = hex_bytes::<4>, default_value = "ffffffff")]
+        mock_selector: String,
+        #[arg(long, default_value = ROUTER_WASM)]
+        router_wasm: PathBuf,
+        /// Defaults to the build output of the chosen `--verifier`.
+        #[arg(long)]
+        verifier_wasm: Option<PathBuf>,
+        #[arg(long, default_value = GAME_WASM)]
+        game_wasm: PathBuf,
+    },
+    /// Register a deployed verifier with the router under its selector.
+    RegisterVerifier {
+        #[arg(long)]
+        router: String,
+        #[arg(long)]
+        verifier: String,
+        /// Defaults to the selector the verifier reports.
+        #[arg(long, value_parser = hex_bytes::<4>)]
+        selector: Option<String>,
+    },
+    /// Accept proofs of a new guest image, e.g. after a simulation change.
+    SetImageId {
+        #[arg(long)]
+        game: String,
+        #[arg(long, value_parser = hex_bytes::<32>)]
+        image_id: String,
+    },
+    /// Submit a proof returned by the prover (`POST /prove`) as a `submit_score` call.
+    SubmitScore {
+        #[arg(long)]
+        game: String,
+        /// Proof response JSON file.
+        #[arg(long)]
+        proof: PathBuf,
+    },
+}
+
+/// Accepts `N` bytes of hex.
+fn hex_bytes<const N: usize>(value: &str) -> Result<String, String> {
+    if value.len() == 2 * N && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(value.to_ascii_lowercase())
+    } else {
+        Err(format!("expected {} hex characters", 2 * N))
+    }
+}
+
+/// The part of the prover's proof response this tool reads.
+#[derive(Deserialize)]
+struct ProofFile {
+    args: ProofArgs,
+}
+
+#[derive(Deserialize)]
+struct ProofArgs {
+    submit_score: SubmitScoreArgs,
+}
+
+#[derive(Deserialize)]
+struct SubmitScoreArgs {
+    session_id: u32,
+    player: String,
+    score: u32,
+    proof: serde_json::Value,
+}
+
+fn register_verifier(stellar: &Stellar, router: &str, verifier: &str, selector: Option<&str>) -> Result<String> {
+    let selector = match selector {
+        Some(selector) => selector.to_string(),
+        None => stellar.read(verifier, "selector", &[])?,
+    };
+    stellar.invoke(router, "add_verifier", &[("selector", &selector), ("verifier", verifier)])?;
+    Ok(selector)
+}
+
+fn main() -> Result<()> {
+    let Cli { stellar, command } = Cli::parse();
+    match command {
+        Command::Deploy {
+            owner,
+            game_hub,
+            image_id,
+            verifier,
+            mock_selector,
+            router_wasm,
+            verifier_wasm,
+            game_wasm,
+        } => {
+            let router = stellar.deploy("router", &router_wasm.to_string_lossy(), &[("owner", &owner)])?;
+            let (default_wasm, constructor): (&str, Vec<(&str, &str)>) = match verifier {
+                VerifierKind::Groth16 => (GROTH16_WASM, vec![]),
+                VerifierKind::Mock => (MOCK_WASM, vec![("selector", &mock_selector), ("owner", &owner)]),
+            };
+            let verifier_wasm = verifier_wasm.unwrap_or_else(|| default_wasm.into());
+            let verifier_id = stellar.deploy("verifier", &verifier_wasm.to_string_lossy(), &constructor)?;
+            let selector = match verifier {
+                VerifierKind::Groth16 => None,
+                VerifierKind::Mock => Some(mock_selector.as_str()),
+            };
+            let selector = register_verifier(&stellar, &router, &verifier_id, selector)?;
+            let game = stellar.deploy(
+                "game",
+                &game_wasm.to_string_lossy(),
+                &[("admin", &owner), ("game_hub", &game_hub), ("verifier", &router), ("image_id", &image_id)],
+            )?;
+            let ids = serde_json::json!({
+                "router": router,
+                "verifier": verifier_id,
+                "selector": selector,
+                "game": game,
+                "image_id": image_id,
+            });
+            println!("{}", serde_json::to_string_pretty(&ids)?);
+        }
+        Command::RegisterVerifier { router, verifier, selector } => {
+            let selector = register_verifier(&stellar, &router, &verifier, selector.as_deref())?;
+            println!("registered {verifier} under selector {selector}");
+        }
+        Command::SetImageId { game, image_id } => {
+            stellar.invoke(&game, "set_image_id", &[("image_id", &image_id)])?;
+        }
+        Command::SubmitScore { game, proof } => {
+            let text = std::fs::read_to_string(&proof).with_context(|| format!("reading {}", proof.display()))?;
+            let file: ProofFile =
+                serde_json::from_str(&text).with_context(|| format!("{} is not a prover response", proof.display()))?;
+            let args = file.args.submit_score;
+            let session_id = args.session_id.to_string();
+            let score = args.score.to_string();
+            let zk_proof = serde_json::to_string(&args.proof)?;
+            let result = stellar.invoke(
+                &game,
+                "submit_score",
+                &[("session_id", &session_id), ("player", &args.player), ("score", &score), ("proof", &zk_proof)],
+            )?;
+            if !stellar.dry_run && !result.is_empty() {
+                println!("{result}");
+            }
+        }
+    }
+    Ok(())
+}
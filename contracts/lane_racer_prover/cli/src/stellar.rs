@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Network and signer settings shared by every subcommand. Transactions are built,
+/// simulated, signed and sent through the `stellar` CLI, like the prover's submission.
+#[derive(Clone, clap::Args)]
+pub struct Stellar {
+    /// Soroban RPC endpoint.
+    #[arg(long, global = true, default_value = "https://soroban-testnet.stellar.org")]
+    pub rpc_url: String,
+
+    /// Network passphrase used for signing.
+    #[arg(long, global = true, default_value = "Test SDF Network ; September 2015")]
+    pub network_passphrase: String,
+
+    /// Signing account: a secret key or a `stellar keys` identity name. Admin commands
+    /// must be signed by the contracts' owner.
+    #[arg(long, global = true, env = "LANE_RACER_SOURCE_ACCOUNT", hide_env_values = true)]
+    pub source: Option<String>,
+
+    /// Path to the `stellar` CLI binary.
+    #[arg(long, global = true, default_value = "stellar")]
+    pub stellar_cli: PathBuf,
+
+    /// Print the `stellar` commands instead of running them. Contract IDs that earlier
+    /// steps would return are shown as `<name>` placeholders.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+}
+
+impl Stellar {
+    /// Deploys a WASM file and returns the new contract ID. `constructor` holds the
+    /// `__constructor` arguments as `--name value` pairs.
+    pub fn deploy(&self, name: &str, wasm: &str, constructor: &[(&str, &str)]) -> Result<String> {
+        let mut args = vec!["contract".to_string(), "deploy".to_string(), "--wasm".to_string(), wasm.to_string()];
+        args.extend(self.network_args());
+        if !constructor.is_empty() {
+            args.push("--".to_string());
+            args.extend(function_args(constructor));
+        }
+        self.run(&args, &format!("<{name}>"))
+    }
+
+    /// Invokes `function` on `contract` in a transaction and returns its result.
+    pub fn invoke(&self, contract: &str, function: &str, args: &[(&str, &str)]) -> Result<String> {
+        self.call(contract, function, args, true)
+    }
+
+    /// Simulates a read-only call of `function` and returns its result.
+    pub fn read(&self, contract: &str, function: &str, args: &[(&str, &str)]) -> Result<String> {
+        self.call(contract, function, args, false)
+    }
+
+    fn call(&self, contract: &str, function: &str, fn_args: &[(&str, &str)], send: bool) -> Result<String> {
+        let send = if send { "yes" } else { "no" };
+        let mut args: Vec<String> = ["contract", "invoke", "--send", send, "--id", contract]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        args.extend(self.network_args());
+        args.push("--".to_string());
+        args.push(function.to_string());
+        args.extend(function_args(fn_args));
+        self.run(&args, &format!("<{function}>"))
+    }
+
+    fn network_args(&self) -> Vec<String> {
+        vec![
+            "--rpc-url".to_string(),
+            self.rpc_url.clone(),
+            "--network-passphrase".to_string(),
+            self.network_passphrase.clone(),
+        ]
+    }
+
+    /// Runs the CLI and returns its trimmed stdout, or prints the command and returns
+    /// `placeholder` on a dry run.
+    fn run(&self, args: &[String], placeholder: &str) -> Result<String> {
+        if self.dry_run {
+            let quoted: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+            println!("{} {}", self.stellar_cli.display(), quoted.join(" "));
+            return Ok(placeholder.to_string());
+        }
+        let source = self.source.as_deref().context("--source (or LANE_RACER_SOURCE_ACCOUNT) is required")?;
+        // The source goes through the environment so secret keys never appear in `ps`.
+        let output = Command::new(&self.stellar_cli)
+            .env("STELLAR_ACCOUNT", source)
+            .args(args)
+            .output()
+            .with_context(|| format!("running {}", self.stellar_cli.display()))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::ensure!(output.status.success(), "stellar {} failed: {}", args[..2].join(" "), stderr.trim());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // Results are printed as JSON values, so strings come quoted.
+        Ok(stdout.trim().trim_matches('"').to_string())
+    }
+}
+
+fn function_args(args: &[(&str, &str)]) -> Vec<String> {
+    args.iter().flat_map(|(name, value)| [format!("--{name}"), value.to_string()]).collect()
+}
+
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:<>=".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
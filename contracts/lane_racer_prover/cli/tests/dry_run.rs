@@ -0,0 +1,67 @@
+use std::process::Command;
+
+const IMAGE_ID: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+fn dry_run(args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_lane-racer-cli"))
+        .arg("--dry-run")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn deploy_wires_the_router_verifier_and_game_in_order() {
+    let lines = dry_run(&["deploy", "--owner", "GOWNER", "--game-hub", "CHUB", "--image-id", IMAGE_ID, "--verifier", "mock"]);
+    let commands: Vec<&String> = lines.iter().filter(|line| line.starts_with("stellar ")).collect();
+    assert_eq!(commands.len(), 4, "{lines:#?}");
+    assert!(commands[0].contains("contract deploy --wasm contracts/stellar-risc0-verifier/target/wasm32v1-none/release/risc0_router.wasm"));
+    assert!(commands[0].ends_with("-- --owner GOWNER"));
+    assert!(commands[1].contains("mock_verifier.wasm"));
+    assert!(commands[1].ends_with("-- --selector ffffffff --owner GOWNER"));
+    assert!(commands[2].contains("--id <router>"));
+    assert!(commands[2].ends_with("-- add_verifier --selector ffffffff --verifier <verifier>"));
+    assert!(commands[3].contains("lane_racer.wasm"));
+    assert!(commands[3].ends_with(&format!(
+        "-- --admin GOWNER --game_hub CHUB --verifier <router> --image_id {IMAGE_ID}"
+    )));
+}
+
+#[test]
+fn groth16_selectors_are_read_from_the_verifier() {
+    let lines = dry_run(&["register-verifier", "--router", "CROUTER", "--verifier", "CVERIFIER"]);
+    assert_eq!(lines.len(), 3, "{lines:#?}");
+    assert!(lines[0].contains("--send no --id CVERIFIER"));
+    assert!(lines[0].ends_with("-- selector"));
+    assert!(lines[1].ends_with("-- add_verifier --selector <selector> --verifier CVERIFIER"));
+}
+
+#[test]
+fn submit_score_passes_the_prover_arguments() {
+    let proof = std::env::temp_dir().join(format!("lane-racer-cli-proof-{}.json", std::process::id()));
+    std::fs::write(
+        &proof,
+        r#"{"score": 109, "args": {"submit_score": {"session_id": 7, "player": "GPLAYER", "score": 109,
+            "proof": {"seal": "ffffffff00", "journal": "0700"}}}}"#,
+    )
+    .unwrap();
+    let lines = dry_run(&["submit-score", "--game", "CGAME", "--proof", proof.to_str().unwrap()]);
+    std::fs::remove_file(&proof).unwrap();
+    assert_eq!(lines.len(), 1, "{lines:#?}");
+    assert!(lines[0].contains("--send yes --id CGAME"));
+    assert!(lines[0].ends_with(
+        r#"-- submit_score --session_id 7 --player GPLAYER --score 109 --proof '{"journal":"0700","seal":"ffffffff00"}'"#
+    ));
+}
+
+#[test]
+fn malformed_ids_are_rejected() {
+    let output = Command::new(env!("CARGO_BIN_EXE_lane-racer-cli"))
+        .args(["--dry-run", "set-image-id", "--game", "CGAME", "--image-id", "1234"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected 64 hex characters"));
+}
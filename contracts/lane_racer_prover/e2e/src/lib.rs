@@ -0,0 +1,2 @@
+//! End-to-end tests of a proof's path from the prover to the Soroban contracts; see
+//! `tests/`. The tests need the `e2e` feature.
@@ -0,0 +1,144 @@
+//! A dev-mode receipt from the host's proving path, submitted to the lane-racer contract
+//! and verified by the mock verifier, all in one Soroban test `Env`. Any change to the
+//! seal, journal or argument formats on either side fails here rather than on testnet.
+
+#![cfg(feature = "e2e")]
+
+use host::prover::{prove_game, Backend, ProofResponse};
+use lane_racer::{Error, LaneRacerContract, LaneRacerContractClient, SessionStatus, ZKProof};
+use lane_racer_sim::{Action, Difficulty, GameConfig, GameInput, PackedActions, INPUT_VERSION};
+use methods::LANE_RACER_PROVER_ID_BYTES;
+use mock_verifier::{RiscZeroMockVerifier, RiscZeroMockVerifierClient};
+use soroban_sdk::{
+    address_payload::AddressPayload, contract, contractimpl, testutils::Address as _, Address, Bytes, BytesN, Env,
+    String,
+};
+
+const PLAYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+const SESSION_ID: u32 = 7;
+const TRACE: &str = include_str!("../../../lane-racer-journal/fixtures/trace.txt");
+
+/// Accepts the game hub calls lane-racer makes.
+#[contract]
+struct StubGameHub;
+
+#[contractimpl]
+impl StubGameHub {
+    pub fn start_game(
+        _env: Env,
+        _game_id: Address,
+        _session_id: u32,
+        _player1: Address,
+        _player2: Address,
+        _player1_points: i128,
+        _player2_points: i128,
+    ) {
+    }
+
+    pub fn end_game(_env: Env, _session_id: u32, _player1_won: bool) {}
+}
+
+struct Setup<'a> {
+    env: Env,
+    game: LaneRacerContractClient<'a>,
+    verifier: RiscZeroMockVerifierClient<'a>,
+    player: Address,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    let verifier_id = env.register(RiscZeroMockVerifier, (BytesN::from_array(&env, &[0xde, 0xad, 0xbe, 0xef]), &owner));
+    let hub_id = env.register(StubGameHub, ());
+    let image_id = BytesN::from_array(&env, &LANE_RACER_PROVER_ID_BYTES);
+    let game_id = env.register(LaneRacerContract, (&owner, &hub_id, &verifier_id, image_id));
+    let game = LaneRacerContractClient::new(&env, &game_id);
+    let player = Address::from_string(&String::from_str(&env, PLAYER));
+    game.start_game(&SESSION_ID, &player);
+    let verifier = RiscZeroMockVerifierClient::new(&env, &verifier_id);
+    Setup { env, game, verifier, player }
+}
+
+/// Proves the fixture run for the session on `game` through the host's dev backend, on the
+/// seed the contract drew for it.
+fn prove(game: &LaneRacerContractClient) -> ProofResponse {
+    let contract_id = match game.address.to_payload() {
+        Some(AddressPayload::ContractIdHash(hash)) => hash.to_array(),
+        _ => unreachable!("contracts have contract addresses"),
+    };
+    let actions: Vec<Action> = TRACE
+        .trim()
+        .chars()
+        .map(|c| Action::try_from(c.to_digit(10).unwrap()).unwrap())
+        .collect();
+    let input = GameInput {
+        version: INPUT_VERSION,
+        seed: game.get_session(&SESSION_ID).unwrap().seed.unwrap(),
+        actions: PackedActions::pack(&actions),
+        player_address: PLAYER.to_string(),
+        game_id: 1,
+        session_id: SESSION_ID,
+        contract_id,
+        config: GameConfig::default(),
+        difficulty: Difficulty::Normal,
+    };
+    prove_game(input, None, Backend::Dev).unwrap()
+}
+
+fn bytes(env: &Env, hex_str: &str) -> Bytes {
+    Bytes::from_slice(env, &hex::decode(hex_str).unwrap())
+}
+
+fn bytes32(env: &Env, hex_str: &str) -> BytesN<32> {
+    BytesN::from_array(env, &hex::decode(hex_str).unwrap().try_into().unwrap())
+}
+
+#[test]
+fn dev_receipt_verifies_against_the_mock_verifier() {
+    let Setup { env, game, verifier, .. } = setup();
+    let proof = prove(&game);
+    let args = &proof.args.verify;
+    verifier.verify(&bytes(&env, &args.seal), &bytes32(&env, &args.image_id), &bytes32(&env, &args.journal));
+    assert_eq!(verifier.calls().get(0).unwrap().claim_digest, bytes32(&env, &proof.claim_digest));
+}
+
+#[test]
+fn dev_receipt_is_accepted_by_submit_score() {
+    let Setup { env, game, verifier, player } = setup();
+    let proof = prove(&game);
+    let args = &proof.args.submit_score;
+    assert_eq!(args.player, PLAYER);
+    let zk_proof = ZKProof { seal: bytes(&env, &args.proof.seal), journal: bytes(&env, &args.proof.journal) };
+    game.submit_score(&args.session_id, &player, &args.score, &zk_proof);
+    assert_eq!(verifier.calls().last().unwrap().caller, Some(game.address.clone()));
+
+    let session = game.get_session(&SESSION_ID).unwrap();
+    assert_eq!(session.score, proof.score);
+    assert_eq!(session.status, SessionStatus::Completed);
+    assert_eq!(session.image_id, Some(BytesN::from_array(&env, &LANE_RACER_PROVER_ID_BYTES)));
+    let leaderboard = game.get_leaderboard();
+    assert_eq!(leaderboard.len(), 1);
+    assert_eq!(leaderboard.get(0).unwrap().score, proof.score);
+}
+
+#[test]
+fn submit_score_rejects_a_tampered_journal_or_score() {
+    let Setup { env, game, player, .. } = setup();
+    let proof = prove(&game);
+    let args = &proof.args.submit_score;
+    let seal = bytes(&env, &args.proof.seal);
+
+    // Raising the score in the journal breaks the seal
+    let mut journal = hex::decode(&args.proof.journal).unwrap();
+    journal[152] = journal[152].wrapping_add(1);
+    let tampered = ZKProof { seal: seal.clone(), journal: Bytes::from_slice(&env, &journal) };
+    assert_eq!(game.try_submit_score(&SESSION_ID, &player, &args.score, &tampered), Err(Ok(Error::InvalidProof)));
+
+    // Claiming another score than the proven one is a mismatch
+    let honest = ZKProof { seal, journal: bytes(&env, &args.proof.journal) };
+    assert_eq!(
+        game.try_submit_score(&SESSION_ID, &player, &(args.score + 1), &honest),
+        Err(Ok(Error::JournalMismatch))
+    );
+}
@@ -0,0 +1,416 @@
+use crate::auth::{ApiKey, Auth, AuthError};
+use crate::diagnose::diagnose;
+use crate::jobs::{CancelError, JobId, JobQueue, JobRequest, JobStatus, Owner, Scope, SubmitError};
+use crate::openapi;
+use crate::prover::{decode_assumption, image_id_hex, Accelerator, Backend};
+use crate::schema::{
+    AcceleratorInfo, AdminJobsResponse, DiagnoseRequest, DrainResponse, ErrorResponse, HealthResponse, ImageIdResponse, InfoResponse,
+    JobResponse, JobStatusResponse, MetricsResponse, PausedResponse, ProveRequest, SimulateResponse, SimulatedResult,
+};
+use crate::submit::SubmitConfig;
+use crate::validate;
+use crate::webhook;
+use lane_racer_sim::{
+    find_overlong_pause, simulate_game, Action, GameInput, PackedActions, INPUT_VERSION, MAX_BASE_SPEED_PX, MAX_LANES, MAX_PAUSE_TICKS,
+    MAX_POINTS, MAX_SPEED, MIN_LANES,
+};
+use risc0_zkvm::VerifierContext;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response};
+use tracing::{info, info_span, warn};
+
+/// Largest request body accepted. Plain action arrays cost a few bytes per tick; long runs
+/// should use one of the compact encodings in [`crate::trace`].
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+type Reply = (u16, Value);
+
+/// State shared by every request handler.
+pub struct AppState {
+    pub queue: Arc<JobQueue>,
+    pub auth: Auth,
+    pub backend: Backend,
+    /// Hardware the local backend proves on; `None` for remote and dev backends.
+    pub accelerator: Option<Accelerator>,
+    /// Whether the server can honour `"submit": true`.
+    pub can_submit: bool,
+    /// Whether a webhook secret is configured for `callback_url`.
+    pub can_callback: bool,
+    /// Longest accepted action trace, in ticks.
+    pub max_actions: usize,
+    /// Contract proofs are bound to when a request names none: the `submit_score` target.
+    pub default_contract_id: Option<[u8; 32]>,
+    /// Set with `--check-sessions`: runs for the submit contract are checked against
+    /// their on-chain session before they are queued.
+    pub session_check: Option<SubmitConfig>,
+}
+
+impl ProveRequest {
+    /// Checks the request and builds the guest input, collecting every problem found.
+    fn into_input(self, state: &AppState) -> Result<GameInput, Reply> {
+        let max_actions = state.max_actions;
+        let mut details = Vec::new();
+        if let Some(version) = self.version.filter(|version| *version != INPUT_VERSION) {
+            details.push(format!("version {} is not supported, this prover accepts version {}", version, INPUT_VERSION));
+        }
+        match &self.player {
+            Some(player) => {
+                if let Err(e) = validate::decode_account(player) {
+                    details.push(e);
+                }
+            }
+            None => details.push("player is required".to_string()),
+        }
+        if self.seed.is_none() {
+            details.push("seed is required".to_string());
+        }
+        if self.session_id.is_none() {
+            details.push("session_id is required".to_string());
+        }
+        let actions = match self.actions.map(|trace| trace.decode(max_actions)) {
+            Some(Ok(actions)) if actions.len() > max_actions => {
+                details.push(format!("actions has {} entries, the maximum is {}", actions.len(), max_actions));
+                Vec::new()
+            }
+            Some(Ok(codes)) => match codes
+                .into_iter()
+                .enumerate()
+                .map(|(tick, code)| Action::try_from(code).map_err(|code| (tick, code)))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(actions) => {
+                    if let Some(tick) = find_overlong_pause(actions.iter().copied()) {
+                        details.push(format!("actions[{}] starts a pause longer than {} ticks", tick, MAX_PAUSE_TICKS));
+                    }
+                    actions
+                }
+                Err((tick, code)) => {
+                    let valid: Vec<u8> = Action::ALL.iter().map(|action| action.code()).collect();
+                    details.push(format!("actions[{}] is {}, expected one of {:?}", tick, code, valid));
+                    Vec::new()
+                }
+            },
+            Some(Err(e)) => {
+                details.push(e);
+                Vec::new()
+            }
+            None => {
+                details.push("actions is required".to_string());
+                Vec::new()
+            }
+        };
+        let contract_id = match (&self.contract_id, state.default_contract_id) {
+            (Some(contract_id), _) => validate::decode_contract(contract_id).unwrap_or_else(|e| {
+                details.push(e);
+                [0; 32]
+            }),
+            (None, Some(default)) => default,
+            (None, None) => {
+                details.push("contract_id is required".to_string());
+                [0; 32]
+            }
+        };
+        let config = self.config.unwrap_or_default();
+        if !config.is_valid() {
+            details.push(format!(
+                "config is invalid: lanes must be {}-{}, base_speed_px at most {}, speed_increment at most {}, \
+                 points at most {}, obstacles_per_speed_up positive, and chances within their scale",
+                MIN_LANES, MAX_LANES, MAX_BASE_SPEED_PX, MAX_SPEED, MAX_POINTS
+            ));
+        }
+        let Some((seed, session_id)) = self.seed.zip(self.session_id).filter(|_| details.is_empty()) else {
+            return Err(reply(422, ErrorResponse::invalid(details)));
+        };
+        Ok(GameInput {
+            version: INPUT_VERSION,
+            seed,
+            actions: PackedActions::pack(&actions),
+            player_address: self.player.unwrap_or_default(),
+            game_id: self.game_id.unwrap_or(session_id).into(),
+            session_id,
+            contract_id,
+            config,
+            difficulty: self.difficulty,
+        })
+    }
+}
+
+fn reply(status: u16, body: impl serde::Serialize) -> Reply {
+    (status, serde_json::to_value(body).expect("response body serializes"))
+}
+
+fn error(status: u16, message: impl std::fmt::Display) -> Reply {
+    reply(status, ErrorResponse::new(message))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn header_value<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn auth_error(e: AuthError) -> Reply {
+    match e {
+        AuthError::Unauthorized => error(401, "Missing or invalid API key"),
+        AuthError::RateLimited { retry_after_secs } => {
+            reply(429, ErrorResponse::new("Rate limit exceeded").retry_after(retry_after_secs))
+        }
+    }
+}
+
+/// Jobs `key` may see: its tenant's, or all of them when authentication is disabled, on a
+/// prover without tenants, and for admin keys without a tenant. Other keys without a
+/// tenant only see the jobs they submitted, so they cannot read the tenants' jobs.
+fn scope<'a>(auth: &Auth, key: Option<&'a ApiKey>) -> Scope<'a> {
+    match key {
+        None => Scope::All,
+        Some(ApiKey { tenant: Some(tenant), .. }) => Scope::Tenant(tenant),
+        Some(key) if key.admin || !auth.has_tenants() => Scope::All,
+        Some(key) => Scope::Key(&key.key),
+    }
+}
+
+fn authenticate<'a>(request: &Request, state: &'a AppState) -> Result<Option<&'a ApiKey>, Reply> {
+    state
+        .auth
+        .authenticate(header_value(request, "Authorization"), header_value(request, "X-API-Key"))
+        .map_err(auth_error)
+}
+
+/// Reads the full body, honouring Content-Length and chunked encoding, up to `MAX_BODY_BYTES`.
+fn read_body(request: &mut Request) -> Result<String, Reply> {
+    if request.body_length().is_some_and(|len| len > MAX_BODY_BYTES) {
+        return Err(error(413, "Request body too large"));
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| error(400, e))?;
+    if body.len() > MAX_BODY_BYTES {
+        return Err(error(413, "Request body too large"));
+    }
+    Ok(body)
+}
+
+/// Decodes and sanity-checks an assumption receipt so malformed ones fail with 422 instead
+/// of after the job is queued.
+fn parse_assumption(hex_receipt: &str) -> Result<Vec<u8>, Reply> {
+    let invalid = |e: &dyn std::fmt::Display| reply(422, ErrorResponse::invalid(vec![format!("assumption: {}", e)]));
+    let bytes = hex::decode(hex_receipt).map_err(|e| invalid(&e))?;
+    let receipt = decode_assumption(&bytes).map_err(|e| invalid(&e))?;
+    receipt.verify_integrity_with_context(&VerifierContext::default()).map_err(|e| invalid(&e))?;
+    Ok(bytes)
+}
+
+fn prove(http: &mut Request, state: &AppState, key: Option<&ApiKey>) -> Result<Reply, Reply> {
+    if let Some(key) = key {
+        state.auth.check_rate(key).map_err(auth_error)?;
+    }
+    let body = read_body(http)?;
+    let req: ProveRequest = serde_json::from_str(&body).map_err(|e| error(400, e))?;
+    if req.submit && !state.can_submit {
+        return Err(error(400, "Transaction submission is not configured on this prover"));
+    }
+    if let Some(url) = &req.callback_url {
+        if !state.can_callback {
+            return Err(error(400, "Callbacks are not configured on this prover"));
+        }
+        webhook::validate_url(url).map_err(|e| reply(422, ErrorResponse::invalid(vec![e])))?;
+    }
+    let submit = req.submit;
+    let assumption = req.assumption.as_deref().map(parse_assumption).transpose()?;
+    let callback_url = req.callback_url.clone();
+    let start_by = req.max_wait_secs.and_then(|secs| Instant::now().checked_add(Duration::from_secs(secs)));
+    let input = req.into_input(state)?;
+    if submit && Some(input.contract_id) != state.default_contract_id {
+        return Err(error(400, "A proof bound to another contract cannot be submitted by this prover"));
+    }
+    if let Some(check) = state.session_check.as_ref().filter(|_| Some(input.contract_id) == state.default_contract_id) {
+        match check.session_problem(&input) {
+            Ok(Some(problem)) => {
+                return Err(reply(409, ErrorResponse::new("The contract would refuse this run").with_details(vec![problem])));
+            }
+            Ok(None) => {}
+            // An RPC outage should not stop proving; the contract still checks on submission.
+            Err(e) => warn!(session_id = input.session_id, error = %e, "session check failed, proving anyway"),
+        }
+    }
+    let request = JobRequest { submit, assumption, callback_url, input, start_by };
+    let owner = key.map(|key| Owner {
+        key: key.key.clone(),
+        max_active: key.max_concurrent,
+        tier: key.tier,
+        account: key.account.clone(),
+        tenant: key.tenant.clone(),
+        tenant_max_active: state.auth.tenant(key).and_then(|tenant| tenant.max_concurrent),
+    });
+    let idempotency_key = header_value(http, "Idempotency-Key").map(str::to_string);
+    let job_id = state.queue.submit(request, owner, idempotency_key).map_err(|e| match e {
+        SubmitError::TooManyActiveJobs => error(429, "Too many concurrent jobs for this API key"),
+        SubmitError::TooManyTenantJobs => error(429, "Too many concurrent jobs for this tenant"),
+        SubmitError::QueueFull => error(503, "Proving queue is full, retry later"),
+        SubmitError::ShuttingDown => error(503, "Prover is shutting down"),
+        SubmitError::Paused => error(503, "Intake is paused by the operator, retry later"),
+        SubmitError::IdempotencyConflict => error(422, "Idempotency-Key was already used with a different request"),
+        SubmitError::DeadlineUnreachable { retry_after_secs } => {
+            reply(503, ErrorResponse::new("Job is unlikely to start within max_wait_secs").retry_after(retry_after_secs))
+        }
+    })?;
+    info!(job_id, "prove request accepted");
+    let status = state.queue.status(job_id).expect("job was just submitted");
+    // A deduplicated request may already be finished.
+    let code = if matches!(status, JobStatus::Done { .. }) { 200 } else { 202 };
+    Ok(reply(code, JobResponse { job_id, status }))
+}
+
+/// Runs the shared simulation natively and returns the result the guest would commit.
+fn simulate(request: &mut Request, state: &AppState) -> Result<Reply, Reply> {
+    let body = read_body(request)?;
+    let req: ProveRequest = serde_json::from_str(&body).map_err(|e| error(400, e))?;
+    let input = req.into_input(state)?;
+    let start = Instant::now();
+    let result = SimulatedResult(simulate_game(&input));
+    Ok(reply(200, SimulateResponse { result, simulate_time_ms: start.elapsed().as_secs_f64() * 1000.0 }))
+}
+
+/// Re-simulates a run and reports where it departs from the frontend's claimed score.
+fn diagnose_run(request: &mut Request, state: &AppState) -> Result<Reply, Reply> {
+    let body = read_body(request)?;
+    let req: DiagnoseRequest = serde_json::from_str(&body).map_err(|e| error(400, e))?;
+    let input = req.game.into_input(state)?;
+    Ok(reply(200, diagnose(&input, req.claimed_score, req.checkpoints)))
+}
+
+fn info(state: &AppState) -> Reply {
+    reply(
+        200,
+        InfoResponse {
+            image_id: image_id_hex(),
+            guest_version: methods::GUEST_VERSION,
+            sim_version: lane_racer_sim::SIM_VERSION,
+            host_version: env!("CARGO_PKG_VERSION"),
+            backend: state.backend,
+            accelerator: AcceleratorInfo {
+                selected: state.accelerator,
+                built: Accelerator::built(),
+                detected: Accelerator::ALL.into_iter().filter(|a| a.detected()).collect(),
+            },
+            seal_formats: state.backend.seal_formats(),
+            submit_enabled: state.can_submit,
+            callbacks_enabled: state.can_callback,
+        },
+    )
+}
+
+/// Metrics of the caller's tenant; unscoped callers also get a breakdown per tenant.
+fn metrics(queue: &JobQueue, scope: Scope) -> Reply {
+    let tenants = match scope {
+        Scope::All => queue.tenant_metrics(),
+        Scope::Tenant(_) | Scope::Key(_) => BTreeMap::new(),
+    };
+    reply(200, MetricsResponse { tiers: queue.metrics(scope), tenants })
+}
+
+fn job_status(id: &str, queue: &JobQueue, scope: Scope) -> Reply {
+    let Some((status, usage)) = id.parse::<JobId>().ok().and_then(|id| queue.status_with_usage(id, scope)) else {
+        return error(404, "Unknown job");
+    };
+    reply(200, JobStatusResponse { status, usage })
+}
+
+/// Queue management for operators: `GET /admin/jobs` lists unfinished jobs,
+/// `POST /admin/jobs/<id>/cancel` cancels one, `POST /admin/pause` and `/admin/resume`
+/// stop and restart intake, and `POST /admin/drain` cancels every queued job. Only keys
+/// with `admin` set may call them, so they are unavailable without a key file. Admin keys
+/// of a tenant manage only its jobs and cannot pause the shared intake.
+fn admin(method: &Method, path: &str, state: &AppState, key: Option<&ApiKey>) -> Result<Reply, Reply> {
+    let Some(key) = key.filter(|key| key.admin) else {
+        return Err(error(403, "Admin routes require an admin API key"));
+    };
+    let scope = scope(&state.auth, Some(key));
+    let queue = &state.queue;
+    match (method, path) {
+        (Method::Get, "/admin/jobs") => Ok(reply(200, AdminJobsResponse { paused: queue.is_paused(), jobs: queue.active_jobs(scope) })),
+        (Method::Post, "/admin/pause" | "/admin/resume") if key.tenant.is_some() => {
+            Err(error(403, "Pausing intake requires an admin API key without a tenant"))
+        }
+        (Method::Post, "/admin/pause" | "/admin/resume") => {
+            let paused = path == "/admin/pause";
+            queue.set_paused(paused);
+            info!(paused, "intake updated by operator");
+            Ok(reply(200, PausedResponse { paused }))
+        }
+        (Method::Post, "/admin/drain") => Ok(reply(200, DrainResponse { cancelled: queue.drain(scope) })),
+        (Method::Post, path) if path.starts_with("/admin/jobs/") => {
+            let id = path.strip_prefix("/admin/jobs/").and_then(|rest| rest.strip_suffix("/cancel"));
+            let Some(id) = id.and_then(|id| id.parse::<JobId>().ok()) else { return Err(error(404, "Unknown job")) };
+            match queue.cancel(id, scope) {
+                Some(Ok(status)) => {
+                    info!(job_id = id, "job cancelled by operator");
+                    Ok(reply(200, JobResponse { job_id: id, status }))
+                }
+                Some(Err(CancelError::Finished)) => Err(error(409, "Job already finished")),
+                None => Err(error(404, "Unknown job")),
+            }
+        }
+        (_, "/admin/jobs" | "/admin/pause" | "/admin/resume" | "/admin/drain") => Err(error(405, "Method not allowed")),
+        _ => Err(error(404, "Unknown route")),
+    }
+}
+
+fn route(request: &mut Request, state: &AppState) -> Result<Reply, Reply> {
+    let method = request.method().clone();
+    let path = request.url().split('?').next().unwrap_or_default().to_string();
+    match (&method, path.as_str()) {
+        (Method::Options, _) => return Ok((204, Value::Null)),
+        (Method::Get, "/health") => return Ok(reply(200, HealthResponse { status: "ok" })),
+        (Method::Get, "/info") => return Ok(info(state)),
+        (Method::Get, "/image_id") => return Ok(reply(200, ImageIdResponse { image_id: image_id_hex() })),
+        (Method::Get, "/openapi.json") => return Ok((200, openapi::document().clone())),
+        _ => {}
+    }
+    let key = authenticate(request, state)?;
+    match (&method, path.as_str()) {
+        (Method::Post, "/prove") => prove(request, state, key),
+        (Method::Post, "/simulate") => simulate(request, state),
+        (Method::Post, "/diagnose") => diagnose_run(request, state),
+        (Method::Get, "/metrics") => Ok(metrics(&state.queue, scope(&state.auth, key))),
+        (Method::Get, path) if path.starts_with("/jobs/") => Ok(job_status(&path["/jobs/".len()..], &state.queue, scope(&state.auth, key))),
+        (_, path) if path.starts_with("/admin/") => admin(&method, path, state, key),
+        (_, "/health" | "/info" | "/image_id" | "/openapi.json" | "/prove" | "/simulate" | "/diagnose" | "/metrics") => {
+            Err(error(405, "Method not allowed"))
+        }
+        _ => Err(error(404, "Unknown route")),
+    }
+}
+
+/// Routes one request and writes its JSON response with CORS headers.
+pub fn handle(mut request: Request, state: &AppState) {
+    let span = info_span!("request", method = %request.method(), path = %request.url());
+    let _enter = span.enter();
+    let start = Instant::now();
+    let (status, body) = route(&mut request, state).unwrap_or_else(|reply| reply);
+    info!(status, elapsed_ms = start.elapsed().as_millis() as u64, "request handled");
+    let body = if body.is_null() { String::new() } else { body.to_string() };
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"))
+        .with_header(header("Access-Control-Allow-Headers", "Content-Type, Authorization, X-API-Key, Idempotency-Key"));
+    if let Err(e) = request.respond(response) {
+        warn!(error = %e, "failed to send response");
+    }
+}
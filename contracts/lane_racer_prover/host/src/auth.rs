@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use crate::jobs::Tier;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+fn default_max_concurrent() -> usize {
+    1
+}
+
+fn default_requests_per_minute() -> usize {
+    10
+}
+
+/// One entry of the API key file, a JSON array of these objects.
+#[derive(Clone, serde::Deserialize)]
+pub struct ApiKey {
+    pub key: String,
+    /// Jobs this key may have queued or proving at once.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Proof requests this key may submit per rolling minute.
+    #[serde(default = "default_requests_per_minute")]
+    pub requests_per_minute: usize,
+    /// Scheduling tier: `casual`, `standard` or `tournament`.
+    #[serde(default)]
+    pub tier: Tier,
+    /// Billing account charged for this key's proofs, reported to the billing webhook.
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Grants the `/admin` queue management routes.
+    #[serde(default)]
+    pub admin: bool,
+    /// Game or studio the key belongs to. A tenant's keys only see its jobs and metrics,
+    /// and share its limits. Keys without one see every job on a prover without tenants;
+    /// once tenants are configured they only see the jobs they submitted, unless `admin`.
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+/// Limits shared by all keys of a tenant, on top of each key's own.
+#[derive(Clone, serde::Deserialize)]
+pub struct Tenant {
+    pub id: String,
+    /// Jobs the tenant's keys may have queued or proving at once, together.
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+    /// Proof requests the tenant's keys may submit per rolling minute, together.
+    #[serde(default)]
+    pub requests_per_minute: Option<usize>,
+}
+
+/// The API key file: a JSON array of keys, or an object also listing the tenants they
+/// belong to. With a `tenants` list, every key's tenant must be in it.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum KeyFile {
+    Keys(Vec<ApiKey>),
+    Tenants { tenants: Vec<Tenant>, keys: Vec<ApiKey> },
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    /// No key was presented or it is not in the key file.
+    Unauthorized,
+    /// The key exhausted its request budget; retry after the given number of seconds.
+    RateLimited { retry_after_secs: u64 },
+}
+
+/// API key registry with per-key request rate tracking. Without a key file the prover is
+/// open, which is only suitable for local development.
+#[derive(Default)]
+pub struct Auth {
+    keys: Option<HashMap<String, ApiKey>>,
+    tenants: HashMap<String, Tenant>,
+    recent: Mutex<HashMap<String, VecDeque<Instant>>>,
+    /// Requests per tenant, kept apart from `recent` so tenant IDs and keys cannot clash.
+    tenant_recent: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl Auth {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let file: KeyFile = serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?;
+        let (keys, tenants) = match file {
+            KeyFile::Keys(keys) => (keys, HashMap::new()),
+            KeyFile::Tenants { tenants, keys } => {
+                let tenants: HashMap<String, Tenant> = tenants.into_iter().map(|tenant| (tenant.id.clone(), tenant)).collect();
+                if let Some(id) = keys.iter().filter_map(|key| key.tenant.as_ref()).find(|id| !tenants.contains_key(*id)) {
+                    anyhow::bail!("{}: tenant {:?} of an API key is not listed in tenants", path.display(), id);
+                }
+                (keys, tenants)
+            }
+        };
+        Ok(Self {
+            keys: Some(keys.into_iter().map(|key| (key.key.clone(), key)).collect()),
+            tenants,
+            recent: Mutex::default(),
+            tenant_recent: Mutex::default(),
+        })
+    }
+
+    /// Limits of the tenant `key` belongs to, if it has a tenant with limits.
+    pub fn tenant(&self, key: &ApiKey) -> Option<&Tenant> {
+        self.tenants.get(key.tenant.as_ref()?)
+    }
+
+    /// Whether the key file lists tenants.
+    pub fn has_tenants(&self) -> bool {
+        !self.tenants.is_empty()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.keys.is_some()
+    }
+
+    /// Resolves the key from an `Authorization: Bearer <key>` or `X-API-Key` header value.
+    /// Returns `None` when authentication is disabled.
+    pub fn authenticate(&self, bearer: Option<&str>, api_key: Option<&str>) -> Result<Option<&ApiKey>, AuthError> {
+        let Some(keys) = &self.keys else { return Ok(None) };
+        let presented = bearer.and_then(|value| value.strip_prefix("Bearer ")).or(api_key);
+        presented
+            .and_then(|key| keys.get(key.trim()))
+            .map(Some)
+            .ok_or(AuthError::Unauthorized)
+    }
+
+    /// Records a proof request for `key`, failing when it exceeds its own or its tenant's
+    /// per-minute budget. A refused request counts against neither.
+    pub fn check_rate(&self, key: &ApiKey) -> Result<(), AuthError> {
+        let now = Instant::now();
+        let mut recent = self.recent.lock().unwrap();
+        let mut tenant_recent = self.tenant_recent.lock().unwrap();
+        let window = recent.entry(key.key.clone()).or_default();
+        check_window(window, key.requests_per_minute, now)?;
+        let tenant = self.tenant(key).and_then(|tenant| Some((tenant.id.clone(), tenant.requests_per_minute?)));
+        if let Some((id, limit)) = tenant {
+            let tenant_window = tenant_recent.entry(id).or_default();
+            check_window(tenant_window, limit, now)?;
+            tenant_window.push_back(now);
+        }
+        window.push_back(now);
+        Ok(())
+    }
+}
+
+/// Forgets requests older than `RATE_WINDOW` and fails if `limit` remain.
+fn check_window(window: &mut VecDeque<Instant>, limit: usize, now: Instant) -> Result<(), AuthError> {
+    while window.front().is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW) {
+        window.pop_front();
+    }
+    if window.len() >= limit {
+        let oldest = window.front().copied().unwrap_or(now);
+        let retry_after = RATE_WINDOW.saturating_sub(now.duration_since(oldest));
+        return Err(AuthError::RateLimited { retry_after_secs: retry_after.as_secs().max(1) });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str, requests_per_minute: usize, tenant: Option<&str>) -> ApiKey {
+        ApiKey {
+            key: key.to_string(),
+            max_concurrent: 1,
+            requests_per_minute,
+            tier: Tier::default(),
+            account: None,
+            admin: false,
+            tenant: tenant.map(str::to_string),
+        }
+    }
+
+    fn with_tenant(requests_per_minute: usize) -> Auth {
+        let tenant = Tenant { id: "studio".to_string(), max_concurrent: None, requests_per_minute: Some(requests_per_minute) };
+        Auth { tenants: HashMap::from([(tenant.id.clone(), tenant)]), ..Auth::default() }
+    }
+
+    #[test]
+    fn a_key_is_limited_to_its_requests_per_minute() {
+        let auth = Auth::default();
+        let key = key("a", 2, None);
+        assert!(auth.check_rate(&key).is_ok());
+        assert!(auth.check_rate(&key).is_ok());
+        let Err(AuthError::RateLimited { retry_after_secs }) = auth.check_rate(&key) else {
+            panic!("third request within a minute was accepted");
+        };
+        assert!((1..=60).contains(&retry_after_secs));
+    }
+
+    #[test]
+    fn keys_have_separate_budgets() {
+        let auth = Auth::default();
+        assert!(auth.check_rate(&key("a", 1, None)).is_ok());
+        assert!(auth.check_rate(&key("b", 1, None)).is_ok());
+        assert!(auth.check_rate(&key("a", 1, None)).is_err());
+    }
+
+    #[test]
+    fn a_tenant_budget_is_shared_by_its_keys() {
+        let auth = with_tenant(2);
+        assert!(auth.check_rate(&key("a", 5, Some("studio"))).is_ok());
+        assert!(auth.check_rate(&key("b", 5, Some("studio"))).is_ok());
+        assert!(auth.check_rate(&key("c", 5, Some("studio"))).is_err());
+        assert!(auth.check_rate(&key("d", 5, None)).is_ok());
+    }
+
+    #[test]
+    fn a_refused_request_counts_against_neither_budget() {
+        let auth = with_tenant(2);
+        let limited = key("a", 1, Some("studio"));
+        assert!(auth.check_rate(&limited).is_ok());
+        assert!(auth.check_rate(&limited).is_err());
+        // The key's refusal did not use up the tenant's second request.
+        assert!(auth.check_rate(&key("b", 5, Some("studio"))).is_ok());
+    }
+
+    #[test]
+    fn requests_older_than_the_window_are_forgotten() {
+        let Some(long_ago) = Instant::now().checked_sub(RATE_WINDOW) else { return };
+        let mut window = VecDeque::from([long_ago]);
+        assert!(check_window(&mut window, 1, Instant::now()).is_ok());
+        assert!(window.is_empty());
+    }
+}
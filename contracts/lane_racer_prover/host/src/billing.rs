@@ -0,0 +1,69 @@
+use crate::jobs::{JobId, JobStatus};
+use crate::prover::Backend;
+use crate::webhook;
+use std::time::Duration;
+
+/// Pricing and the optional billing webhook, for operators charging studios per proof.
+#[derive(Clone, clap::Args)]
+pub struct BillingConfig {
+    /// Price of each successful proof, in the operator's billing currency.
+    #[arg(long = "price-per-proof", default_value_t = 0.0)]
+    pub price_per_proof: f64,
+
+    /// Price per million proven cycles, added to `--price-per-proof`.
+    #[arg(long = "price-per-mcycle", default_value_t = 0.0)]
+    pub price_per_mcycle: f64,
+
+    /// URL receiving a signed usage record for every job a worker ran.
+    #[arg(long = "billing-webhook-url", requires = "billing_secret")]
+    pub webhook_url: Option<String>,
+
+    /// Secret for signing billing records, sent as `X-Signature-256` like job callbacks.
+    #[arg(long = "billing-webhook-secret", id = "billing_secret", env = "PROVER_BILLING_SECRET", hide_env_values = true)]
+    pub webhook_secret: Option<String>,
+}
+
+/// Resources a job consumed, reported by `GET /jobs/<id>` and the billing webhook.
+/// Jobs answered from a previous proof ran nothing and carry no usage.
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct Usage {
+    pub backend: Backend,
+    /// Cycles proven; zero for failed jobs.
+    pub total_cycles: u64,
+    pub segments: usize,
+    /// Time the job waited for a worker.
+    pub queue_wait_secs: f64,
+    /// Time the worker spent on the job, including on-chain submission.
+    pub wall_time_secs: f64,
+    /// Price under the configured pricing. Failed jobs are not charged.
+    pub cost: f64,
+}
+
+impl BillingConfig {
+    /// Prices a finished job from its final status and timings.
+    pub fn usage(&self, backend: Backend, status: &JobStatus, queue_wait: Duration, wall_time: Duration) -> Usage {
+        let (total_cycles, segments, cost) = match status {
+            JobStatus::Done { result } => {
+                let cycles = result.stats.total_cycles;
+                (cycles, result.stats.segments, self.price_per_proof + cycles as f64 / 1e6 * self.price_per_mcycle)
+            }
+            _ => (0, 0, 0.0),
+        };
+        Usage {
+            backend,
+            total_cycles,
+            segments,
+            queue_wait_secs: queue_wait.as_secs_f64(),
+            wall_time_secs: wall_time.as_secs_f64(),
+            cost,
+        }
+    }
+
+    /// Sends the usage of job `id` to the billing webhook, if one is configured. `account`
+    /// is the billing account of the API key that submitted the job.
+    pub fn report(&self, id: JobId, account: Option<String>, usage: &Usage) {
+        let (Some(url), Some(secret)) = (&self.webhook_url, &self.webhook_secret) else { return };
+        let body = serde_json::json!({ "job_id": id, "account": account, "usage": usage });
+        webhook::post_signed(url.clone(), secret.clone(), id, body.to_string(), "billing");
+    }
+}
@@ -0,0 +1,115 @@
+use lane_racer_sim::{simulate_game_with, GameInput, GameResult, TickState};
+use std::collections::BTreeMap;
+
+/// Frontend state at one tick. Fields left out are not compared.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct Checkpoint {
+    pub tick: u32,
+    pub lane: Option<u8>,
+    pub score: Option<u32>,
+    pub obstacles_dodged: Option<u32>,
+    pub gems_collected: Option<u32>,
+    pub speed: Option<u32>,
+}
+
+/// A checkpoint field that disagrees with the guest.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct Mismatch {
+    /// The guest's value.
+    pub expected: u32,
+    /// The frontend's value.
+    pub actual: u32,
+}
+
+/// The first checkpoint that disagrees with the guest.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct Divergence {
+    pub tick: u32,
+    pub fields: BTreeMap<&'static str, Mismatch>,
+    /// The guest's full state at `tick`.
+    pub expected_state: TickState,
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct ScoreChange {
+    pub tick: u32,
+    pub score: u32,
+}
+
+/// Body of `POST /diagnose`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct Diagnosis {
+    /// The claimed score matches and no checkpoint disagrees.
+    #[serde(rename = "match")]
+    pub matches: bool,
+    pub claimed_score: u32,
+    pub expected_score: u32,
+    pub result: GameResult,
+    pub first_divergence: Option<Divergence>,
+    /// Checkpoints past the tick where the guest stopped (collision or end of trace).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreached_checkpoints: Vec<u32>,
+    /// Ticks at which the guest's score changed; only sent for mismatches without
+    /// checkpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_changes: Option<Vec<ScoreChange>>,
+}
+
+impl Checkpoint {
+    /// Fields that differ from the guest's state.
+    fn mismatches(&self, state: &TickState) -> BTreeMap<&'static str, Mismatch> {
+        let mut fields = BTreeMap::new();
+        let mut compare = |name: &'static str, actual: Option<u32>, expected: u32| {
+            if let Some(actual) = actual.filter(|actual| *actual != expected) {
+                fields.insert(name, Mismatch { expected, actual });
+            }
+        };
+        compare("lane", self.lane.map(u32::from), state.lane as u32);
+        compare("score", self.score, state.score);
+        compare("obstacles_dodged", self.obstacles_dodged, state.obstacles_dodged);
+        compare("gems_collected", self.gems_collected, state.gems_collected);
+        compare("speed", self.speed, state.speed);
+        fields
+    }
+}
+
+/// Re-simulates `input` and compares it with what the frontend reported. `expected` values
+/// are the guest's, which is what a proof will commit; `actual` values are the frontend's.
+///
+/// With checkpoints, the first checkpoint that disagrees is reported with the guest's
+/// full state at that tick. Without them, the ticks at which the guest's score changed
+/// are listed so they can be lined up against frontend logs by hand.
+pub fn diagnose(input: &GameInput, claimed_score: u32, mut checkpoints: Vec<Checkpoint>) -> Diagnosis {
+    checkpoints.sort_by_key(|checkpoint| checkpoint.tick);
+    let mut pending = checkpoints.iter().peekable();
+    let mut divergence = None;
+    let mut score_changes = Vec::new();
+    let mut last_score = 0;
+    let result = simulate_game_with(input, |state| {
+        if state.score != last_score {
+            score_changes.push(ScoreChange { tick: state.tick, score: state.score });
+            last_score = state.score;
+        }
+        while let Some(checkpoint) = pending.next_if(|checkpoint| checkpoint.tick <= state.tick) {
+            // Ticks are reported in order, so a checkpoint taken here is for this tick.
+            if divergence.is_some() {
+                continue;
+            }
+            let fields = checkpoint.mismatches(state);
+            if !fields.is_empty() {
+                divergence = Some(Divergence { tick: state.tick, fields, expected_state: state.clone() });
+            }
+        }
+    });
+    let unreached_checkpoints = pending.map(|checkpoint| checkpoint.tick).collect();
+    let matches = result.score == claimed_score && divergence.is_none();
+    Diagnosis {
+        matches,
+        claimed_score,
+        expected_score: result.score,
+        result,
+        first_divergence: divergence,
+        unreached_checkpoints,
+        score_changes: (checkpoints.is_empty() && !matches).then_some(score_changes),
+    }
+}
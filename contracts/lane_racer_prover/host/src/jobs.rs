@@ -0,0 +1,995 @@
+use crate::billing::{BillingConfig, Usage};
+use crate::prover::{decode_assumption, prove_game, Backend, ProofResponse};
+use crate::store::{JobRecord, JobStore, OutcomeRecord, Recovered};
+use crate::submit::SubmitConfig;
+use crate::webhook;
+use sha2::{Digest as _, Sha256};
+use shared::GameInput;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{error, info, info_span, warn};
+
+pub type JobId = u64;
+
+/// SHA-256 of a canonically encoded `JobRequest`.
+type RequestHash = [u8; 32];
+
+/// Most finished jobs remembered for deduplication; older entries are forgotten first.
+const DEDUP_CAPACITY: usize = 1024;
+
+/// Most idempotency keys remembered; older keys are forgotten first.
+const IDEMPOTENCY_CAPACITY: usize = 4096;
+
+/// Idempotency keys are scoped to the API key that presented them.
+type IdempotencyKey = (Option<String>, String);
+
+/// A job waiting at least this long is started ahead of newer jobs from higher tiers, so
+/// a steady stream of priority work cannot starve casual runs.
+const STARVATION_LIMIT: Duration = Duration::from_secs(10 * 60);
+
+/// Error of jobs an operator cancelled through the admin routes.
+const CANCELLED: &str = "Cancelled by an operator";
+
+/// Scheduling tier of an API key; pending jobs of higher tiers start first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Casual,
+    #[default]
+    Standard,
+    Tournament,
+}
+
+/// Per-tier counters reported by `GET /metrics`.
+#[derive(Clone, Default, serde::Serialize, schemars::JsonSchema)]
+pub struct TierMetrics {
+    pub queued: usize,
+    pub proving: usize,
+    pub submitted: u64,
+    pub completed: u64,
+    pub failed: u64,
+    /// Mean time jobs of this tier waited before a worker started them.
+    pub avg_wait_secs: f64,
+    /// Mean cycles and segments of this tier's completed proofs.
+    pub avg_total_cycles: f64,
+    pub avg_segments: f64,
+    #[serde(skip)]
+    started: u64,
+    #[serde(skip)]
+    total_wait: Duration,
+    #[serde(skip)]
+    total_cycles: u64,
+    #[serde(skip)]
+    total_segments: u64,
+}
+
+impl TierMetrics {
+    /// Adds the counters of `other`; queue depths and averages are filled in afterwards.
+    fn merge(&mut self, other: &TierMetrics) {
+        self.submitted += other.submitted;
+        self.completed += other.completed;
+        self.failed += other.failed;
+        self.started += other.started;
+        self.total_wait += other.total_wait;
+        self.total_cycles += other.total_cycles;
+        self.total_segments += other.total_segments;
+    }
+}
+
+/// The jobs a caller may see and manage: every job, those of one tenant, or those one
+/// API key outside any tenant submitted.
+#[derive(Clone, Copy)]
+pub enum Scope<'a> {
+    All,
+    Tenant(&'a str),
+    /// Jobs without a tenant submitted by this key. Its metrics are those of every job
+    /// without a tenant, as counters are only kept per tenant.
+    Key(&'a str),
+}
+
+impl Scope<'_> {
+    fn includes(self, job: &Job) -> bool {
+        match self {
+            Scope::Key(key) => job.tenant.is_none() && job.owner.as_deref() == Some(key),
+            _ => self.includes_tenant(job.tenant.as_deref()),
+        }
+    }
+
+    fn includes_tenant(self, tenant: Option<&str>) -> bool {
+        match self {
+            Scope::All => true,
+            Scope::Tenant(scope) => tenant == Some(scope),
+            Scope::Key(_) => tenant.is_none(),
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Proving,
+    Done { result: ProofResponse },
+    Failed { error: String },
+}
+
+/// Everything a worker needs to run one job.
+pub struct JobRequest {
+    pub input: GameInput,
+    /// Submit `submit_score` on-chain once the proof is ready.
+    pub submit: bool,
+    /// Bincode-encoded receipt added as a composition assumption.
+    pub assumption: Option<Vec<u8>>,
+    /// URL notified with the final status, signed with the webhook secret.
+    pub callback_url: Option<String>,
+    /// Latest time a worker may start the job; it fails instead of starting later. Not
+    /// persisted, so jobs resumed after a restart have no deadline.
+    pub start_by: Option<Instant>,
+}
+
+impl JobRequest {
+    /// Hashes the fields that determine the proof with a fixed, length-prefixed layout, so
+    /// retries of the same run map to the same key regardless of JSON formatting. Tenants
+    /// never share proofs, so the tenant is hashed too.
+    fn hash(&self, tenant: Option<&str>) -> RequestHash {
+        let input = &self.input;
+        let mut hasher = Sha256::new();
+        hasher.update(input.seed.to_le_bytes());
+        hasher.update(input.game_id.to_le_bytes());
+        hasher.update(input.session_id.to_le_bytes());
+        hasher.update(input.contract_id);
+        hasher.update((input.player_address.len() as u64).to_le_bytes());
+        hasher.update(input.player_address.as_bytes());
+        hasher.update((input.actions.len() as u64).to_le_bytes());
+        hasher.update(input.actions.as_bytes());
+        hasher.update(input.config.to_bytes());
+        hasher.update([input.difficulty.code()]);
+        hasher.update([self.submit as u8]);
+        if let Some(assumption) = &self.assumption {
+            hasher.update((assumption.len() as u64).to_le_bytes());
+            hasher.update(assumption);
+        }
+        // Requests differing only in callback are separate jobs, so each callback fires.
+        if let Some(url) = &self.callback_url {
+            hasher.update((url.len() as u64).to_le_bytes());
+            hasher.update(url.as_bytes());
+        }
+        // Untenanted requests hash as they did before tenants existed, so stored proofs
+        // still deduplicate.
+        if let Some(tenant) = tenant {
+            hasher.update((tenant.len() as u64).to_le_bytes());
+            hasher.update(tenant.as_bytes());
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Who submitted a job, how many unfinished jobs they may have at once, their tier, the
+/// account billed for the job, and their tenant with its limit on unfinished jobs.
+pub struct Owner {
+    pub key: String,
+    pub max_active: usize,
+    pub tier: Tier,
+    pub account: Option<String>,
+    pub tenant: Option<String>,
+    pub tenant_max_active: Option<usize>,
+}
+
+/// An unfinished job as listed by `GET /admin/jobs`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct JobSummary {
+    pub job_id: JobId,
+    #[serde(flatten)]
+    pub status: JobStatus,
+    pub tier: Tier,
+    pub account: Option<String>,
+    pub tenant: Option<String>,
+    /// Cancelled while proving; the result will be discarded.
+    pub cancelling: bool,
+}
+
+#[derive(Debug)]
+pub enum CancelError {
+    /// The job already finished.
+    Finished,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The owner already has `max_active` jobs queued or proving.
+    TooManyActiveJobs,
+    /// The owner's tenant already has `tenant_max_active` jobs queued or proving.
+    TooManyTenantJobs,
+    /// The queue already holds its maximum number of pending jobs.
+    QueueFull,
+    /// The queue is closed because the prover is shutting down.
+    ShuttingDown,
+    /// An operator paused intake.
+    Paused,
+    /// The idempotency key was already used for a different request.
+    IdempotencyConflict,
+    /// The job is unlikely to start before its deadline; the queue should have drained
+    /// enough after the given number of seconds.
+    DeadlineUnreachable { retry_after_secs: u64 },
+}
+
+struct Job {
+    owner: Option<String>,
+    tier: Tier,
+    account: Option<String>,
+    tenant: Option<String>,
+    hash: RequestHash,
+    status: JobStatus,
+    /// Set once a worker has finished the job.
+    usage: Option<Usage>,
+}
+
+struct Pending {
+    id: JobId,
+    request: JobRequest,
+    queued_at: Instant,
+}
+
+impl Job {
+    fn is_active(&self) -> bool {
+        matches!(self.status, JobStatus::Queued | JobStatus::Proving)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    next_id: JobId,
+    pending: BTreeMap<Tier, VecDeque<Pending>>,
+    jobs: HashMap<JobId, Job>,
+    /// Latest job for each request hash, used to answer duplicate requests.
+    by_hash: HashMap<RequestHash, JobId>,
+    /// Finished hashes in completion order, bounding `by_hash` to `DEDUP_CAPACITY`.
+    finished: VecDeque<RequestHash>,
+    /// Job created for each idempotency key, with the hash of the request that created it.
+    by_idempotency_key: HashMap<IdempotencyKey, (JobId, RequestHash)>,
+    /// Idempotency keys in insertion order, bounding `by_idempotency_key`.
+    idempotency_keys: VecDeque<IdempotencyKey>,
+    /// Counters per tenant and tier; jobs without a tenant count under `None`.
+    metrics: BTreeMap<(Option<String>, Tier), TierMetrics>,
+    /// Total worker time of completed jobs and their count, for estimating queue waits.
+    run_time: Duration,
+    runs: u32,
+    /// Set on shutdown; no new jobs are accepted or started.
+    closed: bool,
+    /// Set by an operator; no new jobs are accepted, queued jobs still start.
+    paused: bool,
+    /// Proving jobs an operator cancelled; their results are discarded when they finish.
+    cancelled: HashSet<JobId>,
+}
+
+impl State {
+    fn restore(&mut self, recovered: Recovered) {
+        for (id, record) in recovered.finished {
+            let Some(hash) = hex::decode(&record.hash).ok().and_then(|hash| RequestHash::try_from(hash).ok()) else {
+                continue;
+            };
+            self.next_id = self.next_id.max(id);
+            self.jobs.insert(
+                id,
+                Job {
+                    owner: record.owner,
+                    tier: record.tier,
+                    account: None,
+                    tenant: record.tenant,
+                    hash,
+                    status: record.status,
+                    usage: record.usage,
+                },
+            );
+            self.by_hash.insert(hash, id);
+            self.finished.push_back(hash);
+        }
+        while self.finished.len() > DEDUP_CAPACITY {
+            let expired = self.finished.pop_front().expect("finished is not empty");
+            self.by_hash.remove(&expired);
+        }
+        for (id, record) in recovered.unfinished {
+            let Ok(assumption) = record.assumption.as_deref().map(hex::decode).transpose() else { continue };
+            let request = JobRequest {
+                input: record.input,
+                submit: record.submit,
+                assumption,
+                callback_url: record.callback_url,
+                start_by: None,
+            };
+            let hash = request.hash(record.tenant.as_deref());
+            self.next_id = self.next_id.max(id);
+            self.jobs.insert(
+                id,
+                Job {
+                    owner: record.owner,
+                    tier: record.tier,
+                    account: record.account,
+                    tenant: record.tenant,
+                    hash,
+                    status: JobStatus::Queued,
+                    usage: None,
+                },
+            );
+            self.by_hash.insert(hash, id);
+            self.pending.entry(record.tier).or_default().push_back(Pending { id, request, queued_at: Instant::now() });
+        }
+    }
+
+    fn insert(&mut self, owner: Option<Owner>, hash: RequestHash, status: JobStatus) -> JobId {
+        self.next_id += 1;
+        let id = self.next_id;
+        let tier = owner.as_ref().map_or_else(Tier::default, |owner| owner.tier);
+        let (owner, account, tenant) = owner.map_or((None, None, None), |owner| (Some(owner.key), owner.account, owner.tenant));
+        self.jobs.insert(id, Job { owner, tier, account, tenant, hash, status, usage: None });
+        self.by_hash.insert(hash, id);
+        id
+    }
+
+    fn pending_len(&self) -> usize {
+        self.pending.values().map(VecDeque::len).sum()
+    }
+
+    /// Rough time until a new job of `tier` starts: the jobs it would queue behind and
+    /// those proving, spread over `workers` at the mean run time so far. Zero until a job
+    /// has completed, as there is nothing to estimate from.
+    fn estimated_wait(&self, tier: Tier, workers: usize) -> Duration {
+        if self.runs == 0 {
+            return Duration::ZERO;
+        }
+        let ahead: usize = self.pending.range(tier..).map(|(_, queue)| queue.len()).sum();
+        let proving = self.jobs.values().filter(|job| matches!(job.status, JobStatus::Proving)).count();
+        let waiting = (ahead + proving + 1).saturating_sub(workers);
+        (self.run_time / self.runs).mul_f64(waiting as f64 / workers.max(1) as f64)
+    }
+
+    /// Removes a queued job from the pending queues and fails it as cancelled.
+    fn cancel_pending(&mut self, id: JobId) -> bool {
+        let Some(job) = self.jobs.get_mut(&id).filter(|job| matches!(job.status, JobStatus::Queued)) else {
+            return false;
+        };
+        job.status = JobStatus::Failed { error: CANCELLED.to_string() };
+        let (hash, tier, tenant) = (job.hash, job.tier, job.tenant.clone());
+        if let Some(queue) = self.pending.get_mut(&tier) {
+            queue.retain(|pending| pending.id != id);
+        }
+        self.metrics.entry((tenant, tier)).or_default().failed += 1;
+        self.record_finished(hash);
+        true
+    }
+
+    /// Remembers a finished request hash, forgetting the oldest beyond `DEDUP_CAPACITY`.
+    fn record_finished(&mut self, hash: RequestHash) {
+        self.finished.push_back(hash);
+        if self.finished.len() > DEDUP_CAPACITY {
+            let expired = self.finished.pop_front().expect("finished is not empty");
+            // Only forget the hash if no newer job has claimed it since.
+            if self.by_hash.get(&expired).is_some_and(|id| self.jobs.get(id).is_some_and(|job| !job.is_active())) {
+                self.by_hash.remove(&expired);
+            }
+        }
+    }
+
+    /// Picks the oldest starved job if any, otherwise the oldest job of the highest tier.
+    fn next_pending(&mut self) -> Option<Pending> {
+        let now = Instant::now();
+        let starved = self
+            .pending
+            .iter()
+            .filter_map(|(tier, queue)| queue.front().map(|job| (*tier, job.queued_at)))
+            .filter(|(_, queued_at)| now.duration_since(*queued_at) >= STARVATION_LIMIT)
+            .min_by_key(|(_, queued_at)| *queued_at)
+            .map(|(tier, _)| tier);
+        let tier = starved.or_else(|| self.pending.iter().rev().find(|(_, queue)| !queue.is_empty()).map(|(tier, _)| *tier))?;
+        self.pending.get_mut(&tier)?.pop_front()
+    }
+}
+
+/// In-process proving queue. Handlers submit inputs and poll by job ID; worker threads
+/// take jobs by tier, FIFO within a tier, and record the outcome.
+///
+/// Identical requests are deduplicated: one that matches a queued or proving job returns
+/// that job, and one that matches a finished proof gets a new job that is already done,
+/// with `cache_hit` set on its result.
+///
+/// With a [`JobStore`], jobs are persisted as they are queued and finished, and jobs that
+/// were queued or proving when the prover stopped are queued again on startup.
+pub struct JobQueue {
+    state: Mutex<State>,
+    ready: Condvar,
+    max_pending: usize,
+    /// Worker threads proving from the queue, for wait estimates.
+    workers: usize,
+    store: Option<JobStore>,
+}
+
+impl JobQueue {
+    /// Creates a queue holding at most `max_pending` jobs waiting for one of `workers`,
+    /// restoring jobs from `store` if given. Restored jobs are queued regardless of
+    /// `max_pending`.
+    pub fn new(max_pending: usize, workers: usize, store: Option<JobStore>) -> anyhow::Result<Self> {
+        let mut state = State::default();
+        if let Some(store) = &store {
+            let recovered = store.recover()?;
+            info!(unfinished = recovered.unfinished.len(), finished = recovered.finished.len(), "recovered jobs");
+            state.restore(recovered);
+        }
+        Ok(Self { state: Mutex::new(state), ready: Condvar::new(), max_pending, workers, store })
+    }
+
+    /// Queues `request`. A repeated `idempotency_key` from the same owner returns the job
+    /// the key first created, whatever its state, instead of starting a new proof.
+    pub fn submit(
+        &self,
+        request: JobRequest,
+        owner: Option<Owner>,
+        idempotency_key: Option<String>,
+    ) -> Result<JobId, SubmitError> {
+        let hash = request.hash(owner.as_ref().and_then(|owner| owner.tenant.as_deref()));
+        let mut state = self.state.lock().unwrap();
+        let idempotency_key = idempotency_key.map(|key| (owner.as_ref().map(|owner| owner.key.clone()), key));
+        if let Some(key) = &idempotency_key {
+            if let Some((id, original)) = state.by_idempotency_key.get(key) {
+                return if *original == hash { Ok(*id) } else { Err(SubmitError::IdempotencyConflict) };
+            }
+        }
+        let id = self.enqueue(&mut state, request, hash, owner)?;
+        if let Some(key) = idempotency_key {
+            state.by_idempotency_key.insert(key.clone(), (id, hash));
+            state.idempotency_keys.push_back(key);
+            if state.idempotency_keys.len() > IDEMPOTENCY_CAPACITY {
+                let expired = state.idempotency_keys.pop_front().expect("keys is not empty");
+                state.by_idempotency_key.remove(&expired);
+            }
+        }
+        Ok(id)
+    }
+
+    fn enqueue(
+        &self,
+        state: &mut State,
+        request: JobRequest,
+        hash: RequestHash,
+        owner: Option<Owner>,
+    ) -> Result<JobId, SubmitError> {
+        if state.closed {
+            return Err(SubmitError::ShuttingDown);
+        }
+        if state.paused {
+            return Err(SubmitError::Paused);
+        }
+        let existing = state.by_hash.get(&hash).and_then(|id| Some((*id, state.jobs.get(id)?.status.clone())));
+        match existing {
+            Some((id, JobStatus::Queued | JobStatus::Proving)) => return Ok(id),
+            Some((_, JobStatus::Done { result })) => {
+                let result = ProofResponse { cache_hit: true, ..result };
+                let id = state.insert(owner, hash, JobStatus::Done { result });
+                self.persist_outcome(id, &state.jobs[&id]);
+                return Ok(id);
+            }
+            Some((_, JobStatus::Failed { .. })) | None => {}
+        }
+        if let Some(owner) = &owner {
+            let active = state
+                .jobs
+                .values()
+                .filter(|job| job.is_active() && job.owner.as_ref() == Some(&owner.key))
+                .count();
+            if active >= owner.max_active {
+                return Err(SubmitError::TooManyActiveJobs);
+            }
+            if let Some(max_active) = owner.tenant_max_active {
+                let active = state.jobs.values().filter(|job| job.is_active() && job.tenant == owner.tenant).count();
+                if active >= max_active {
+                    return Err(SubmitError::TooManyTenantJobs);
+                }
+            }
+        }
+        if state.pending_len() >= self.max_pending {
+            return Err(SubmitError::QueueFull);
+        }
+        if let Some(start_by) = request.start_by {
+            let tier = owner.as_ref().map_or_else(Tier::default, |owner| owner.tier);
+            let wait = state.estimated_wait(tier, self.workers);
+            let available = start_by.saturating_duration_since(Instant::now());
+            if wait > available {
+                return Err(SubmitError::DeadlineUnreachable { retry_after_secs: (wait - available).as_secs().max(1) });
+            }
+        }
+        let id = state.insert(owner, hash, JobStatus::Queued);
+        let tier = state.jobs[&id].tier;
+        if let Some(store) = &self.store {
+            let record = JobRecord {
+                input: request.input.clone(),
+                submit: request.submit,
+                assumption: request.assumption.as_ref().map(hex::encode),
+                callback_url: request.callback_url.clone(),
+                owner: state.jobs[&id].owner.clone(),
+                tier,
+                account: state.jobs[&id].account.clone(),
+                tenant: state.jobs[&id].tenant.clone(),
+            };
+            if let Err(e) = store.save_job(id, &record) {
+                error!(job_id = id, error = %e, "failed to persist job; it will not survive a restart");
+            }
+        }
+        let tenant = state.jobs[&id].tenant.clone();
+        state.metrics.entry((tenant, tier)).or_default().submitted += 1;
+        state.pending.entry(tier).or_default().push_back(Pending { id, request, queued_at: Instant::now() });
+        self.ready.notify_one();
+        Ok(id)
+    }
+
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        self.state.lock().unwrap().jobs.get(&id).map(|job| job.status.clone())
+    }
+
+    /// The job's status together with the resources it consumed, once finished. Jobs
+    /// outside `scope` are reported as unknown.
+    pub fn status_with_usage(&self, id: JobId, scope: Scope) -> Option<(JobStatus, Option<Usage>)> {
+        let state = self.state.lock().unwrap();
+        let job = state.jobs.get(&id).filter(|job| scope.includes(job))?;
+        Some((job.status.clone(), job.usage.clone()))
+    }
+
+    /// Current per-tier queue depth, throughput and wait times of the jobs in `scope`.
+    pub fn metrics(&self, scope: Scope) -> BTreeMap<Tier, TierMetrics> {
+        Self::scoped_metrics(&self.state.lock().unwrap(), scope)
+    }
+
+    /// `metrics` for each tenant that has submitted a job.
+    pub fn tenant_metrics(&self) -> BTreeMap<String, BTreeMap<Tier, TierMetrics>> {
+        let state = self.state.lock().unwrap();
+        let tenants: HashSet<&String> = state.metrics.keys().filter_map(|(tenant, _)| tenant.as_ref()).collect();
+        tenants.into_iter().map(|tenant| (tenant.clone(), Self::scoped_metrics(&state, Scope::Tenant(tenant)))).collect()
+    }
+
+    fn scoped_metrics(state: &State, scope: Scope) -> BTreeMap<Tier, TierMetrics> {
+        let mut metrics: BTreeMap<Tier, TierMetrics> = BTreeMap::new();
+        for ((tenant, tier), counters) in &state.metrics {
+            if scope.includes_tenant(tenant.as_deref()) {
+                metrics.entry(*tier).or_default().merge(counters);
+            }
+        }
+        for job in state.jobs.values().filter(|job| scope.includes(job)) {
+            let entry = metrics.entry(job.tier).or_default();
+            match job.status {
+                JobStatus::Queued => entry.queued += 1,
+                JobStatus::Proving => entry.proving += 1,
+                _ => {}
+            }
+        }
+        for entry in metrics.values_mut() {
+            if entry.started > 0 {
+                entry.avg_wait_secs = entry.total_wait.as_secs_f64() / entry.started as f64;
+            }
+            if entry.completed > 0 {
+                entry.avg_total_cycles = entry.total_cycles as f64 / entry.completed as f64;
+                entry.avg_segments = entry.total_segments as f64 / entry.completed as f64;
+            }
+        }
+        metrics
+    }
+
+    /// Unfinished jobs in `scope` in ID order, for the admin routes.
+    pub fn active_jobs(&self, scope: Scope) -> Vec<JobSummary> {
+        let state = self.state.lock().unwrap();
+        let mut jobs: Vec<JobSummary> = state
+            .jobs
+            .iter()
+            .filter(|(_, job)| job.is_active() && scope.includes(job))
+            .map(|(id, job)| JobSummary {
+                job_id: *id,
+                status: job.status.clone(),
+                tier: job.tier,
+                account: job.account.clone(),
+                tenant: job.tenant.clone(),
+                cancelling: state.cancelled.contains(id),
+            })
+            .collect();
+        jobs.sort_by_key(|job| job.job_id);
+        jobs
+    }
+
+    /// Stops or resumes accepting jobs. Queued jobs keep starting while paused.
+    pub fn set_paused(&self, paused: bool) {
+        self.state.lock().unwrap().paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    /// Cancels a job. A queued job fails at once; a proving job keeps its worker until the
+    /// proof finishes, then fails without being submitted. Returns the job's status, or
+    /// `None` for a job that is unknown or outside `scope`.
+    pub fn cancel(&self, id: JobId, scope: Scope) -> Option<Result<JobStatus, CancelError>> {
+        let mut state = self.state.lock().unwrap();
+        let status = state.jobs.get(&id).filter(|job| scope.includes(job))?.status.clone();
+        let result = match status {
+            JobStatus::Queued => {
+                state.cancel_pending(id);
+                self.persist_outcome(id, &state.jobs[&id]);
+                Ok(state.jobs[&id].status.clone())
+            }
+            JobStatus::Proving => {
+                state.cancelled.insert(id);
+                Ok(status)
+            }
+            JobStatus::Done { .. } | JobStatus::Failed { .. } => Err(CancelError::Finished),
+        };
+        Some(result)
+    }
+
+    /// Cancels every queued job in `scope`, leaving jobs already proving alone. Returns
+    /// how many were cancelled.
+    pub fn drain(&self, scope: Scope) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let pending: Vec<JobId> = state
+            .pending
+            .values()
+            .flatten()
+            .map(|job| job.id)
+            .filter(|id| scope.includes(&state.jobs[id]))
+            .collect();
+        for id in &pending {
+            state.cancel_pending(*id);
+            self.persist_outcome(*id, &state.jobs[id]);
+        }
+        info!(cancelled = pending.len(), "queue drained");
+        pending.len()
+    }
+
+    fn is_cancelled(&self, id: JobId) -> bool {
+        self.state.lock().unwrap().cancelled.contains(&id)
+    }
+
+    /// Stops accepting jobs and fails those not yet started. Jobs already proving run to
+    /// completion; workers exit once they finish them. With a store, the jobs not started
+    /// stay persisted and resume on the next start.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        let pending: Vec<JobId> = std::mem::take(&mut state.pending).into_values().flatten().map(|job| job.id).collect();
+        let error = if self.store.is_some() {
+            "Prover shut down before the job started; it resumes when the prover restarts"
+        } else {
+            "Prover shut down before the job started"
+        };
+        for id in pending {
+            if let Some(job) = state.jobs.get_mut(&id) {
+                job.status = JobStatus::Failed { error: error.to_string() };
+            }
+        }
+        self.ready.notify_all();
+    }
+
+    /// Blocks until a job is pending and marks it as proving, returning it with the time it
+    /// waited. Returns `None` once the queue is closed.
+    fn take(&self) -> Option<(JobId, JobRequest, Duration)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(Pending { id, request, queued_at }) = state.next_pending() {
+                let job = state.jobs.get_mut(&id).expect("pending jobs are tracked");
+                job.status = JobStatus::Proving;
+                let key = (job.tenant.clone(), job.tier);
+                let wait = queued_at.elapsed();
+                let metrics = state.metrics.entry(key).or_default();
+                metrics.started += 1;
+                metrics.total_wait += wait;
+                return Some((id, request, wait));
+            }
+            state = self.ready.wait(state).unwrap();
+        }
+    }
+
+    /// Records the job's outcome and usage, returning the account to bill.
+    fn finish(&self, id: JobId, mut status: JobStatus, usage: Usage) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled.remove(&id) {
+            status = JobStatus::Failed { error: CANCELLED.to_string() };
+        }
+        let job = state.jobs.get_mut(&id)?;
+        let stats = match &status {
+            JobStatus::Done { result } => Some(result.stats),
+            _ => None,
+        };
+        job.status = status;
+        let run_time = Duration::from_secs_f64(usage.wall_time_secs);
+        job.usage = Some(usage);
+        let (hash, key, account) = (job.hash, (job.tenant.clone(), job.tier), job.account.clone());
+        if stats.is_some() {
+            state.run_time += run_time;
+            state.runs += 1;
+        }
+        let metrics = state.metrics.entry(key).or_default();
+        match stats {
+            Some(stats) => {
+                metrics.completed += 1;
+                metrics.total_cycles += stats.total_cycles;
+                metrics.total_segments += stats.segments as u64;
+            }
+            None => metrics.failed += 1,
+        }
+        self.persist_outcome(id, &state.jobs[&id]);
+        state.record_finished(hash);
+        account
+    }
+
+    fn persist_outcome(&self, id: JobId, job: &Job) {
+        let Some(store) = &self.store else { return };
+        let record = OutcomeRecord {
+            hash: hex::encode(job.hash),
+            owner: job.owner.clone(),
+            tier: job.tier,
+            tenant: job.tenant.clone(),
+            status: job.status.clone(),
+            usage: job.usage.clone(),
+        };
+        if let Err(e) = store.save_outcome(id, &record) {
+            error!(job_id = id, error = %e, "failed to persist job outcome");
+        }
+    }
+}
+
+/// Configuration shared by all worker threads.
+pub struct WorkerConfig {
+    pub backend: Backend,
+    pub submit: SubmitConfig,
+    /// HMAC key for signing job callbacks.
+    pub webhook_secret: Option<String>,
+    pub billing: BillingConfig,
+}
+
+fn run_job(id: JobId, request: JobRequest, queue: &JobQueue, config: &WorkerConfig) -> anyhow::Result<ProofResponse> {
+    let assumption = request.assumption.as_deref().map(decode_assumption).transpose()?;
+    let mut proof = prove_game(request.input, assumption, config.backend)?;
+    anyhow::ensure!(!queue.is_cancelled(id), CANCELLED);
+    if request.submit {
+        proof.tx_hash = Some(config.submit.submit_score(&proof)?);
+    }
+    Ok(proof)
+}
+
+/// Starts `count` worker threads proving jobs from `queue`. The threads exit after the
+/// queue is closed.
+pub fn spawn_workers(queue: &Arc<JobQueue>, count: usize, config: Arc<WorkerConfig>) -> Vec<JoinHandle<()>> {
+    (0..count)
+        .map(|worker| {
+            let queue = Arc::clone(queue);
+            let config = Arc::clone(&config);
+            std::thread::spawn(move || {
+                while let Some((id, request, queue_wait)) = queue.take() {
+                    let span = info_span!("job", job_id = id, worker);
+                    let _enter = span.enter();
+                    info!("job started");
+                    let start = Instant::now();
+                    let callback_url = request.callback_url.clone();
+                    let status = if request.start_by.is_some_and(|start_by| start > start_by) {
+                        warn!("job missed its start deadline");
+                        JobStatus::Failed { error: "Job could not start before its deadline".to_string() }
+                    } else {
+                        match run_job(id, request, &queue, &config) {
+                            Ok(result) => {
+                                info!(elapsed_secs = start.elapsed().as_secs_f64(), "job done");
+                                JobStatus::Done { result }
+                            }
+                            Err(e) => {
+                                error!(elapsed_secs = start.elapsed().as_secs_f64(), error = %e, "job failed");
+                                JobStatus::Failed { error: e.to_string() }
+                            }
+                        }
+                    };
+                    let status = match status {
+                        _ if queue.is_cancelled(id) => JobStatus::Failed { error: CANCELLED.to_string() },
+                        status => status,
+                    };
+                    let usage = config.billing.usage(config.backend, &status, queue_wait, start.elapsed());
+                    let account = queue.finish(id, status, usage.clone());
+                    config.billing.report(id, account, &usage);
+                    // Only after `finish`, so a client polling on the callback sees the
+                    // job's final status rather than `proving`.
+                    if let (Some(url), Some(secret)) = (callback_url, &config.webhook_secret) {
+                        if let Some(status) = queue.status(id) {
+                            webhook::deliver(url, secret.clone(), id, &status);
+                        }
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::PackedActions;
+
+    fn request(seed: u64) -> JobRequest {
+        let input = GameInput {
+            version: lane_racer_sim::INPUT_VERSION,
+            seed,
+            actions: PackedActions::pack(&[]),
+            player_address: "GPLAYER".to_string(),
+            game_id: 1,
+            session_id: 1,
+            contract_id: [7; 32],
+            config: Default::default(),
+            difficulty: Default::default(),
+        };
+        JobRequest { input, submit: false, assumption: None, callback_url: None, start_by: None }
+    }
+
+    fn owner(key: &str, tier: Tier, tenant: Option<&str>) -> Option<Owner> {
+        Some(Owner {
+            key: key.to_string(),
+            max_active: 10,
+            tier,
+            account: None,
+            tenant: tenant.map(str::to_string),
+            tenant_max_active: None,
+        })
+    }
+
+    fn usage() -> Usage {
+        Usage { backend: Backend::Dev, total_cycles: 0, segments: 0, queue_wait_secs: 0.0, wall_time_secs: 0.0, cost: 0.0 }
+    }
+
+    fn failed(error: &str) -> JobStatus {
+        JobStatus::Failed { error: error.to_string() }
+    }
+
+    fn error_of(status: Option<JobStatus>) -> Option<String> {
+        match status? {
+            JobStatus::Failed { error } => Some(error),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn identical_requests_share_an_unfinished_job() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let first = queue.submit(request(1), None, None).unwrap();
+        assert_eq!(queue.submit(request(1), None, None).unwrap(), first);
+        assert_ne!(queue.submit(request(2), None, None).unwrap(), first);
+    }
+
+    #[test]
+    fn tenants_do_not_share_jobs() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let a = queue.submit(request(1), owner("a", Tier::Standard, Some("studio-a")), None).unwrap();
+        let b = queue.submit(request(1), owner("b", Tier::Standard, Some("studio-b")), None).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn a_failed_request_is_proved_again() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let first = queue.submit(request(1), None, None).unwrap();
+        let (id, _, _) = queue.take().unwrap();
+        assert_eq!(id, first);
+        queue.finish(id, failed("boom"), usage());
+        assert_ne!(queue.submit(request(1), None, None).unwrap(), first);
+    }
+
+    #[test]
+    fn idempotency_keys_return_the_first_job_per_key() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let key = || Some("retry-1".to_string());
+        let first = queue.submit(request(1), owner("a", Tier::Standard, None), key()).unwrap();
+        assert_eq!(queue.submit(request(1), owner("a", Tier::Standard, None), key()).unwrap(), first);
+        assert!(matches!(
+            queue.submit(request(2), owner("a", Tier::Standard, None), key()),
+            Err(SubmitError::IdempotencyConflict)
+        ));
+        // Another key's identical idempotency key is its own.
+        let other = queue.submit(request(2), owner("b", Tier::Standard, None), key()).unwrap();
+        assert_ne!(other, first);
+    }
+
+    #[test]
+    fn active_jobs_are_limited_per_owner() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let limited = || owner("a", Tier::Standard, None).map(|owner| Owner { max_active: 1, ..owner });
+        queue.submit(request(1), limited(), None).unwrap();
+        assert!(matches!(queue.submit(request(2), limited(), None), Err(SubmitError::TooManyActiveJobs)));
+    }
+
+    #[test]
+    fn the_queue_refuses_jobs_beyond_max_pending() {
+        let queue = JobQueue::new(1, 1, None).unwrap();
+        queue.submit(request(1), None, None).unwrap();
+        assert!(matches!(queue.submit(request(2), None, None), Err(SubmitError::QueueFull)));
+    }
+
+    #[test]
+    fn higher_tiers_start_first_and_each_tier_is_fifo() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let casual = queue.submit(request(1), owner("a", Tier::Casual, None), None).unwrap();
+        let standard = queue.submit(request(2), owner("b", Tier::Standard, None), None).unwrap();
+        let tournament = queue.submit(request(3), owner("c", Tier::Tournament, None), None).unwrap();
+        let later_standard = queue.submit(request(4), owner("d", Tier::Standard, None), None).unwrap();
+        let order: Vec<JobId> = (0..4).map(|_| queue.take().unwrap().0).collect();
+        assert_eq!(order, vec![tournament, standard, later_standard, casual]);
+    }
+
+    #[test]
+    fn a_starved_job_starts_ahead_of_higher_tiers() {
+        let Some(long_ago) = Instant::now().checked_sub(STARVATION_LIMIT) else { return };
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let casual = queue.submit(request(1), owner("a", Tier::Casual, None), None).unwrap();
+        let tournament = queue.submit(request(2), owner("b", Tier::Tournament, None), None).unwrap();
+        queue.state.lock().unwrap().pending.get_mut(&Tier::Casual).unwrap()[0].queued_at = long_ago;
+        assert_eq!(queue.take().unwrap().0, casual);
+        assert_eq!(queue.take().unwrap().0, tournament);
+    }
+
+    #[test]
+    fn cancelling_a_queued_job_fails_it_and_frees_its_slot() {
+        let queue = JobQueue::new(1, 1, None).unwrap();
+        let id = queue.submit(request(1), None, None).unwrap();
+        assert_eq!(error_of(queue.cancel(id, Scope::All).unwrap().ok()).as_deref(), Some(CANCELLED));
+        assert!(queue.submit(request(2), None, None).is_ok());
+        assert!(matches!(queue.cancel(id, Scope::All), Some(Err(CancelError::Finished))));
+    }
+
+    #[test]
+    fn a_job_cancelled_while_proving_fails_when_it_finishes() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let id = queue.submit(request(1), None, None).unwrap();
+        queue.take().unwrap();
+        assert!(matches!(queue.cancel(id, Scope::All), Some(Ok(JobStatus::Proving))));
+        assert!(queue.is_cancelled(id));
+        queue.finish(id, failed("worker error"), usage());
+        assert_eq!(error_of(queue.status(id)).as_deref(), Some(CANCELLED));
+    }
+
+    #[test]
+    fn drain_cancels_only_queued_jobs_in_scope() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let proving = queue.submit(request(1), owner("a", Tier::Tournament, Some("studio-a")), None).unwrap();
+        queue.take().unwrap();
+        let queued = queue.submit(request(2), owner("a", Tier::Standard, Some("studio-a")), None).unwrap();
+        let other = queue.submit(request(3), owner("b", Tier::Standard, Some("studio-b")), None).unwrap();
+        assert_eq!(queue.drain(Scope::Tenant("studio-a")), 1);
+        assert!(matches!(queue.status(proving), Some(JobStatus::Proving)));
+        assert_eq!(error_of(queue.status(queued)).as_deref(), Some(CANCELLED));
+        assert!(matches!(queue.status(other), Some(JobStatus::Queued)));
+    }
+
+    #[test]
+    fn scopes_hide_other_tenants_and_keys() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let tenant_job = queue.submit(request(1), owner("a", Tier::Standard, Some("studio-a")), None).unwrap();
+        let own_job = queue.submit(request(2), owner("b", Tier::Standard, None), None).unwrap();
+        let other_job = queue.submit(request(3), owner("c", Tier::Standard, None), None).unwrap();
+
+        assert!(queue.status_with_usage(tenant_job, Scope::Tenant("studio-a")).is_some());
+        assert!(queue.status_with_usage(tenant_job, Scope::Tenant("studio-b")).is_none());
+        assert!(queue.status_with_usage(own_job, Scope::Tenant("studio-a")).is_none());
+
+        assert!(queue.status_with_usage(own_job, Scope::Key("b")).is_some());
+        assert!(queue.status_with_usage(other_job, Scope::Key("b")).is_none());
+        assert!(queue.status_with_usage(tenant_job, Scope::Key("a")).is_none());
+        assert!(queue.cancel(other_job, Scope::Key("b")).is_none());
+
+        assert_eq!(queue.metrics(Scope::Tenant("studio-a"))[&Tier::Standard].queued, 1);
+        assert_eq!(queue.metrics(Scope::All)[&Tier::Standard].queued, 3);
+        assert_eq!(queue.active_jobs(Scope::Tenant("studio-b")).len(), 0);
+    }
+
+    #[test]
+    fn closing_fails_queued_jobs_and_refuses_new_ones() {
+        let queue = JobQueue::new(10, 1, None).unwrap();
+        let id = queue.submit(request(1), None, None).unwrap();
+        queue.close();
+        assert!(matches!(queue.status(id), Some(JobStatus::Failed { .. })));
+        assert!(matches!(queue.submit(request(2), None, None), Err(SubmitError::ShuttingDown)));
+        assert!(queue.take().is_none());
+    }
+}
@@ -0,0 +1,4 @@
+//! The parts of the prover service other crates reuse: proving a game and formatting the
+//! result as contract call arguments. The HTTP service itself is the `host` binary.
+
+pub mod prover;
@@ -1,117 +1,274 @@
-use anyhow::Result;
-use methods::{LANE_RACER_PROVER_ELF, LANE_RACER_PROVER_ID};
-use risc0_zkvm::{default_prover, ExecutorEnv};
-use shared::{GameInput, GameResult};
-use sha2::{Digest, Sha256};
-use std::time::Instant;
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
-
-#[derive(serde::Serialize)]
-struct ProofResponse {
-    seal: String,
-    journal: String,
-    score: u32,
-    obstacles_dodged: u32,
-    gems_collected: u32,
-    image_id: String,
-    prove_time_secs: f64,
-}
+mod api;
+mod auth;
+mod billing;
+mod diagnose;
+mod jobs;
+mod openapi;
+mod schema;
+mod store;
+mod submit;
+mod trace;
+mod validate;
+mod webhook;
 
-#[derive(serde::Deserialize)]
-struct ProveRequest {
-    score: Option<u32>,
-    player: Option<String>,
-    seed: Option<u64>,
-    actions: Option<Vec<u32>>,
-    game_id: Option<u32>,
-}
+use anyhow::{Context, Result};
+use api::AppState;
+use auth::Auth;
+use billing::BillingConfig;
+use clap::Parser;
+use host::prover::{self, Accelerator, Backend};
+use jobs::{JobQueue, WorkerConfig};
+use store::JobStore;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use submit::SubmitConfig;
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+/// Rough peak RAM of one local prover producing a Groth16 receipt.
+const LOCAL_PROVER_MEMORY_BYTES: u64 = 16 << 30;
 
-fn prove_game(input: GameInput) -> Result<ProofResponse> {
-    println!("[ZK] Building executor environment...");
-    let env = ExecutorEnv::builder().write(&input)?.build()?;
-    println!("[ZK] Generating proof...");
-    let start = Instant::now();
-    let prover = default_prover();
-    let info = prover.prove(env, LANE_RACER_PROVER_ELF)?;
-    let receipt = info.receipt;
-    let elapsed = start.elapsed().as_secs_f64();
-    println!("[ZK] Proof generated in {:.1}s", elapsed);
-    receipt.verify(LANE_RACER_PROVER_ID)?;
-    println!("[ZK] Verification passed ✓");
-    let result: GameResult = receipt.journal.decode()?;
-    println!("Score: {} | Obstacles: {} | Gems: {}", result.score, result.obstacles_dodged, result.gems_collected);
-    let journal_hash = hex::encode(Sha256::digest(&receipt.journal.bytes));
-    let receipt_bytes = bincode::serialize(&receipt)?;
-    let seal = hex::encode(Sha256::digest(&receipt_bytes));
-    let image_id_bytes: Vec<u8> = LANE_RACER_PROVER_ID.iter().flat_map(|x| x.to_be_bytes()).collect();
-    let image_id = hex::encode(&image_id_bytes);
-    Ok(ProofResponse { seal, journal: journal_hash, score: result.score, obstacles_dodged: result.obstacles_dodged, gems_collected: result.gems_collected, image_id, prove_time_secs: elapsed })
+/// Number of threads serving HTTP requests. Handlers only queue work, so a few suffice.
+const HTTP_THREADS: usize = 4;
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines.
+    Text,
+    /// One JSON object per event, for log aggregation.
+    Json,
 }
 
-fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
-    let mut buf = [0u8; 8192];
-    let n = stream.read(&mut buf).ok()?;
-    let raw = String::from_utf8_lossy(&buf[..n]).to_string();
-    let first_line = raw.lines().next()?;
-    let mut parts = first_line.split_whitespace();
-    let method = parts.next()?.to_string();
-    let path = parts.next()?.to_string();
-    let body = if let Some(idx) = raw.find("\r\n\r\n") { raw[idx + 4..].to_string() } else { String::new() };
-    Some((format!("{} {}", method, path), body))
+#[derive(Parser)]
+#[command(about = "Lane Racer ZK prover service")]
+struct Config {
+    /// Proving backend.
+    #[arg(long, value_enum, default_value_t = Backend::Local)]
+    backend: Backend,
+
+    /// Hardware for the local backend. Defaults to the accelerator this binary was built
+    /// for (`cuda` or `metal` feature, otherwise `cpu`).
+    #[arg(long, value_enum)]
+    accelerator: Option<Accelerator>,
+
+    /// Shorthand for `--backend dev`, also enabled by a truthy `RISC0_DEV_MODE`.
+    #[arg(long, conflicts_with = "backend")]
+    dev: bool,
+
+    /// JSON file listing API keys and their limits, optionally grouped into tenants with
+    /// shared limits. Without it the prover accepts unauthenticated requests.
+    #[arg(long)]
+    api_keys: Option<PathBuf>,
+
+    /// Proofs generated concurrently. Defaults to what the cores and RAM can hold for the
+    /// local backend, and to the core count for remote or dev backends.
+    #[arg(long)]
+    workers: Option<usize>,
+
+    /// Jobs that may wait for a worker before `POST /prove` returns 503.
+    #[arg(long, default_value_t = 64)]
+    max_queue: usize,
+
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:3002")]
+    listen: String,
+
+    /// PEM certificate chain; serves HTTPS together with `--tls-key`.
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key for `--tls-cert`.
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Longest action trace accepted, in ticks. Cannot exceed the guest's own limit.
+    #[arg(long, default_value_t = lane_racer_sim::MAX_TICKS)]
+    max_actions: usize,
+
+    /// Directory persisting jobs and their outcomes. Jobs unfinished when the prover stops
+    /// or crashes are queued again on the next start; without it they are lost.
+    #[arg(long)]
+    state_dir: Option<PathBuf>,
+
+    /// Secret for signing job callbacks. Enables `callback_url` on prove requests.
+    #[arg(long, env = "PROVER_WEBHOOK_SECRET", hide_env_values = true)]
+    webhook_secret: Option<String>,
+
+    /// Log output format. Verbosity follows `RUST_LOG` (default `info`).
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    #[command(flatten)]
+    submit: SubmitConfig,
+
+    #[command(flatten)]
+    billing: BillingConfig,
 }
 
-fn send_response(stream: &mut TcpStream, status: u16, body: &str) {
-    let status_text = if status == 200 { "OK" } else { "Bad Request" };
-    let response = format!(
-        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\nContent-Length: {}\r\n\r\n{}",
-        status, status_text, body.len(), body
+fn main() -> Result<()> {
+    let mut config = Config::parse();
+    init_logging(config.log_format);
+    let dev_env = std::env::var("RISC0_DEV_MODE").is_ok_and(|v| matches!(v.as_str(), "1" | "true" | "yes"));
+    if config.dev || dev_env {
+        config.backend = Backend::Dev;
+    }
+    config.backend.check()?;
+    anyhow::ensure!(
+        config.max_actions <= lane_racer_sim::MAX_TICKS,
+        "--max-actions {} exceeds the guest limit of {} ticks",
+        config.max_actions,
+        lane_racer_sim::MAX_TICKS
     );
-    let _ = stream.write_all(response.as_bytes());
+    let accelerator = match config.backend {
+        Backend::Local => {
+            let accelerator = config.accelerator.unwrap_or_else(Accelerator::built);
+            accelerator.check()?;
+            Some(accelerator)
+        }
+        Backend::Bonsai | Backend::Dev => None,
+    };
+    let auth = match &config.api_keys {
+        Some(path) => Auth::load(path)?,
+        None => Auth::default(),
+    };
+    let server = Arc::new(start_server(&config)?);
+    let scheme = if config.tls_cert.is_some() { "https" } else { "http" };
+    info!(
+        address = %format!("{}://{}", scheme, config.listen),
+        backend = ?config.backend,
+        accelerator = ?accelerator,
+        detected = ?Accelerator::ALL.into_iter().filter(|a| a.detected()).collect::<Vec<_>>(),
+        guest_version = methods::GUEST_VERSION,
+        image_id = %prover::image_id_hex(),
+        "Lane Racer ZK prover listening"
+    );
+    if !auth.is_enabled() {
+        warn!("no --api-keys file, accepting unauthenticated requests");
+    }
+    let default_contract_id = config
+        .submit
+        .contract_id
+        .as_deref()
+        .map(validate::decode_contract)
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("--submit-contract-id: {}", e))?;
+    let can_submit = config.submit.is_enabled();
+    let session_check = config.submit.check_sessions.then(|| config.submit.clone());
+    if can_submit {
+        info!(contract_id = config.submit.contract_id.as_deref().unwrap_or_default(), "submitting scores on-chain");
+    }
+    let worker_count = config.workers.unwrap_or_else(|| default_workers(config.backend, accelerator)).max(1);
+    info!(workers = worker_count, queue_capacity = config.max_queue, "starting workers");
+    let store = config.state_dir.as_deref().map(JobStore::open).transpose()?;
+    let queue = Arc::new(JobQueue::new(config.max_queue, worker_count, store)?);
+    if let Some(url) = &config.billing.webhook_url {
+        webhook::validate_url(url).map_err(|_| anyhow::anyhow!("--billing-webhook-url must be an http:// or https:// URL"))?;
+        info!(url = %url, "reporting job usage to the billing webhook");
+    }
+    let can_callback = config.webhook_secret.is_some();
+    let workers = WorkerConfig {
+        backend: config.backend,
+        submit: config.submit,
+        webhook_secret: config.webhook_secret,
+        billing: config.billing,
+    };
+    let workers = jobs::spawn_workers(&queue, worker_count, Arc::new(workers));
+    install_shutdown_handler(Arc::clone(&queue))?;
+    let state = Arc::new(AppState {
+        queue,
+        auth,
+        backend: config.backend,
+        accelerator,
+        can_submit,
+        can_callback,
+        max_actions: config.max_actions,
+        default_contract_id,
+        session_check,
+    });
+    let handlers: Vec<_> = (0..HTTP_THREADS)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    api::handle(request, &state);
+                }
+            })
+        })
+        .collect();
+    // Keep serving status requests while in-flight proofs finish, then stop the server.
+    for worker in workers {
+        let _ = worker.join();
+    }
+    info!("all jobs drained, shutting down");
+    for _ in 0..HTTP_THREADS {
+        server.unblock();
+    }
+    for handler in handlers {
+        let _ = handler.join();
+    }
+    Ok(())
 }
 
-fn handle_connection(mut stream: TcpStream) {
-    let (route, body) = match read_request(&mut stream) {
-        Some(r) => r,
-        None => return,
-    };
-    if route.starts_with("OPTIONS") { send_response(&mut stream, 200, "{}"); return; }
-    if route == "GET /health" { send_response(&mut stream, 200, r#"{"status":"ok"}"#); return; }
-    if route == "POST /prove" {
-        println!("[SERVER] Received prove request");
-        let req: ProveRequest = match serde_json::from_str(&body) {
-            Ok(r) => r,
-            Err(e) => { send_response(&mut stream, 400, &format!(r#"{{"error":"{}"}}"#, e)); return; }
-        };
-        let seed = req.seed.unwrap_or(42);
-        let game_id = req.game_id.unwrap_or(1);
-        let player = req.player.unwrap_or_else(|| "UNKNOWN".to_string());
-        let actions = req.actions.unwrap_or_else(|| {
-            let ticks = (req.score.unwrap_or(0) as usize * 10).max(50);
-            vec![0u32; ticks]
-        });
-        let input = GameInput { seed, actions, player_address: player, game_id };
-        match prove_game(input) {
-            Ok(proof) => { let json = serde_json::to_string(&proof).unwrap(); send_response(&mut stream, 200, &json); }
-            Err(e) => { send_response(&mut stream, 400, &format!(r#"{{"error":"{}"}}"#, e)); }
-        }
-        return;
+fn init_logging(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
     }
-    send_response(&mut stream, 400, r#"{"error":"Unknown route"}"#);
 }
 
-fn main() -> Result<()> {
-    let addr = "127.0.0.1:3002";
-    let listener = TcpListener::bind(addr)?;
-    println!("╔══════════════════════════════════════╗");
-    println!("║   Lane Racer ZK Prover — Port 3002   ║");
-    println!("║   POST /prove  — generate ZK proof   ║");
-    println!("║   GET  /health — health check        ║");
-    println!("╚══════════════════════════════════════╝");
-    for stream in listener.incoming() {
-        if let Ok(s) = stream {
-            std::thread::spawn(move || handle_connection(s));
+/// Binds the HTTP server, terminating TLS with rustls when a certificate is configured.
+fn start_server(config: &Config) -> Result<tiny_http::Server> {
+    let server = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => {
+            let ssl = tiny_http::SslConfig {
+                certificate: std::fs::read(cert).with_context(|| format!("reading {}", cert.display()))?,
+                private_key: std::fs::read(key).with_context(|| format!("reading {}", key.display()))?,
+            };
+            tiny_http::Server::https(&config.listen, ssl)
         }
+        _ => tiny_http::Server::http(&config.listen),
+    };
+    server.map_err(|e| anyhow::anyhow!("binding {}: {}", config.listen, e))
+}
+
+/// Local provers each use every core and several GB of RAM, so run as many as memory
+/// allows, capped by the core count. A GPU proves one job at a time. Remote and dev
+/// backends are not memory bound.
+fn default_workers(backend: Backend, accelerator: Option<Accelerator>) -> usize {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    match backend {
+        Backend::Local if accelerator != Some(Accelerator::Cpu) => 1,
+        Backend::Local => match total_memory_bytes() {
+            Some(memory) => cores.min((memory / LOCAL_PROVER_MEMORY_BYTES) as usize).max(1),
+            None => 1,
+        },
+        Backend::Bonsai | Backend::Dev => cores,
     }
+}
+
+/// Total system RAM from `/proc/meminfo`; `None` where that is unavailable.
+fn total_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kib = meminfo.lines().find_map(|line| line.strip_prefix("MemTotal:"))?;
+    let kib: u64 = kib.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// On the first SIGINT/SIGTERM, closes the queue so workers drain and `main` returns; a
+/// second signal exits immediately.
+fn install_shutdown_handler(queue: Arc<JobQueue>) -> Result<()> {
+    let signalled = AtomicBool::new(false);
+    ctrlc::set_handler(move || {
+        if signalled.swap(true, Ordering::SeqCst) {
+            warn!("forced shutdown");
+            std::process::exit(130);
+        }
+        info!("shutdown requested, finishing in-flight proofs (signal again to force)");
+        queue.close();
+    })?;
     Ok(())
 }
@@ -0,0 +1,128 @@
+//! The OpenAPI 3 document served at `GET /openapi.json`, generated from the bodies in
+//! [`crate::schema`] so the frontend and integrators can generate clients from it.
+
+use crate::diagnose::Diagnosis;
+use crate::jobs::JobId;
+use crate::schema::{
+    AdminJobsResponse, DiagnoseRequest, DrainResponse, ErrorResponse, HealthResponse, ImageIdResponse, InfoResponse, JobResponse,
+    JobStatusResponse, MetricsResponse, PausedResponse, ProveRequest, SimulateResponse,
+};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+/// The document, built on first use.
+pub fn document() -> &'static Value {
+    static DOCUMENT: OnceLock<Value> = OnceLock::new();
+    DOCUMENT.get_or_init(build)
+}
+
+fn build() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let gen = &mut gen;
+    let job_id = json!([{ "name": "id", "in": "path", "required": true, "schema": gen.subschema_for::<JobId>() }]);
+    let idempotency_key = json!({
+        "name": "Idempotency-Key",
+        "in": "header",
+        "description": "Retries with the same key and request return the original job; reusing it for another request is a 422.",
+        "schema": { "type": "string" },
+    });
+
+    let mut prove = operation::<JobResponse>(gen, "Queue a proof of a run", 202, &[400, 401, 409, 413, 422, 429, 503]);
+    prove["requestBody"] = body::<ProveRequest>(gen);
+    prove["parameters"] = json!([idempotency_key]);
+    prove["responses"]["200"] = response::<JobResponse>(gen, "A previous identical request already finished");
+
+    let mut simulate = operation::<SimulateResponse>(gen, "Simulate a run without proving it", 200, &[400, 401, 413, 422]);
+    simulate["requestBody"] = body::<ProveRequest>(gen);
+
+    let mut diagnose =
+        operation::<Diagnosis>(gen, "Find where a run departs from the frontend's claimed score", 200, &[400, 401, 413, 422]);
+    diagnose["requestBody"] = body::<DiagnoseRequest>(gen);
+
+    let mut job = operation::<JobStatusResponse>(gen, "Status of a job", 200, &[401, 404]);
+    job["parameters"] = job_id.clone();
+
+    let mut cancel = operation::<JobResponse>(gen, "Cancel a job", 200, &[401, 403, 404, 409]);
+    cancel["parameters"] = job_id;
+
+    let paths = json!({
+        "/health": { "get": public(operation::<HealthResponse>(gen, "Liveness check", 200, &[])) },
+        "/info": { "get": public(operation::<InfoResponse>(gen, "Versions and capabilities of this prover", 200, &[])) },
+        "/image_id": { "get": public(operation::<ImageIdResponse>(gen, "Image ID proofs are generated for", 200, &[])) },
+        "/openapi.json": { "get": public(json!({
+            "summary": "This document",
+            "responses": { "200": { "description": "OpenAPI 3 document", "content": { "application/json": {} } } },
+        })) },
+        "/prove": { "post": prove },
+        "/simulate": { "post": simulate },
+        "/diagnose": { "post": diagnose },
+        "/metrics": { "get": operation::<MetricsResponse>(gen, "Per-tier queue metrics", 200, &[401]) },
+        "/jobs/{id}": { "get": job },
+        "/admin/jobs": { "get": operation::<AdminJobsResponse>(gen, "List unfinished jobs", 200, &[401, 403]) },
+        "/admin/jobs/{id}/cancel": { "post": cancel },
+        "/admin/pause": { "post": operation::<PausedResponse>(gen, "Stop accepting jobs; not for tenant keys", 200, &[401, 403]) },
+        "/admin/resume": { "post": operation::<PausedResponse>(gen, "Resume accepting jobs; not for tenant keys", 200, &[401, 403]) },
+        "/admin/drain": { "post": operation::<DrainResponse>(gen, "Cancel every queued job", 200, &[401, 403]) },
+    });
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Lane Racer prover",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Proves Lane Racer runs for the on-chain leaderboard. Admin routes require an admin API key. \
+                Keys of a tenant only see and manage that tenant's jobs and metrics.",
+        },
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+            },
+        },
+        // Either header works; without a key file the prover ignores both.
+        "security": [{ "bearer": [] }, { "apiKey": [] }],
+    })
+}
+
+/// An operation answering `status` with `T`, and an `ErrorResponse` for each of `errors`.
+fn operation<T: JsonSchema>(gen: &mut SchemaGenerator, summary: &str, status: u16, errors: &[u16]) -> Value {
+    let mut responses = Map::new();
+    responses.insert(status.to_string(), response::<T>(gen, "Success"));
+    for code in errors {
+        responses.insert(code.to_string(), response::<ErrorResponse>(gen, error_description(*code)));
+    }
+    json!({ "summary": summary, "responses": responses })
+}
+
+/// Marks an operation as needing no API key.
+fn public(mut operation: Value) -> Value {
+    operation["security"] = json!([]);
+    operation
+}
+
+fn body<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": gen.subschema_for::<T>() } } })
+}
+
+fn response<T: JsonSchema>(gen: &mut SchemaGenerator, description: &str) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": gen.subschema_for::<T>() } } })
+}
+
+fn error_description(code: u16) -> &'static str {
+    match code {
+        400 => "Malformed JSON, or a feature this prover does not enable",
+        401 => "Missing or invalid API key",
+        403 => "The API key is not an admin key, or its tenant may not use this route",
+        404 => "Unknown job, or a job of another tenant",
+        409 => "The job already finished, or the contract would refuse the run",
+        413 => "Request body too large",
+        422 => "The request failed validation; `details` lists every problem",
+        429 => "Rate limit or concurrent job limit exceeded",
+        503 => "Queue full, paused, shutting down, or the job cannot start within `max_wait_secs`",
+        _ => "Error",
+    }
+}
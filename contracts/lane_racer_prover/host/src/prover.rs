@@ -0,0 +1,351 @@
+use anyhow::{Context, Result};
+use lane_racer_sim::Difficulty;
+pub use methods::image_id_hex;
+use methods::{LANE_RACER_PROVER_ELF, LANE_RACER_PROVER_ID};
+use risc0_zkvm::{
+    default_executor, default_prover, sha::Digestible, BonsaiProver, Digest, ExecutorEnv, ExitCode,
+    Journal, Prover, ProverOpts, Receipt, ReceiptClaim,
+};
+use shared::{GameInput, GameJournal};
+use sha2::{Digest as _, Sha256};
+use std::rc::Rc;
+use std::time::Instant;
+use tracing::info;
+
+/// Where proofs are generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Prove on this machine with the default local prover.
+    Local,
+    /// Upload the ELF and input to Bonsai and poll for the SNARK receipt. Reads
+    /// `BONSAI_API_URL` and `BONSAI_API_KEY` from the environment.
+    Bonsai,
+    /// Execute the guest without proving and return a mock seal accepted by
+    /// `RiscZeroMockVerifier`. For full-stack testing only; the seal proves nothing.
+    Dev,
+}
+
+impl Backend {
+    /// Fails early when the backend is missing configuration it only needs at proving time.
+    pub fn check(self) -> Result<()> {
+        if self == Backend::Bonsai {
+            for var in ["BONSAI_API_URL", "BONSAI_API_KEY"] {
+                anyhow::ensure!(std::env::var(var).is_ok(), "--backend bonsai requires {} to be set", var);
+            }
+        }
+        Ok(())
+    }
+
+    /// Seal layouts this backend produces, as reported by `GET /info`.
+    pub fn seal_formats(self) -> Vec<SealFormat> {
+        match self {
+            Backend::Local | Backend::Bonsai => {
+                vec![SealFormat { name: "groth16", layout: "selector || a || b || c", length: SEAL_LEN }]
+            }
+            Backend::Dev => {
+                vec![SealFormat { name: "mock", layout: "ffffffff || claim_digest", length: DEV_MODE_SELECTOR.len() + 32 }]
+            }
+        }
+    }
+}
+
+/// A seal layout a backend produces.
+#[derive(Clone, Copy, serde::Serialize, schemars::JsonSchema)]
+pub struct SealFormat {
+    pub name: &'static str,
+    pub layout: &'static str,
+    /// Seal length in bytes.
+    pub length: usize,
+}
+
+/// Hardware the local prover runs on. risc0 fixes this at compile time through the
+/// `cuda` and `metal` features, so only the accelerator the binary was built for works.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Accelerator {
+    Cpu,
+    /// NVIDIA GPUs; requires building with `--features cuda`.
+    Cuda,
+    /// Apple GPUs; requires building with `--features metal`.
+    Metal,
+}
+
+impl Accelerator {
+    pub const ALL: [Accelerator; 3] = [Accelerator::Cpu, Accelerator::Cuda, Accelerator::Metal];
+
+    /// The accelerator this binary was compiled for.
+    pub fn built() -> Self {
+        if cfg!(feature = "cuda") {
+            Accelerator::Cuda
+        } else if cfg!(feature = "metal") {
+            Accelerator::Metal
+        } else {
+            Accelerator::Cpu
+        }
+    }
+
+    /// Whether this machine has a device for the accelerator, whatever the build.
+    pub fn detected(self) -> bool {
+        match self {
+            Accelerator::Cpu => true,
+            Accelerator::Cuda => std::path::Path::new("/dev/nvidiactl").exists(),
+            Accelerator::Metal => cfg!(all(target_os = "macos", target_arch = "aarch64")),
+        }
+    }
+
+    /// Fails when the accelerator is not compiled in or has no device.
+    pub fn check(self) -> Result<()> {
+        let built = Self::built();
+        anyhow::ensure!(self == built, "--accelerator {:?} is unavailable: this binary was built for {:?}", self, built);
+        anyhow::ensure!(self.detected(), "--accelerator {:?} selected but no device was found", self);
+        Ok(())
+    }
+}
+
+/// Selector risc0 uses for fake receipts, which the mock verifier always accepts.
+const DEV_MODE_SELECTOR: [u8; 4] = [0xFF; 4];
+
+/// Length of a Soroban Groth16 seal: selector (4) || a (64) || b (128) || c (64).
+const SEAL_LEN: usize = 260;
+
+/// Arguments of the verifier's `verify(seal, image_id, journal)`, hex encoded.
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct VerifyArgs {
+    pub seal: String,
+    pub image_id: String,
+    /// SHA-256 of the journal bytes, which is what `verify` takes as `journal`.
+    pub journal: String,
+}
+
+/// The `ZKProof` argument of `submit_score`.
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ZkProofArg {
+    pub seal: String,
+    /// Raw journal bytes; the contract hashes them for the verifier and decodes them.
+    pub journal: String,
+}
+
+/// Arguments of the lane-racer `submit_score(session_id, player, score, proof)`.
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct SubmitScoreArgs {
+    pub session_id: u32,
+    pub player: String,
+    pub score: u32,
+    pub proof: ZkProofArg,
+}
+
+/// Contract call arguments in the exact shapes the entrypoints take, so clients do not
+/// recompute digests themselves.
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct CallArgs {
+    pub verify: VerifyArgs,
+    pub submit_score: SubmitScoreArgs,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ProofResponse {
+    pub seal: String,
+    /// Raw journal committed by the guest, hex encoded.
+    pub journal: String,
+    pub journal_digest: String,
+    /// RISC Zero `ReceiptClaim` digest, as carried in a Soroban `Receipt`.
+    pub claim_digest: String,
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub shields_collected: u32,
+    pub shields_used: u32,
+    pub magnets_collected: u32,
+    pub near_misses: u32,
+    pub best_combo: u32,
+    pub obstacles_spawned: u32,
+    pub gems_spawned: u32,
+    pub ticks: u32,
+    pub lanes: u8,
+    pub difficulty: Difficulty,
+    pub image_id: String,
+    pub prove_time_secs: f64,
+    /// Hash of the `submit_score` transaction when the job asked for submission.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    /// Whether this response was served from a previous identical request.
+    pub cache_hit: bool,
+    pub stats: ExecutionStats,
+    pub args: CallArgs,
+    /// When an assumption was supplied: the conditional composite receipt, before the
+    /// assumption was resolved into the Groth16 proof above (hex of bincode).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conditional_receipt: Option<String>,
+}
+
+/// Execution cost of a run, for seeing how simulation changes affect proving time.
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExecutionStats {
+    /// Cycles proven, including paging and padding of each segment to a power of two.
+    pub total_cycles: u64,
+    /// Cycles spent executing guest instructions.
+    pub user_cycles: u64,
+    pub segments: usize,
+    /// High-water mark of this process's resident memory in bytes, read from
+    /// `/proc/self/status`. Process-wide, so concurrent jobs inflate it; absent off Linux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_memory_bytes: Option<u64>,
+}
+
+/// Reads `VmHWM`, the peak resident set size of this process.
+fn peak_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Output of a proving or dev-mode run.
+struct Proven {
+    seal: Vec<u8>,
+    journal: Journal,
+    claim_digest: Digest,
+    conditional_receipt: Option<Receipt>,
+    stats: ExecutionStats,
+}
+
+/// Decodes a receipt supplied as a composition assumption (bincode, as risc0 serializes it).
+pub fn decode_assumption(bytes: &[u8]) -> Result<Receipt> {
+    bincode::deserialize(bytes).context("assumption is not a bincode-encoded risc0 receipt")
+}
+
+/// Proves one game. An `assumption` receipt (e.g. a tournament registration proof) is
+/// added to the executor so the guest may `env::verify` it; the proof is then generated
+/// as a conditional composite receipt and resolved while compressing to Groth16.
+pub fn prove_game(input: GameInput, assumption: Option<Receipt>, backend: Backend) -> Result<ProofResponse> {
+    anyhow::ensure!(
+        input.version == lane_racer_sim::INPUT_VERSION,
+        "input version {} is not supported, the guest accepts {}",
+        input.version,
+        lane_racer_sim::INPUT_VERSION
+    );
+    let composed = assumption.is_some();
+    let mut builder = ExecutorEnv::builder();
+    if let Some(assumption) = assumption {
+        builder.add_assumption(assumption);
+    }
+    let env = builder.write(&input)?.build()?;
+    let start = Instant::now();
+    let Proven { seal, journal, claim_digest, conditional_receipt, mut stats } = match backend {
+        Backend::Dev => execute_dev(env)?,
+        Backend::Local | Backend::Bonsai => prove_groth16(env, backend, composed)?,
+    };
+    let elapsed = start.elapsed().as_secs_f64();
+    stats.peak_memory_bytes = peak_memory_bytes();
+    let result = GameJournal::decode(&journal.bytes).context("journal does not match the expected layout")?;
+    anyhow::ensure!(result.sim_version == lane_racer_sim::SIM_VERSION, "guest committed sim version {}, host expects {}", result.sim_version, lane_racer_sim::SIM_VERSION);
+    info!(score = result.score, obstacles_dodged = result.obstacles_dodged, gems_collected = result.gems_collected, elapsed_secs = elapsed, "game proven");
+    info!(total_cycles = stats.total_cycles, user_cycles = stats.user_cycles, segments = stats.segments, "execution stats");
+    let seal = hex::encode(seal);
+    let journal_digest = hex::encode(Sha256::digest(&journal.bytes));
+    let image_id = image_id_hex();
+    let args = CallArgs {
+        verify: VerifyArgs { seal: seal.clone(), image_id: image_id.clone(), journal: journal_digest.clone() },
+        submit_score: SubmitScoreArgs {
+            session_id: result.session_id,
+            player: input.player_address.clone(),
+            score: result.score,
+            proof: ZkProofArg { seal: seal.clone(), journal: hex::encode(&journal.bytes) },
+        },
+    };
+    Ok(ProofResponse {
+        seal,
+        journal: hex::encode(&journal.bytes),
+        journal_digest,
+        claim_digest: hex::encode(claim_digest.as_bytes()),
+        score: result.score,
+        obstacles_dodged: result.obstacles_dodged,
+        gems_collected: result.gems_collected,
+        shields_collected: result.shields_collected,
+        shields_used: result.shields_used,
+        magnets_collected: result.magnets_collected,
+        near_misses: result.near_misses,
+        best_combo: result.best_combo,
+        obstacles_spawned: result.obstacles_spawned,
+        gems_spawned: result.gems_spawned,
+        ticks: result.ticks,
+        lanes: result.lanes,
+        difficulty: result.difficulty,
+        image_id,
+        prove_time_secs: elapsed,
+        tx_hash: None,
+        cache_hit: false,
+        stats,
+        args,
+        conditional_receipt: conditional_receipt.map(|receipt| bincode::serialize(&receipt).map(hex::encode)).transpose()?,
+    })
+}
+
+fn prove_groth16(env: ExecutorEnv, backend: Backend, composed: bool) -> Result<Proven> {
+    info!(?backend, composed, "generating Groth16 proof");
+    let start = Instant::now();
+    let prover: Rc<dyn Prover> = match backend {
+        Backend::Bonsai => Rc::new(BonsaiProver::new("bonsai")),
+        _ => default_prover(),
+    };
+    let (receipt, conditional_receipt, session) = if composed {
+        anyhow::ensure!(backend == Backend::Local, "composition requires the local backend");
+        let info = prover.prove_with_opts(env, LANE_RACER_PROVER_ELF, &ProverOpts::composite())?;
+        info!(elapsed_secs = start.elapsed().as_secs_f64(), "conditional receipt generated");
+        (prover.compress(&ProverOpts::groth16(), &info.receipt)?, Some(info.receipt), info.stats)
+    } else {
+        let info = prover.prove_with_opts(env, LANE_RACER_PROVER_ELF, &ProverOpts::groth16())?;
+        (info.receipt, None, info.stats)
+    };
+    let stats = ExecutionStats {
+        total_cycles: session.total_cycles,
+        user_cycles: session.user_cycles,
+        segments: session.segments,
+        peak_memory_bytes: None,
+    };
+    info!(elapsed_secs = start.elapsed().as_secs_f64(), "proof generated");
+    check_exit(receipt.claim()?.as_value()?.exit_code)?;
+    let start = Instant::now();
+    receipt.verify(LANE_RACER_PROVER_ID)?;
+    info!(elapsed_ms = start.elapsed().as_millis() as u64, "receipt verified");
+    let claim_digest = receipt.claim()?.digest();
+    Ok(Proven { seal: encode_seal(&receipt)?, journal: receipt.journal, claim_digest, conditional_receipt, stats })
+}
+
+/// Runs the guest in the executor and builds the `selector || claim_digest` seal the mock
+/// verifier checks, skipping proof generation entirely.
+fn execute_dev(env: ExecutorEnv) -> Result<Proven> {
+    info!("executing guest in dev mode (no proof)");
+    let session = default_executor().execute(env, LANE_RACER_PROVER_ELF)?;
+    check_exit(session.exit_code)?;
+    let stats = ExecutionStats {
+        total_cycles: session.segments.iter().map(|segment| 1u64 << segment.po2).sum(),
+        user_cycles: session.segments.iter().map(|segment| segment.cycles as u64).sum(),
+        segments: session.segments.len(),
+        peak_memory_bytes: None,
+    };
+    let claim_digest = ReceiptClaim::ok(LANE_RACER_PROVER_ID, session.journal.bytes.clone()).digest();
+    let mut seal = DEV_MODE_SELECTOR.to_vec();
+    seal.extend_from_slice(claim_digest.as_bytes());
+    Ok(Proven { seal, journal: session.journal, claim_digest, conditional_receipt: None, stats })
+}
+
+/// Fails unless the guest halted normally, explaining user exit codes from `shared::exit`.
+fn check_exit(exit_code: ExitCode) -> Result<()> {
+    match exit_code {
+        ExitCode::Halted(0) => Ok(()),
+        ExitCode::Halted(code) => anyhow::bail!("guest rejected the input: {} (exit code {})", shared::exit::describe(code), code),
+        other => anyhow::bail!("guest exited with {:?}", other),
+    }
+}
+
+/// Encodes a Groth16 receipt as `selector || a || b || c`, the layout the groth16-verifier
+/// contract decodes. The selector is the first four bytes of the verifier parameters digest.
+fn encode_seal(receipt: &Receipt) -> Result<Vec<u8>> {
+    let groth16 = receipt.inner.groth16()?;
+    let mut seal = groth16.verifier_parameters.as_bytes()[..4].to_vec();
+    seal.extend_from_slice(&groth16.seal);
+    anyhow::ensure!(seal.len() == SEAL_LEN, "unexpected Groth16 seal length {}", seal.len());
+    Ok(seal)
+}
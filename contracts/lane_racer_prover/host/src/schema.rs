@@ -0,0 +1,214 @@
+//! Request and response bodies of the HTTP API. Handlers read and write exactly these
+//! types and `GET /openapi.json` is generated from them, so the published schema cannot
+//! drift from what the server does.
+
+use crate::billing::Usage;
+use crate::diagnose::Checkpoint;
+use crate::jobs::{JobId, JobStatus, JobSummary, Tier, TierMetrics};
+use crate::prover::{Accelerator, Backend, SealFormat};
+use crate::trace::ActionTrace;
+use lane_racer_sim::{Difficulty, GameConfig, GameResult};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Body of `POST /prove` and `POST /simulate`.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct ProveRequest {
+    /// `GameInput` format the client was written against; the current one when omitted.
+    pub version: Option<u32>,
+    /// `G...` address of the player.
+    pub player: Option<String>,
+    /// Seed the run was played with. Required.
+    pub seed: Option<u64>,
+    /// A plain array of action codes or a compact encoding, see [`ActionTrace`].
+    pub actions: Option<ActionTrace>,
+    /// Client-side game number recorded in the journal; `session_id` when omitted.
+    pub game_id: Option<u32>,
+    /// Soroban session of the run. Required, the proof is bound to it.
+    pub session_id: Option<u32>,
+    /// `C...` address of the contract the proof is for; the server's submit contract when
+    /// omitted.
+    pub contract_id: Option<String>,
+    /// Game rules; the official configuration when omitted.
+    pub config: Option<GameConfig>,
+    /// `easy`, `normal` (default) or `hard`.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Submit the proof on-chain with the server's account after proving.
+    #[serde(default)]
+    pub submit: bool,
+    /// Prior receipt to compose with, hex of its bincode encoding.
+    pub assumption: Option<String>,
+    /// URL to POST the final job status to, signed with `X-Signature-256`. Not called
+    /// when the response already carries the result (a cached proof).
+    pub callback_url: Option<String>,
+    /// Longest the job may wait for a worker. Requests that are unlikely to start in time
+    /// get a 503 with `retry_after_secs`, and queued jobs that miss it fail unstarted.
+    pub max_wait_secs: Option<u64>,
+}
+
+/// Body of `POST /diagnose`: a run and what the frontend made of it.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct DiagnoseRequest {
+    #[serde(flatten)]
+    pub game: ProveRequest,
+    pub claimed_score: u32,
+    /// Frontend state at chosen ticks, compared field by field.
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// Body of every error response.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// Every problem found in a request that failed validation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Vec<String>>,
+    /// Seconds to wait before retrying a rate-limited or unschedulable request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+}
+
+impl ErrorResponse {
+    pub fn new(message: impl Display) -> Self {
+        Self { error: message.to_string(), details: None, retry_after_secs: None }
+    }
+
+    /// A 422 body listing every problem with the request.
+    pub fn invalid(details: Vec<String>) -> Self {
+        Self { details: Some(details), ..Self::new("Invalid request") }
+    }
+
+    pub fn with_details(self, details: Vec<String>) -> Self {
+        Self { details: Some(details), ..self }
+    }
+
+    pub fn retry_after(self, secs: u64) -> Self {
+        Self { retry_after_secs: Some(secs), ..self }
+    }
+}
+
+/// Body of `GET /health`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct HealthResponse {
+    /// Always `ok`.
+    pub status: &'static str,
+}
+
+/// Body of `GET /image_id`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct ImageIdResponse {
+    /// Hex image ID of the guest; proofs verify only against this ID.
+    pub image_id: String,
+}
+
+/// Body of `GET /info`: versions and capabilities of this deployment.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct InfoResponse {
+    pub image_id: String,
+    pub guest_version: &'static str,
+    pub sim_version: u32,
+    pub host_version: &'static str,
+    pub backend: Backend,
+    pub accelerator: AcceleratorInfo,
+    pub seal_formats: Vec<SealFormat>,
+    /// Whether `"submit": true` is honoured.
+    pub submit_enabled: bool,
+    /// Whether `callback_url` is honoured.
+    pub callbacks_enabled: bool,
+}
+
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct AcceleratorInfo {
+    /// Hardware the local backend proves on; `null` for remote and dev backends.
+    pub selected: Option<Accelerator>,
+    /// The accelerator this binary was compiled for.
+    pub built: Accelerator,
+    /// Accelerators this machine has a device for.
+    pub detected: Vec<Accelerator>,
+}
+
+/// Body of `POST /prove` and `POST /admin/jobs/<id>/cancel`: the job and its status.
+/// `POST /prove` answers 200 when a deduplicated request is already done, 202 otherwise.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct JobResponse {
+    pub job_id: JobId,
+    #[serde(flatten)]
+    pub status: JobStatus,
+}
+
+/// Body of `GET /jobs/<id>`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct JobStatusResponse {
+    #[serde(flatten)]
+    pub status: JobStatus,
+    /// Resources the job consumed, once a worker ran it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// Body of `POST /simulate`: the result the guest would commit.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct SimulateResponse {
+    #[serde(flatten)]
+    pub result: SimulatedResult,
+    pub simulate_time_ms: f64,
+}
+
+/// A `GameResult` with `contract_id` hex encoded, as `POST /simulate` reports it.
+pub struct SimulatedResult(pub GameResult);
+
+impl serde::Serialize for SimulatedResult {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut body = serde_json::to_value(&self.0).map_err(serde::ser::Error::custom)?;
+        body["contract_id"] = hex::encode(self.0.contract_id).into();
+        serde::Serialize::serialize(&body, serializer)
+    }
+}
+
+impl JsonSchema for SimulatedResult {
+    fn schema_name() -> String {
+        "SimulatedResult".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = GameResult::json_schema(gen).into_object();
+        schema.object().properties.insert("contract_id".to_string(), gen.subschema_for::<String>());
+        schema.into()
+    }
+}
+
+/// Body of `GET /metrics`, covering the jobs of the caller's tenant.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct MetricsResponse {
+    pub tiers: BTreeMap<Tier, TierMetrics>,
+    /// The same per tenant, for keys without a tenant.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tenants: BTreeMap<String, BTreeMap<Tier, TierMetrics>>,
+}
+
+/// Body of `GET /admin/jobs`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct AdminJobsResponse {
+    /// Whether intake is paused.
+    pub paused: bool,
+    /// Unfinished jobs of the caller's tenant in ID order.
+    pub jobs: Vec<JobSummary>,
+}
+
+/// Body of `POST /admin/pause` and `POST /admin/resume`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+/// Body of `POST /admin/drain`.
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct DrainResponse {
+    /// Number of queued jobs of the caller's tenant cancelled.
+    pub cancelled: usize,
+}
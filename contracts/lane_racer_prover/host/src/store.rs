@@ -0,0 +1,217 @@
+use crate::billing::Usage;
+use crate::jobs::{JobId, JobStatus, Tier};
+use anyhow::{Context, Result};
+use shared::GameInput;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// An unfinished job, written when it is queued and removed once its outcome is stored.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct JobRecord {
+    pub input: GameInput,
+    pub submit: bool,
+    /// Hex of the bincode-encoded assumption receipt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assumption: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    pub owner: Option<String>,
+    pub tier: Tier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// A finished job: its final status plus what is needed to deduplicate against it.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct OutcomeRecord {
+    /// Hex of the request hash.
+    pub hash: String,
+    pub owner: Option<String>,
+    pub tier: Tier,
+    /// Tenant allowed to retrieve the outcome; any key may when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    pub status: JobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// Jobs read back on startup, each list in ID order.
+#[derive(Default)]
+pub struct Recovered {
+    pub unfinished: Vec<(JobId, JobRecord)>,
+    pub finished: Vec<(JobId, OutcomeRecord)>,
+}
+
+/// On-disk job state: `jobs/<id>.json` for unfinished jobs and `proofs/<id>.json` for
+/// outcomes. Files are written to a temporary name and renamed, so a crash never leaves
+/// a truncated record behind.
+pub struct JobStore {
+    jobs: PathBuf,
+    proofs: PathBuf,
+}
+
+impl JobStore {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let store = Self { jobs: dir.join("jobs"), proofs: dir.join("proofs") };
+        for dir in [&store.jobs, &store.proofs] {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        Ok(store)
+    }
+
+    pub fn save_job(&self, id: JobId, record: &JobRecord) -> Result<()> {
+        write_atomic(&self.jobs.join(format!("{}.json", id)), record)
+    }
+
+    /// Stores the outcome, then drops the job record it supersedes.
+    pub fn save_outcome(&self, id: JobId, record: &OutcomeRecord) -> Result<()> {
+        write_atomic(&self.proofs.join(format!("{}.json", id)), record)?;
+        remove_if_exists(&self.jobs.join(format!("{}.json", id)))
+    }
+
+    /// Reads every record back. A job record whose outcome was also stored (a crash
+    /// between the two writes) is finished, so its stale record is removed. Unreadable
+    /// files are skipped with a warning rather than blocking startup.
+    pub fn recover(&self) -> Result<Recovered> {
+        let mut recovered = Recovered { finished: read_dir(&self.proofs)?, ..Recovered::default() };
+        let finished: HashSet<JobId> = recovered.finished.iter().map(|(id, _)| *id).collect();
+        for (id, record) in read_dir::<JobRecord>(&self.jobs)? {
+            if finished.contains(&id) {
+                remove_if_exists(&self.jobs.join(format!("{}.json", id)))?;
+            } else if record.input.version != lane_racer_sim::INPUT_VERSION {
+                warn!(job_id = id, version = record.input.version, "skipping job record with an unsupported input version");
+            } else {
+                recovered.unfinished.push((id, record));
+            }
+        }
+        Ok(recovered)
+    }
+}
+
+fn write_atomic(path: &Path, value: &impl serde::Serialize) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec(value)?).with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("renaming {}", tmp.display()))
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("removing {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parses every `<id>.json` in `dir`, sorted by ID.
+fn read_dir<T: serde::de::DeserializeOwned>(dir: &Path) -> Result<Vec<(JobId, T)>> {
+    let mut records = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|stem| stem.to_str()?.parse::<JobId>().ok()) else { continue };
+        let parsed = std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(serde_json::from_slice(&bytes)?));
+        match parsed {
+            Ok(record) => records.push((id, record)),
+            Err(e) => warn!(path = %path.display(), error = %e, "skipping unreadable job record"),
+        }
+    }
+    records.sort_by_key(|(id, _)| *id);
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::PackedActions;
+
+    /// A fresh store under the system temp directory, removed when dropped.
+    struct TempStore {
+        dir: PathBuf,
+        store: JobStore,
+    }
+
+    impl TempStore {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("lane-racer-store-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            let store = JobStore::open(&dir).unwrap();
+            Self { dir, store }
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn job(version: u32) -> JobRecord {
+        let input = GameInput {
+            version,
+            seed: 1,
+            actions: PackedActions::pack(&[]),
+            player_address: "GPLAYER".to_string(),
+            game_id: 1,
+            session_id: 1,
+            contract_id: [0; 32],
+            config: Default::default(),
+            difficulty: Default::default(),
+        };
+        JobRecord { input, submit: false, assumption: None, callback_url: None, owner: None, tier: Tier::default(), account: None, tenant: None }
+    }
+
+    fn outcome() -> OutcomeRecord {
+        OutcomeRecord {
+            hash: hex::encode([1; 32]),
+            owner: None,
+            tier: Tier::default(),
+            tenant: None,
+            status: JobStatus::Failed { error: "boom".to_string() },
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn recover_returns_unfinished_and_finished_jobs_in_id_order() {
+        let temp = TempStore::new("order");
+        temp.store.save_job(3, &job(lane_racer_sim::INPUT_VERSION)).unwrap();
+        temp.store.save_job(1, &job(lane_racer_sim::INPUT_VERSION)).unwrap();
+        temp.store.save_job(2, &job(lane_racer_sim::INPUT_VERSION)).unwrap();
+        temp.store.save_outcome(2, &outcome()).unwrap();
+        let recovered = temp.store.recover().unwrap();
+        assert_eq!(recovered.unfinished.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(recovered.finished.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn recover_removes_job_records_that_already_have_an_outcome() {
+        let temp = TempStore::new("stale");
+        temp.store.save_job(1, &job(lane_racer_sim::INPUT_VERSION)).unwrap();
+        // A crash between writing the outcome and removing the job record.
+        write_atomic(&temp.store.proofs.join("1.json"), &outcome()).unwrap();
+        let recovered = temp.store.recover().unwrap();
+        assert!(recovered.unfinished.is_empty());
+        assert_eq!(recovered.finished.len(), 1);
+        assert!(!temp.store.jobs.join("1.json").exists());
+    }
+
+    #[test]
+    fn recover_skips_unsupported_versions_and_unreadable_files() {
+        let temp = TempStore::new("skip");
+        temp.store.save_job(1, &job(lane_racer_sim::INPUT_VERSION + 1)).unwrap();
+        std::fs::write(temp.store.jobs.join("2.json"), "{ truncated").unwrap();
+        std::fs::write(temp.store.jobs.join("notes.txt"), "ignored").unwrap();
+        temp.store.save_job(3, &job(lane_racer_sim::INPUT_VERSION)).unwrap();
+        let recovered = temp.store.recover().unwrap();
+        assert_eq!(recovered.unfinished.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![3]);
+    }
+}
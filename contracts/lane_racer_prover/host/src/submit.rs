@@ -0,0 +1,255 @@
+use crate::prover::ProofResponse;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use shared::GameInput;
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::info;
+
+/// Settings for submitting `submit_score` on behalf of players. Transactions are built,
+/// simulated, signed and sent through the `stellar` CLI, so the source account may be a
+/// secret key or the name of an identity in the CLI keystore.
+///
+/// `submit_score` requires the player's authorization, so the source account must be able
+/// to authorize for the player (e.g. the player's own key on a custodial deployment).
+#[derive(Clone, clap::Args)]
+pub struct SubmitConfig {
+    /// Lane Racer contract ID. Enables `"submit": true` on prove requests.
+    #[arg(long = "submit-contract-id")]
+    pub contract_id: Option<String>,
+
+    /// Soroban RPC endpoint used for submission.
+    #[arg(long = "submit-rpc-url", default_value = "https://soroban-testnet.stellar.org")]
+    pub rpc_url: String,
+
+    /// Network passphrase used for signing.
+    #[arg(long = "submit-network-passphrase", default_value = "Test SDF Network ; September 2015")]
+    pub network_passphrase: String,
+
+    /// Signing account: a secret key or a `stellar keys` identity name.
+    #[arg(long = "submit-source", env = "PROVER_SOURCE_ACCOUNT", hide_env_values = true)]
+    pub source: Option<String>,
+
+    /// Path to the `stellar` CLI binary.
+    #[arg(long = "stellar-cli", default_value = "stellar")]
+    pub stellar_cli: PathBuf,
+
+    /// Before queueing a run for the submit contract, read its session over RPC and reject
+    /// runs `submit_score` would refuse: unknown or closed sessions, another player, or a
+    /// seed other than the session's.
+    #[arg(long, requires = "contract_id", requires = "source")]
+    pub check_sessions: bool,
+}
+
+impl SubmitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.contract_id.is_some() && self.source.is_some()
+    }
+
+    /// Reads the run's session from the contract and returns why `submit_score` would
+    /// refuse the run, or `None` if it would be accepted. Errors mean the session could
+    /// not be read.
+    pub fn session_problem(&self, input: &GameInput) -> Result<Option<String>> {
+        check_session(input, |session_id| self.get_session(session_id))
+    }
+
+    /// Simulates `get_session` through the `stellar` CLI and returns what it printed.
+    fn get_session(&self, session_id: u32) -> Result<Vec<u8>> {
+        let (Some(contract_id), Some(source)) = (&self.contract_id, &self.source) else {
+            anyhow::bail!("session checks are not configured");
+        };
+        let output = Command::new(&self.stellar_cli)
+            .env("STELLAR_ACCOUNT", source)
+            .args(["contract", "invoke", "--send", "no"])
+            .args(["--id", contract_id])
+            .args(["--rpc-url", &self.rpc_url])
+            .args(["--network-passphrase", &self.network_passphrase])
+            .args(["--", "get_session"])
+            .args(["--session_id", &session_id.to_string()])
+            .output()
+            .with_context(|| format!("running {}", self.stellar_cli.display()))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::ensure!(output.status.success(), "get_session failed: {}", stderr.trim());
+        Ok(output.stdout)
+    }
+
+    /// Invokes `submit_score` with the proof's prebuilt arguments and returns the
+    /// transaction hash.
+    pub fn submit_score(&self, proof: &ProofResponse) -> Result<String> {
+        let (Some(contract_id), Some(source)) = (&self.contract_id, &self.source) else {
+            anyhow::bail!("transaction submission is not configured");
+        };
+        let args = &proof.args.submit_score;
+        info!(session_id = args.session_id, "invoking submit_score");
+        // The source goes through the environment so secret keys never appear in `ps`.
+        let output = Command::new(&self.stellar_cli)
+            .env("STELLAR_ACCOUNT", source)
+            .args(["contract", "invoke", "--send", "yes"])
+            .args(["--id", contract_id])
+            .args(["--rpc-url", &self.rpc_url])
+            .args(["--network-passphrase", &self.network_passphrase])
+            .args(["--", "submit_score"])
+            .args(["--session_id", &args.session_id.to_string()])
+            .args(["--player", &args.player])
+            .args(["--score", &args.score.to_string()])
+            .args(["--proof", &serde_json::to_string(&args.proof)?])
+            .output()
+            .with_context(|| format!("running {}", self.stellar_cli.display()))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::ensure!(output.status.success(), "submit_score failed: {}", stderr.trim());
+        // The CLI logs "Transaction hash is <hex>" while sending.
+        stderr
+            .split(|c: char| !c.is_ascii_hexdigit())
+            .find(|word| word.len() == 64)
+            .map(str::to_string)
+            .context("stellar CLI did not report a transaction hash")
+    }
+}
+
+/// Reads the run's session through `get_session`, which returns the CLI's `get_session`
+/// output for a session ID, and returns why `submit_score` would refuse the run.
+fn check_session(input: &GameInput, get_session: impl FnOnce(u32) -> Result<Vec<u8>>) -> Result<Option<String>> {
+    let session = parse_session(&get_session(input.session_id)?)?;
+    Ok(session_problem(session.as_ref(), input))
+}
+
+/// The fields of a `GameSession` that `submit_score` checks before verifying the proof.
+#[derive(Debug, PartialEq)]
+struct Session {
+    /// `SessionStatus`; the CLI prints `repr(u32)` enums as numbers.
+    status: u64,
+    player: String,
+    seed: Option<u64>,
+}
+
+/// Parses `get_session` output: a `GameSession` as JSON, or `null` for an unknown session.
+fn parse_session(stdout: &[u8]) -> Result<Option<Session>> {
+    let session: Value = serde_json::from_slice(stdout).context("get_session returned invalid JSON")?;
+    if session.is_null() {
+        return Ok(None);
+    }
+    let status = session["status"].as_u64().context("get_session returned no status")?;
+    let player = session["player"].as_str().context("get_session returned no player")?.to_string();
+    // u64 values may be printed as numbers or strings depending on the CLI version.
+    let seed = match &session["seed"] {
+        Value::Null => None,
+        Value::Number(seed) => Some(seed.as_u64().context("get_session returned an invalid seed")?),
+        Value::String(seed) => Some(seed.parse().context("get_session returned an invalid seed")?),
+        _ => anyhow::bail!("get_session returned an invalid seed"),
+    };
+    Ok(Some(Session { status, player, seed }))
+}
+
+/// Compares a session with the checks `submit_score` makes before verifying the proof.
+fn session_problem(session: Option<&Session>, input: &GameInput) -> Option<String> {
+    let id = input.session_id;
+    let Some(session) = session else {
+        return Some(format!("session {id} does not exist"));
+    };
+    match session.status {
+        0 => {}
+        1 => return Some(format!("session {id} already has a score")),
+        2 => return Some(format!("session {id} has expired")),
+        3 => return Some(format!("session {id} is disputed")),
+        _ => return Some(format!("session {id} is no longer active")),
+    }
+    if session.player != input.player_address {
+        return Some(format!("session {id} belongs to another player"));
+    }
+    match session.seed {
+        Some(seed) if seed != input.seed => {
+            Some(format!("session {id} requires seed {seed}, the run used {}", input.seed))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::PackedActions;
+
+    fn input() -> GameInput {
+        GameInput {
+            version: lane_racer_sim::INPUT_VERSION,
+            seed: 42,
+            actions: PackedActions::pack(&[]),
+            player_address: "GPLAYER".to_string(),
+            game_id: 1,
+            session_id: 7,
+            contract_id: [7; 32],
+            config: Default::default(),
+            difficulty: Default::default(),
+        }
+    }
+
+    fn session(status: u64, player: &str, seed: Option<u64>) -> Session {
+        Session { status, player: player.to_string(), seed }
+    }
+
+    /// Runs the check against a `get_session` that prints `stdout` for session 7.
+    fn check(stdout: &str) -> Result<Option<String>> {
+        check_session(&input(), |session_id| {
+            assert_eq!(session_id, 7);
+            Ok(stdout.as_bytes().to_vec())
+        })
+    }
+
+    #[test]
+    fn parses_sessions_as_the_cli_prints_them() {
+        let printed = r#"{"session_id":7,"player":"GPLAYER","score":0,"status":0,"seed":"42","practice":false}"#;
+        assert_eq!(parse_session(printed.as_bytes()).unwrap(), Some(session(0, "GPLAYER", Some(42))));
+
+        let numeric_seed = r#"{"player":"GPLAYER","status":1,"seed":18446744073709551615}"#;
+        assert_eq!(parse_session(numeric_seed.as_bytes()).unwrap(), Some(session(1, "GPLAYER", Some(u64::MAX))));
+
+        let practice = r#"{"player":"GPLAYER","status":0,"seed":null}"#;
+        assert_eq!(parse_session(practice.as_bytes()).unwrap(), Some(session(0, "GPLAYER", None)));
+
+        assert_eq!(parse_session(b"null\n").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_output_that_is_not_a_session() {
+        for stdout in [
+            "",
+            "not json",
+            r#"{"player":"GPLAYER"}"#,
+            r#"{"status":0}"#,
+            r#"{"player":"GPLAYER","status":0,"seed":"x"}"#,
+            r#"{"player":"GPLAYER","status":0,"seed":-1}"#,
+        ] {
+            assert!(parse_session(stdout.as_bytes()).is_err(), "{stdout:?}");
+        }
+    }
+
+    #[test]
+    fn maps_session_state_to_the_problem_submit_score_would_report() {
+        let input = input();
+        let problem = |session: Session| session_problem(Some(&session), &input);
+
+        assert_eq!(session_problem(None, &input).as_deref(), Some("session 7 does not exist"));
+        assert_eq!(problem(session(1, "GPLAYER", Some(42))).as_deref(), Some("session 7 already has a score"));
+        assert_eq!(problem(session(2, "GPLAYER", Some(42))).as_deref(), Some("session 7 has expired"));
+        assert_eq!(problem(session(3, "GPLAYER", Some(42))).as_deref(), Some("session 7 is disputed"));
+        assert_eq!(problem(session(0, "GOTHER", Some(42))).as_deref(), Some("session 7 belongs to another player"));
+        assert_eq!(
+            problem(session(0, "GPLAYER", Some(43))).as_deref(),
+            Some("session 7 requires seed 43, the run used 42")
+        );
+        assert_eq!(problem(session(0, "GPLAYER", Some(42))), None);
+        assert_eq!(problem(session(0, "GPLAYER", None)), None);
+    }
+
+    #[test]
+    fn checks_the_run_against_the_session_get_session_prints() {
+        assert_eq!(check("null").unwrap().as_deref(), Some("session 7 does not exist"));
+        let settled = r#"{"player":"GPLAYER","status":1,"seed":"42"}"#;
+        assert_eq!(check(settled).unwrap().as_deref(), Some("session 7 already has a score"));
+        assert_eq!(check(r#"{"player":"GPLAYER","status":0,"seed":"42"}"#).unwrap(), None);
+        assert!(check("error: no such contract").is_err());
+
+        let failing = check_session(&input(), |_| anyhow::bail!("get_session failed: rpc unreachable"));
+        assert_eq!(failing.unwrap_err().to_string(), "get_session failed: rpc unreachable");
+    }
+}
@@ -0,0 +1,122 @@
+//! Action trace encodings accepted by `POST /prove` and `POST /simulate`.
+//!
+//! `actions` may be a plain JSON array of action codes, or an object naming a compact
+//! encoding whose `data` is standard base64:
+//!
+//! - `{"encoding": "rle", "data": ...}`: pairs of bytes `[action, run_length]`, each
+//!   expanding to `run_length` (1-255) repeats of `action`. Longer runs use several pairs.
+//! - `{"encoding": "packed", "length": n, "data": ...}`: two bits per action, four actions
+//!   per byte, least significant bits first. `length` gives the number of actions, since
+//!   the last byte may be padded.
+
+use base64::Engine as _;
+
+/// Action codes of a run, one per tick, as a plain array or a compact encoding.
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum ActionTrace {
+    Plain(Vec<u32>),
+    Encoded(EncodedTrace),
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(tag = "encoding", rename_all = "lowercase")]
+pub enum EncodedTrace {
+    Rle { data: String },
+    Packed { length: usize, data: String },
+}
+
+impl ActionTrace {
+    /// Expands the trace into one action code per tick, refusing to grow past `max_actions`.
+    pub fn decode(self, max_actions: usize) -> Result<Vec<u32>, String> {
+        let encoded = match self {
+            ActionTrace::Plain(actions) => return Ok(actions),
+            ActionTrace::Encoded(encoded) => encoded,
+        };
+        let too_long = |len: usize| format!("actions has {} entries, the maximum is {}", len, max_actions);
+        match encoded {
+            EncodedTrace::Rle { data } => {
+                let bytes = decode_base64(&data)?;
+                if bytes.len() % 2 != 0 {
+                    return Err("actions: rle data must be whole [action, run_length] pairs".to_string());
+                }
+                let total: usize = bytes.chunks(2).map(|pair| pair[1] as usize).sum();
+                if total > max_actions {
+                    return Err(too_long(total));
+                }
+                let mut actions = Vec::with_capacity(total);
+                for (index, pair) in bytes.chunks(2).enumerate() {
+                    if pair[1] == 0 {
+                        return Err(format!("actions: rle run {} has length 0", index));
+                    }
+                    actions.extend(std::iter::repeat(pair[0] as u32).take(pair[1] as usize));
+                }
+                Ok(actions)
+            }
+            EncodedTrace::Packed { length, data } => {
+                if length > max_actions {
+                    return Err(too_long(length));
+                }
+                let bytes = decode_base64(&data)?;
+                if bytes.len() != length.div_ceil(4) {
+                    return Err(format!("actions: packed data is {} bytes, expected {} for length {}", bytes.len(), length.div_ceil(4), length));
+                }
+                Ok((0..length).map(|tick| ((bytes[tick / 4] >> (2 * (tick % 4))) & 0b11) as u32).collect())
+            }
+        }
+    }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("actions: invalid base64: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rle(bytes: &[u8]) -> ActionTrace {
+        ActionTrace::Encoded(EncodedTrace::Rle { data: base64::engine::general_purpose::STANDARD.encode(bytes) })
+    }
+
+    fn packed(length: usize, bytes: &[u8]) -> ActionTrace {
+        ActionTrace::Encoded(EncodedTrace::Packed { length, data: base64::engine::general_purpose::STANDARD.encode(bytes) })
+    }
+
+    #[test]
+    fn plain_traces_are_returned_as_given() {
+        assert_eq!(ActionTrace::Plain(vec![0, 1, 2]).decode(10), Ok(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn rle_expands_each_run() {
+        assert_eq!(rle(&[1, 2, 0, 1, 2, 3]).decode(10), Ok(vec![1, 1, 0, 2, 2, 2]));
+    }
+
+    #[test]
+    fn rle_rejects_odd_data_empty_runs_and_long_traces() {
+        assert!(rle(&[1, 2, 0]).decode(10).is_err());
+        assert!(rle(&[1, 0]).decode(10).is_err());
+        assert_eq!(rle(&[1, 255, 1, 255]).decode(300), Err("actions has 510 entries, the maximum is 300".to_string()));
+    }
+
+    #[test]
+    fn packed_reads_two_bits_per_action_low_bits_first() {
+        // 0b10_01_00_01 then 0b00_00_00_10, the padding bits ignored.
+        assert_eq!(packed(5, &[0b1001_0001, 0b0000_0010]).decode(10), Ok(vec![1, 0, 1, 2, 2]));
+    }
+
+    #[test]
+    fn packed_rejects_wrong_lengths() {
+        assert!(packed(5, &[0]).decode(10).is_err());
+        assert!(packed(11, &[0, 0, 0]).decode(10).is_err());
+    }
+
+    #[test]
+    fn invalid_base64_is_reported() {
+        let trace = ActionTrace::Encoded(EncodedTrace::Rle { data: "not base64!".to_string() });
+        assert!(trace.decode(10).unwrap_err().starts_with("actions: invalid base64"));
+    }
+}
@@ -0,0 +1,23 @@
+use shared::strkey::{self, StrkeyError, ACCOUNT_STRKEY_LEN};
+
+/// Decodes a `C...` contract strkey into its contract ID, with an error message naming the
+/// `contract_id` field.
+pub fn decode_contract(address: &str) -> Result<[u8; 32], String> {
+    strkey::decode_contract(address).map_err(|e| match e {
+        StrkeyError::Length => format!("contract_id must be a {}-character C... Stellar address", ACCOUNT_STRKEY_LEN),
+        StrkeyError::Base32 => "contract_id is not valid base32".to_string(),
+        StrkeyError::Version => "contract_id is not a contract address".to_string(),
+        StrkeyError::Checksum => "contract_id address checksum is invalid".to_string(),
+    })
+}
+
+/// Decodes a `G...` account strkey into its raw ed25519 public key, with an error message
+/// naming the `player` field.
+pub fn decode_account(address: &str) -> Result<[u8; 32], String> {
+    strkey::decode_account(address).map_err(|e| match e {
+        StrkeyError::Length => format!("player must be a {}-character G... Stellar address", ACCOUNT_STRKEY_LEN),
+        StrkeyError::Base32 => "player is not valid base32".to_string(),
+        StrkeyError::Version => "player is not an account address".to_string(),
+        StrkeyError::Checksum => "player address checksum is invalid".to_string(),
+    })
+}
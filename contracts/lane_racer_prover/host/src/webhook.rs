@@ -0,0 +1,64 @@
+use crate::jobs::{JobId, JobStatus};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Delivery attempts per callback, with the delay doubling between them.
+const ATTEMPTS: u32 = 4;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(2);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Checks a callback URL when the job is submitted rather than when it finishes.
+pub fn validate_url(url: &str) -> Result<(), String> {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        Err("callback_url must be an http:// or https:// URL".to_string())
+    }
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`, sent as `X-Signature-256: sha256=<hex>`.
+/// Receivers recompute it over the raw body to authenticate the callback.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// POSTs the job's final status to `url` on a background thread, retrying failures and
+/// non-2xx responses with exponential backoff.
+pub fn deliver(url: String, secret: String, id: JobId, status: &JobStatus) {
+    let mut body = serde_json::to_value(status).expect("job status serializes");
+    body["job_id"] = serde_json::json!(id);
+    post_signed(url, secret, id, body.to_string(), "callback");
+}
+
+/// Signs `body` and POSTs it like [`deliver`]; `kind` names the delivery in logs.
+pub fn post_signed(url: String, secret: String, id: JobId, body: String, kind: &'static str) {
+    let signature = format!("sha256={}", sign(&secret, body.as_bytes()));
+    std::thread::spawn(move || {
+        let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+        let mut delay = FIRST_RETRY_DELAY;
+        for attempt in 1..=ATTEMPTS {
+            let response = agent
+                .post(&url)
+                .set("Content-Type", "application/json")
+                .set("X-Job-Id", &id.to_string())
+                .set("X-Signature-256", &signature)
+                .send_string(&body);
+            match response {
+                Ok(_) => {
+                    info!(job_id = id, attempt, kind, "webhook delivered");
+                    return;
+                }
+                Err(e) => warn!(job_id = id, attempt, kind, error = %e, "webhook failed"),
+            }
+            if attempt < ATTEMPTS {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+        warn!(job_id = id, kind, "giving up on webhook");
+    });
+}
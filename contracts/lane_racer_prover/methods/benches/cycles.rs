@@ -0,0 +1,37 @@
+//! Reports the guest's cycle count for representative traces, to measure how simulation
+//! changes affect proving cost. Run with `cargo bench -p methods`.
+
+use lane_racer_sim::{Action, Difficulty, GameConfig, GameInput, PackedActions, INPUT_VERSION};
+use methods::LANE_RACER_PROVER_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+const PLAYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+/// A trace that weaves between lanes without ever holding still for long.
+fn weaving_trace(ticks: usize) -> PackedActions {
+    use Action::{Left, Right, Stay};
+    let actions: Vec<Action> = (0..ticks).map(|tick| [Stay, Stay, Left, Stay, Stay, Right][tick % 6]).collect();
+    PackedActions::pack(&actions)
+}
+
+fn main() {
+    println!("{:>8}  {:>14}  {:>14}  {:>8}", "ticks", "user_cycles", "total_cycles", "segments");
+    for ticks in [1_000, 10_000, 100_000] {
+        let input = GameInput {
+            version: INPUT_VERSION,
+            seed: 42,
+            actions: weaving_trace(ticks),
+            player_address: PLAYER.to_string(),
+            game_id: 1,
+            session_id: 1,
+            contract_id: [0; 32],
+            config: GameConfig::default(),
+            difficulty: Difficulty::Normal,
+        };
+        let env = ExecutorEnv::builder().write(&input).unwrap().build().unwrap();
+        let session = default_executor().execute(env, LANE_RACER_PROVER_ELF).unwrap();
+        let user_cycles: u64 = session.segments.iter().map(|segment| segment.cycles as u64).sum();
+        let total_cycles: u64 = session.segments.iter().map(|segment| 1u64 << segment.po2).sum();
+        println!("{:>8}  {:>14}  {:>14}  {:>8}", ticks, user_cycles, total_cycles, session.segments.len());
+    }
+}
@@ -1,3 +1,31 @@
+use std::path::Path;
+
 fn main() {
-    risc0_build::embed_methods();
+    let guests = risc0_build::embed_methods();
+    for guest in &guests {
+        write_image_id(&guest.name, guest.image_id.as_bytes());
+    }
+
+    // Expose the guest package version so the host can report which build it serves.
+    let manifest = std::fs::read_to_string("guest/Cargo.toml").expect("guest manifest is readable");
+    let version = manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim().trim_matches('"'))
+        .expect("guest manifest declares a version");
+    println!("cargo:rustc-env=LANE_RACER_GUEST_VERSION={version}");
+    println!("cargo:rerun-if-changed=guest/Cargo.toml");
+}
+
+/// Writes `<name>_image_id.txt` (hex) and `.json` next to the built binaries, e.g.
+/// `target/release/`, for deployment scripts that set a contract's image ID.
+fn write_image_id(name: &str, image_id: &[u8]) {
+    let hex: String = image_id.iter().map(|byte| format!("{byte:02x}")).collect();
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    // OUT_DIR is <target>/<profile>/build/<crate>-<hash>/out
+    let profile_dir = Path::new(&out_dir).ancestors().nth(3).expect("OUT_DIR is inside the target directory");
+    let name = name.to_lowercase();
+    std::fs::write(profile_dir.join(format!("{name}_image_id.txt")), format!("{hex}\n")).expect("image ID file is writable");
+    let json = format!("{{\n  \"name\": \"{name}\",\n  \"image_id\": \"{hex}\"\n}}\n");
+    std::fs::write(profile_dir.join(format!("{name}_image_id.json")), json).expect("image ID file is writable");
 }
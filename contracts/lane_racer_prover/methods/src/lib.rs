@@ -1,22 +1,28 @@
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
 
-use serde::{Deserialize, Serialize};
+/// Version of the `lane_racer_prover` guest package embedded above.
+pub const GUEST_VERSION: &str = env!("LANE_RACER_GUEST_VERSION");
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GameInput {
-    pub seed: u64,
-    pub actions: Vec<u8>,
-    pub player_address: String,
-    pub game_id: u64,
+/// `LANE_RACER_PROVER_ID` as the contracts take it: the digest bytes, each word
+/// little-endian.
+pub const LANE_RACER_PROVER_ID_BYTES: [u8; 32] = image_id_bytes(LANE_RACER_PROVER_ID);
+
+/// Converts image ID words to digest bytes.
+pub const fn image_id_bytes(words: [u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let mut i = 0;
+    while i < 8 {
+        let word = words[i].to_le_bytes();
+        bytes[4 * i] = word[0];
+        bytes[4 * i + 1] = word[1];
+        bytes[4 * i + 2] = word[2];
+        bytes[4 * i + 3] = word[3];
+        i += 1;
+    }
+    bytes
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GameResult {
-    pub player_address: String,
-    pub game_id: u64,
-    pub score: u32,
-    pub obstacles_dodged: u32,
-    pub gems_collected: u32,
-    pub speed_reached: u32,
-    pub collision_occurred: bool,
-}
\ No newline at end of file
+/// Hex of `LANE_RACER_PROVER_ID_BYTES`, as `verify` and deployment scripts take it.
+pub fn image_id_hex() -> String {
+    LANE_RACER_PROVER_ID_BYTES.iter().map(|byte| format!("{byte:02x}")).collect()
+}
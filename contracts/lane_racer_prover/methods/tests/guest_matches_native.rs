@@ -0,0 +1,135 @@
+use lane_racer_sim::{
+    simulate_game, Action, Difficulty, GameConfig, GameInput, PackedActions, INPUT_VERSION, SIM_VERSION,
+};
+use methods::LANE_RACER_PROVER_ELF;
+use proptest::prelude::*;
+use risc0_zkvm::{default_executor, ExecutorEnv, ExitCode};
+use shared::{actions_hash, config_hash, exit, strkey, GameJournal};
+
+const PLAYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+/// Executes the guest (without proving) and returns its raw journal.
+fn execute_guest(input: &GameInput) -> Vec<u8> {
+    let env = ExecutorEnv::builder().write(input).unwrap().build().unwrap();
+    let session = default_executor().execute(env, LANE_RACER_PROVER_ELF).unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    session.journal.bytes
+}
+
+/// Executes the guest (without proving) and decodes its journal.
+fn run_guest(input: &GameInput) -> GameJournal {
+    GameJournal::decode(&execute_guest(input)).expect("journal has the fixed layout")
+}
+
+/// The Soroban-side decoder is tested against this recorded journal; see its
+/// `fixtures/README.md` for the run.
+#[test]
+fn guest_journal_matches_the_contract_fixture() {
+    let trace = include_str!("../../../lane-racer-journal/fixtures/trace.txt");
+    let expected = include_str!("../../../lane-racer-journal/fixtures/journal.hex");
+    let codes: Vec<u32> = trace.trim().chars().map(|c| c.to_digit(10).unwrap()).collect();
+    let actions: Vec<Action> = codes.into_iter().map(|code| Action::try_from(code).unwrap()).collect();
+    let input = GameInput {
+        version: INPUT_VERSION,
+        seed: 42,
+        actions: PackedActions::pack(&actions),
+        player_address: PLAYER.to_string(),
+        game_id: 7,
+        session_id: 7,
+        contract_id: [0xab; 32],
+        config: GameConfig::default(),
+        difficulty: Difficulty::Hard,
+    };
+    assert_eq!(hex::encode(execute_guest(&input)), expected.trim());
+}
+
+#[test]
+fn guest_rejects_unknown_input_versions() {
+    let input = GameInput {
+        version: INPUT_VERSION + 1,
+        seed: 42,
+        actions: PackedActions::default(),
+        player_address: PLAYER.to_string(),
+        game_id: 1,
+        session_id: 1,
+        contract_id: [0; 32],
+        config: GameConfig::default(),
+        difficulty: Difficulty::Normal,
+    };
+    let env = ExecutorEnv::builder().write(&input).unwrap().build().unwrap();
+    let session = default_executor().execute(env, LANE_RACER_PROVER_ELF).unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(exit::UNSUPPORTED_VERSION as u32));
+}
+
+#[test]
+fn guest_flags_empty_traces() {
+    let input = GameInput {
+        version: INPUT_VERSION,
+        seed: 42,
+        actions: PackedActions::default(),
+        player_address: PLAYER.to_string(),
+        game_id: 1,
+        session_id: 1,
+        contract_id: [0; 32],
+        config: GameConfig::default(),
+        difficulty: Difficulty::Normal,
+    };
+    let journal = run_guest(&input);
+    assert!(journal.empty_trace);
+    assert_eq!((journal.score, journal.ticks, journal.obstacles_spawned), (0, 0, 0));
+}
+
+proptest! {
+    // Each case runs the zkVM executor, so keep the count modest.
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn guest_matches_native(
+        seed in any::<u64>(),
+        actions in prop::collection::vec(prop::sample::select(Action::ALL.to_vec()), 0..2_000),
+        game_id in any::<u32>(),
+        session_id in any::<u32>(),
+        contract_id in any::<[u8; 32]>(),
+        difficulty in prop::sample::select(vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]),
+    ) {
+        let input = GameInput {
+            version: INPUT_VERSION,
+            seed,
+            actions: PackedActions::pack(&actions),
+            player_address: PLAYER.to_string(),
+            game_id: game_id.into(),
+            session_id,
+            contract_id,
+            config: GameConfig::default(),
+            difficulty,
+        };
+        let native = simulate_game(&input);
+        let guest = run_guest(&input);
+        prop_assert_eq!(guest, GameJournal {
+            sim_version: SIM_VERSION,
+            player: strkey::decode_account(PLAYER).unwrap(),
+            game_id: native.game_id,
+            session_id: native.session_id,
+            contract_id: native.contract_id,
+            seed,
+            actions_hash: actions_hash(&input.actions),
+            config_hash: config_hash(&input.config),
+            score: native.score,
+            obstacles_dodged: native.obstacles_dodged,
+            gems_collected: native.gems_collected,
+            speed_reached: native.speed_reached,
+            shields_collected: native.shields_collected,
+            shields_used: native.shields_used,
+            magnets_collected: native.magnets_collected,
+            near_misses: native.near_misses,
+            best_combo: native.best_combo,
+            lanes: input.config.lanes,
+            difficulty: input.difficulty,
+            collision_occurred: native.collision_occurred,
+            empty_trace: actions.is_empty(),
+            obstacles_spawned: native.obstacles_spawned,
+            gems_spawned: native.gems_spawned,
+            ticks: native.ticks,
+        });
+    }
+}
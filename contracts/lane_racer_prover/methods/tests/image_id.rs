@@ -0,0 +1,20 @@
+use methods::{image_id_hex, LANE_RACER_PROVER_ID, LANE_RACER_PROVER_ID_BYTES};
+use risc0_zkvm::sha::Digest;
+
+#[test]
+fn image_id_bytes_match_the_risc0_digest() {
+    let digest = Digest::from(LANE_RACER_PROVER_ID);
+    assert_eq!(LANE_RACER_PROVER_ID_BYTES, digest.as_bytes());
+    assert_eq!(image_id_hex(), hex::encode(digest.as_bytes()));
+}
+
+#[test]
+fn build_writes_the_image_id_artifacts() {
+    // Test binaries live in <target>/<profile>/deps
+    let exe = std::env::current_exe().unwrap();
+    let profile_dir = exe.parent().unwrap().parent().unwrap();
+    let txt = std::fs::read_to_string(profile_dir.join("lane_racer_prover_image_id.txt")).unwrap();
+    assert_eq!(txt.trim(), image_id_hex());
+    let json = std::fs::read_to_string(profile_dir.join("lane_racer_prover_image_id.json")).unwrap();
+    assert!(json.contains(&format!("\"image_id\": \"{}\"", image_id_hex())));
+}
@@ -0,0 +1,129 @@
+//! Recorded play traces for checking that every implementation of the simulation agrees.
+//!
+//! Each file in `corpus/` is one run as the frontend exports it: the seed, rules and
+//! action codes, plus the scores the player saw. The tests replay every trace through the
+//! native sim and the zkVM executor and fail on the first field that differs, naming the
+//! trace and the field.
+
+use std::{fs, path::Path};
+
+use lane_racer_sim::{Action, Difficulty, GameConfig, GameInput, GameResult, PackedActions, INPUT_VERSION};
+use serde::Deserialize;
+
+/// Player the replays are attributed to. Scores do not depend on it.
+pub const PLAYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+/// Directory holding the recorded traces.
+pub const CORPUS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus");
+
+/// One recorded run.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Trace {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub seed: u64,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Fields left out take their `GameConfig::default()` values.
+    #[serde(default)]
+    pub config: GameConfig,
+    /// One action code per tick (0 stay, 1 left, 2 right, 3 pause).
+    pub actions: String,
+    pub expected: Expected,
+}
+
+/// Results the frontend showed at the end of the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub speed_reached: u32,
+    pub collision_occurred: bool,
+}
+
+impl Expected {
+    /// The fields of `result` the frontend records.
+    pub fn from_result(result: &GameResult) -> Self {
+        Self {
+            score: result.score,
+            obstacles_dodged: result.obstacles_dodged,
+            gems_collected: result.gems_collected,
+            speed_reached: result.speed_reached,
+            collision_occurred: result.collision_occurred,
+        }
+    }
+}
+
+impl Trace {
+    /// Decodes the action codes, naming the first tick that is not an action.
+    pub fn actions(&self) -> Result<Vec<Action>, String> {
+        self.actions
+            .chars()
+            .enumerate()
+            .map(|(tick, c)| {
+                c.to_digit(10)
+                    .and_then(|code| Action::try_from(code).ok())
+                    .ok_or_else(|| format!("{}: tick {tick} has action code {c:?}", self.name))
+            })
+            .collect()
+    }
+
+    /// The guest input that replays this trace.
+    pub fn input(&self) -> Result<GameInput, String> {
+        Ok(GameInput {
+            version: INPUT_VERSION,
+            seed: self.seed,
+            actions: PackedActions::pack(&self.actions()?),
+            player_address: PLAYER.to_string(),
+            game_id: 1,
+            session_id: 1,
+            contract_id: [0; 32],
+            config: self.config,
+            difficulty: self.difficulty,
+        })
+    }
+
+    /// Compares a replay's results with the recorded ones, listing every field that differs.
+    pub fn check(&self, implementation: &str, actual: Expected) -> Result<(), String> {
+        let expected = self.expected;
+        let fields = [
+            ("score", expected.score, actual.score),
+            ("obstacles_dodged", expected.obstacles_dodged, actual.obstacles_dodged),
+            ("gems_collected", expected.gems_collected, actual.gems_collected),
+            ("speed_reached", expected.speed_reached, actual.speed_reached),
+            ("collision_occurred", expected.collision_occurred as u32, actual.collision_occurred as u32),
+        ];
+        let diverged: Vec<String> = fields
+            .iter()
+            .filter(|(_, expected, actual)| expected != actual)
+            .map(|(field, expected, actual)| format!("{field}: recorded {expected}, {implementation} {actual}"))
+            .collect();
+        if diverged.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{} diverged in {implementation}: {}", self.name, diverged.join("; ")))
+        }
+    }
+}
+
+/// Loads every `.json` trace in `dir`, sorted by file name.
+pub fn load_corpus(dir: impl AsRef<Path>) -> Result<Vec<Trace>, String> {
+    let dir = dir.as_ref();
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("reading {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let text = fs::read_to_string(path).map_err(|e| format!("reading {}: {e}", path.display()))?;
+            serde_json::from_str(&text).map_err(|e| format!("parsing {}: {e}", path.display()))
+        })
+        .collect()
+}
@@ -0,0 +1,24 @@
+use lane_racer_parity::{load_corpus, Expected, CORPUS_DIR};
+use lane_racer_sim::simulate_game;
+
+#[test]
+fn corpus_is_not_empty() {
+    let corpus = load_corpus(CORPUS_DIR).unwrap();
+    assert!(!corpus.is_empty(), "no traces in {CORPUS_DIR}");
+    for trace in &corpus {
+        assert!(trace.config.is_valid(), "{}: invalid config", trace.name);
+    }
+}
+
+#[test]
+fn native_sim_matches_the_recorded_scores() {
+    let mut failures = Vec::new();
+    for trace in load_corpus(CORPUS_DIR).unwrap() {
+        let outcome = trace.input().and_then(|input| {
+            let result = simulate_game(&input);
+            trace.check("native sim", Expected::from_result(&result))
+        });
+        failures.extend(outcome.err());
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,61 @@
+use lane_racer_parity::{load_corpus, Expected, CORPUS_DIR};
+use lane_racer_sim::{simulate_game, GameInput};
+use methods::LANE_RACER_PROVER_ELF;
+use risc0_zkvm::{default_executor, ExecutorEnv, ExitCode};
+use shared::GameJournal;
+
+/// Executes the guest (without proving) and decodes its journal.
+fn run_guest(input: &GameInput) -> Result<GameJournal, String> {
+    let env = ExecutorEnv::builder().write(input).unwrap().build().unwrap();
+    let session = default_executor().execute(env, LANE_RACER_PROVER_ELF).map_err(|e| e.to_string())?;
+    if session.exit_code != ExitCode::Halted(0) {
+        return Err(format!("guest exited with {:?}", session.exit_code));
+    }
+    GameJournal::decode(&session.journal.bytes).ok_or_else(|| "journal does not have the fixed layout".to_string())
+}
+
+#[test]
+fn zkvm_executor_matches_the_recorded_scores() {
+    let mut failures = Vec::new();
+    for trace in load_corpus(CORPUS_DIR).unwrap() {
+        let outcome = trace.input().and_then(|input| {
+            let journal = run_guest(&input).map_err(|e| format!("{}: {e}", trace.name))?;
+            let recorded = Expected {
+                score: journal.score,
+                obstacles_dodged: journal.obstacles_dodged,
+                gems_collected: journal.gems_collected,
+                speed_reached: journal.speed_reached,
+                collision_occurred: journal.collision_occurred,
+            };
+            trace.check("zkVM executor", recorded)?;
+            // Counters the frontend does not record must still agree with the native sim.
+            let native = simulate_game(&input);
+            let guest = [
+                journal.shields_collected,
+                journal.shields_used,
+                journal.magnets_collected,
+                journal.near_misses,
+                journal.best_combo,
+                journal.obstacles_spawned,
+                journal.gems_spawned,
+                journal.ticks,
+            ];
+            let native = [
+                native.shields_collected,
+                native.shields_used,
+                native.magnets_collected,
+                native.near_misses,
+                native.best_combo,
+                native.obstacles_spawned,
+                native.gems_spawned,
+                native.ticks,
+            ];
+            if guest != native {
+                return Err(format!("{}: zkVM counters {guest:?} differ from native {native:?}", trace.name));
+            }
+            Ok(())
+        });
+        failures.extend(outcome.err());
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,75 @@
+//! Primitives of the fixed binary layouts shared by the guest, the host and on-chain
+//! decoders: integers are little-endian at their full width, byte arrays are copied as
+//! is, and nothing is length-prefixed or padded. Unlike serde's formats, the bytes of a
+//! value depend only on this module, so a decoder in another crate or language can be
+//! written from the layout table alone.
+
+/// Appends fields to a fixed-size buffer.
+pub struct Writer<'a> {
+    out: &'a mut [u8],
+    at: usize,
+}
+
+impl<'a> Writer<'a> {
+    pub fn new(out: &'a mut [u8]) -> Self {
+        Self { out, at: 0 }
+    }
+
+    /// Panics if `bytes` runs past the end of the buffer.
+    pub fn put(&mut self, bytes: &[u8]) {
+        self.out[self.at..self.at + bytes.len()].copy_from_slice(bytes);
+        self.at += bytes.len();
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.put(&[value]);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.put(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.put(&value.to_le_bytes());
+    }
+
+    /// Bytes written so far.
+    pub fn position(&self) -> usize {
+        self.at
+    }
+}
+
+/// Reads fields in layout order. Every read returns `None` once the input runs out.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, at: 0 }
+    }
+
+    pub fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let field = self.bytes.get(self.at..self.at + N)?.try_into().ok()?;
+        self.at += N;
+        Some(field)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[value]| value)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    /// Whether every byte was read, so trailing data is not silently ignored.
+    pub fn is_done(&self) -> bool {
+        self.at == self.bytes.len()
+    }
+}
@@ -0,0 +1,35 @@
+//! User exit codes the guest halts with when it rejects its input. The exit code is part
+//! of the receipt claim, so a proof of a rejected run never verifies as a normal
+//! `Halted(0)` run, yet the prover can still tell the caller what was wrong.
+
+/// A packed action trace whose bytes do not match its length, or with padding bits set.
+pub const INVALID_ACTION: u8 = 1;
+
+/// An action trace longer than `MAX_TICKS`.
+pub const TOO_MANY_TICKS: u8 = 2;
+
+/// A player address that is not a valid `G...` account strkey.
+pub const INVALID_PLAYER: u8 = 3;
+
+/// A `GameConfig` the simulation cannot run (see `GameConfig::is_valid`).
+pub const INVALID_CONFIG: u8 = 4;
+
+/// A pause longer than `MAX_PAUSE_TICKS`.
+pub const PAUSE_TOO_LONG: u8 = 5;
+
+/// A `GameInput` whose `version` this guest does not know, e.g. from a newer host.
+pub const UNSUPPORTED_VERSION: u8 = 6;
+
+/// Human-readable reason for a user exit code.
+pub fn describe(code: u32) -> &'static str {
+    match code {
+        0 => "ok",
+        c if c == INVALID_ACTION as u32 => "action trace is malformed",
+        c if c == TOO_MANY_TICKS as u32 => "action trace is longer than the maximum tick count",
+        c if c == INVALID_PLAYER as u32 => "player is not a valid Stellar account address",
+        c if c == INVALID_CONFIG as u32 => "game config is invalid",
+        c if c == PAUSE_TOO_LONG as u32 => "action trace pauses for longer than the maximum pause",
+        c if c == UNSUPPORTED_VERSION as u32 => "game input version is not supported by this guest",
+        _ => "unknown exit code",
+    }
+}
@@ -0,0 +1,186 @@
+//! Fixed binary layout of the guest's journal.
+//!
+//! Every field sits at a fixed offset so the Soroban contract can read it with slicing
+//! alone, without a serde decoder or allocation. Fields use the encoding of
+//! [`crate::codec`]:
+//!
+//! | offset | size | field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 4    | sim_version, u32 LE                     |
+//! | 4      | 32   | player ed25519 public key               |
+//! | 36     | 8    | game_id, u64 LE                         |
+//! | 44     | 4    | session_id, u32 LE                      |
+//! | 48     | 32   | contract_id of the target contract      |
+//! | 80     | 8    | seed, u64 LE                            |
+//! | 88     | 32   | actions_hash, SHA-256 of the actions    |
+//! | 120    | 32   | config_hash, SHA-256 of the GameConfig  |
+//! | 152    | 4    | score, u32 LE                           |
+//! | 156    | 4    | obstacles_dodged, u32 LE                |
+//! | 160    | 4    | gems_collected, u32 LE                  |
+//! | 164    | 4    | speed_reached, u32 LE                   |
+//! | 168    | 4    | shields_collected, u32 LE               |
+//! | 172    | 4    | shields_used, u32 LE                    |
+//! | 176    | 4    | magnets_collected, u32 LE               |
+//! | 180    | 4    | near_misses, u32 LE                     |
+//! | 184    | 4    | best_combo, u32 LE                      |
+//! | 188    | 1    | lanes, 3 to 5                           |
+//! | 189    | 1    | difficulty: 0 easy, 1 normal, 2 hard    |
+//! | 190    | 1    | flags: bit 0 = collision_occurred,      |
+//! |        |      | bit 1 = empty_trace                     |
+//! | 191    | 4    | obstacles_spawned, u32 LE               |
+//! | 195    | 4    | gems_spawned, u32 LE                    |
+//! | 199    | 4    | ticks, u32 LE                           |
+//!
+//! The session and contract IDs let the contract refuse proofs made for another session
+//! or another deployment. The seed lets the contract check a ranked run used the seed it
+//! drew at `start_game`, or the day's published seed for a daily challenge; the actions
+//! hash identifies the trace so the same run cannot be proven twice. The
+//! config hash and difficulty let it hold ranked runs to the season's rules: the official
+//! config or an event's variant. The spawn counts and tick count let auditors check the
+//! score's composition without the trace: nothing can be dodged or collected that never
+//! spawned.
+//! `sim_version` comes first so a reader can reject journals of other versions before
+//! interpreting the rest.
+
+use crate::codec::{Reader, Writer};
+use lane_racer_sim::{Difficulty, GameConfig, PackedActions, MAX_LANES, MIN_LANES};
+use sha2::{Digest, Sha256};
+
+/// Length of an encoded journal in bytes.
+pub const JOURNAL_LEN: usize = 203;
+
+/// SHA-256 of the tick count (u32 LE) followed by the packed action bytes, as committed
+/// in `actions_hash`. Hashing the packed form spares the guest unpacking the trace twice.
+pub fn actions_hash(actions: &PackedActions) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update((actions.len() as u32).to_le_bytes());
+    hasher.update(actions.as_bytes());
+    hasher.finalize().into()
+}
+
+/// SHA-256 of the config's canonical encoding, as committed in `config_hash`.
+pub fn config_hash(config: &GameConfig) -> [u8; 32] {
+    Sha256::digest(config.to_bytes()).into()
+}
+
+const FLAG_COLLISION: u8 = 1;
+const FLAG_EMPTY_TRACE: u8 = 2;
+
+/// Public outputs of one game, as committed by the guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameJournal {
+    /// `SIM_VERSION` of the guest that produced the journal.
+    pub sim_version: u32,
+    pub player: [u8; 32],
+    pub game_id: u64,
+    pub session_id: u32,
+    pub contract_id: [u8; 32],
+    pub seed: u64,
+    pub actions_hash: [u8; 32],
+    pub config_hash: [u8; 32],
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub speed_reached: u32,
+    pub shields_collected: u32,
+    /// Collisions a shield absorbed.
+    pub shields_used: u32,
+    pub magnets_collected: u32,
+    pub near_misses: u32,
+    pub best_combo: u32,
+    /// Lane count of the game mode played, also covered by `config_hash`.
+    pub lanes: u8,
+    pub difficulty: Difficulty,
+    pub collision_occurred: bool,
+    /// The action trace had no ticks, so the run never started and every counter is zero.
+    pub empty_trace: bool,
+    pub obstacles_spawned: u32,
+    pub gems_spawned: u32,
+    /// Ticks played, pauses excluded.
+    pub ticks: u32,
+}
+
+impl GameJournal {
+    pub fn encode(&self) -> [u8; JOURNAL_LEN] {
+        let mut out = [0u8; JOURNAL_LEN];
+        let mut w = Writer::new(&mut out);
+        w.u32(self.sim_version);
+        w.put(&self.player);
+        w.u64(self.game_id);
+        w.u32(self.session_id);
+        w.put(&self.contract_id);
+        w.u64(self.seed);
+        w.put(&self.actions_hash);
+        w.put(&self.config_hash);
+        w.u32(self.score);
+        w.u32(self.obstacles_dodged);
+        w.u32(self.gems_collected);
+        w.u32(self.speed_reached);
+        w.u32(self.shields_collected);
+        w.u32(self.shields_used);
+        w.u32(self.magnets_collected);
+        w.u32(self.near_misses);
+        w.u32(self.best_combo);
+        w.u8(self.lanes);
+        w.u8(self.difficulty.code());
+        let mut flags = 0;
+        if self.collision_occurred {
+            flags |= FLAG_COLLISION;
+        }
+        if self.empty_trace {
+            flags |= FLAG_EMPTY_TRACE;
+        }
+        w.u8(flags);
+        w.u32(self.obstacles_spawned);
+        w.u32(self.gems_spawned);
+        w.u32(self.ticks);
+        debug_assert_eq!(w.position(), JOURNAL_LEN);
+        out
+    }
+
+    /// Decodes a journal, rejecting wrong lengths, lane counts outside the supported range,
+    /// unknown difficulty codes and unknown flag bits. The version is not checked here;
+    /// callers compare `sim_version` against what they accept.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut r = Reader::new(bytes);
+        let journal = Self {
+            sim_version: r.u32()?,
+            player: r.take()?,
+            game_id: r.u64()?,
+            session_id: r.u32()?,
+            contract_id: r.take()?,
+            seed: r.u64()?,
+            actions_hash: r.take()?,
+            config_hash: r.take()?,
+            score: r.u32()?,
+            obstacles_dodged: r.u32()?,
+            gems_collected: r.u32()?,
+            speed_reached: r.u32()?,
+            shields_collected: r.u32()?,
+            shields_used: r.u32()?,
+            magnets_collected: r.u32()?,
+            near_misses: r.u32()?,
+            best_combo: r.u32()?,
+            lanes: r.u8()?,
+            difficulty: Difficulty::from_code(r.u8()?)?,
+            collision_occurred: false,
+            empty_trace: false,
+            obstacles_spawned: 0,
+            gems_spawned: 0,
+            ticks: 0,
+        };
+        let flags = r.u8()?;
+        let (obstacles_spawned, gems_spawned, ticks) = (r.u32()?, r.u32()?, r.u32()?);
+        if !r.is_done() || flags & !(FLAG_COLLISION | FLAG_EMPTY_TRACE) != 0 || !(MIN_LANES..=MAX_LANES).contains(&journal.lanes) {
+            return None;
+        }
+        Some(Self {
+            collision_occurred: flags & FLAG_COLLISION != 0,
+            empty_trace: flags & FLAG_EMPTY_TRACE != 0,
+            obstacles_spawned,
+            gems_spawned,
+            ticks,
+            ..journal
+        })
+    }
+}
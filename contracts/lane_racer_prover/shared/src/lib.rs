@@ -1,20 +1,9 @@
-use serde::{Deserialize, Serialize};
+#![no_std]
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameInput {
-    pub seed: u64,
-    pub actions: Vec<u8>,
-    pub player_address: String,
-    pub game_id: u64,
-}
+pub mod codec;
+pub mod exit;
+pub mod journal;
+pub mod strkey;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameResult {
-    pub player_address: String,
-    pub game_id: u64,
-    pub score: u32,
-    pub obstacles_dodged: u32,
-    pub gems_collected: u32,
-    pub speed_reached: u32,
-    pub collision_occurred: bool,
-}
\ No newline at end of file
+pub use journal::{actions_hash, config_hash, GameJournal, JOURNAL_LEN};
+pub use lane_racer_sim::{Action, GameConfig, GameInput, GameResult, PackedActions};
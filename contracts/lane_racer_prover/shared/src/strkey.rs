@@ -0,0 +1,78 @@
+//! Decoding of Stellar account (`G...`) and contract (`C...`) strkeys, without allocation.
+
+/// Version byte of an ed25519 account strkey (`G...`): 6 << 3.
+const ACCOUNT_VERSION_BYTE: u8 = 6 << 3;
+
+/// Version byte of a contract strkey (`C...`): 2 << 3.
+const CONTRACT_VERSION_BYTE: u8 = 2 << 3;
+
+/// Length of an account or contract strkey: base32 of version (1) + key (32) + checksum (2).
+pub const ACCOUNT_STRKEY_LEN: usize = 56;
+
+/// Decoded length: version (1) + key (32) + checksum (2).
+const PAYLOAD_LEN: usize = 35;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrkeyError {
+    /// Not 56 characters or not starting with the expected letter.
+    Length,
+    /// A character outside the RFC 4648 base32 alphabet.
+    Base32,
+    /// Decodes, but is not the expected kind of key.
+    Version,
+    /// The CRC16 checksum does not match.
+    Checksum,
+}
+
+/// CRC16-XModem, the checksum used by Stellar strkeys.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Decodes a `G...` account strkey into its raw ed25519 public key, checking the version
+/// byte and checksum.
+pub fn decode_account(address: &str) -> Result<[u8; 32], StrkeyError> {
+    decode(address, 'G', ACCOUNT_VERSION_BYTE)
+}
+
+/// Decodes a `C...` contract strkey into the contract ID, the 32-byte hash a Soroban
+/// contract address wraps.
+pub fn decode_contract(address: &str) -> Result<[u8; 32], StrkeyError> {
+    decode(address, 'C', CONTRACT_VERSION_BYTE)
+}
+
+fn decode(address: &str, prefix: char, version: u8) -> Result<[u8; 32], StrkeyError> {
+    if address.len() != ACCOUNT_STRKEY_LEN || !address.starts_with(prefix) {
+        return Err(StrkeyError::Length);
+    }
+    let mut raw = [0u8; PAYLOAD_LEN];
+    let (mut buffer, mut bits, mut len) = (0u32, 0u32, 0usize);
+    for c in address.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|a| *a == c).ok_or(StrkeyError::Base32)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            raw[len] = (buffer >> bits) as u8;
+            len += 1;
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    let (payload, checksum) = raw.split_at(PAYLOAD_LEN - 2);
+    if payload[0] != version {
+        return Err(StrkeyError::Version);
+    }
+    if crc16_xmodem(payload).to_le_bytes() != checksum {
+        return Err(StrkeyError::Checksum);
+    }
+    Ok(payload[1..].try_into().expect("payload holds a 32-byte key"))
+}
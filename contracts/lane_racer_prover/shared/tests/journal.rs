@@ -0,0 +1,85 @@
+use lane_racer_sim::{Difficulty, GameConfig};
+use shared::{config_hash, GameJournal, JOURNAL_LEN};
+
+fn journal() -> GameJournal {
+    GameJournal {
+        sim_version: 9,
+        player: [1; 32],
+        game_id: 0x0102_0304_0506_0708,
+        session_id: 42,
+        contract_id: [2; 32],
+        seed: 99,
+        actions_hash: [3; 32],
+        config_hash: [4; 32],
+        score: 1234,
+        obstacles_dodged: 50,
+        gems_collected: 6,
+        speed_reached: 175,
+        shields_collected: 2,
+        shields_used: 1,
+        magnets_collected: 1,
+        near_misses: 9,
+        best_combo: 4,
+        lanes: 4,
+        difficulty: Difficulty::Hard,
+        collision_occurred: true,
+        empty_trace: false,
+        obstacles_spawned: 61,
+        gems_spawned: 9,
+        ticks: 5000,
+    }
+}
+
+#[test]
+fn round_trips() {
+    let journal = journal();
+    assert_eq!(GameJournal::decode(&journal.encode()), Some(journal));
+}
+
+#[test]
+fn fields_sit_at_their_documented_offsets() {
+    let bytes = journal().encode();
+    assert_eq!(bytes[0..4], 9u32.to_le_bytes());
+    assert_eq!(bytes[36..44], 0x0102_0304_0506_0708u64.to_le_bytes());
+    assert_eq!(bytes[44..48], 42u32.to_le_bytes());
+    assert_eq!(bytes[48..80], [2; 32]);
+    assert_eq!(bytes[152..156], 1234u32.to_le_bytes());
+    assert_eq!(bytes[184..188], 4u32.to_le_bytes());
+    assert_eq!(bytes[188..191], [4, 2, 1]);
+    assert_eq!(bytes[191..195], 61u32.to_le_bytes());
+    assert_eq!(bytes[195..199], 9u32.to_le_bytes());
+    assert_eq!(bytes[199..JOURNAL_LEN], 5000u32.to_le_bytes());
+}
+
+#[test]
+fn empty_traces_set_their_own_flag() {
+    let empty = GameJournal { empty_trace: true, collision_occurred: false, ..journal() };
+    let bytes = empty.encode();
+    assert_eq!(bytes[190], 2);
+    assert_eq!(GameJournal::decode(&bytes), Some(empty));
+}
+
+#[test]
+fn rejects_malformed_journals() {
+    let bytes = journal().encode();
+    assert_eq!(GameJournal::decode(&bytes[..JOURNAL_LEN - 1]), None);
+    assert_eq!(GameJournal::decode(&[&bytes[..], &[0]].concat()), None);
+    for (offset, value) in [(188, 2), (188, 6), (189, 3), (190, 4)] {
+        let mut bad = bytes;
+        bad[offset] = value;
+        assert_eq!(GameJournal::decode(&bad), None, "byte {} = {}", offset, value);
+    }
+}
+
+/// `OFFICIAL_CONFIG_HASH` in `lane-racer-journal`, which the contract requires of ranked runs
+/// unless the admin sets other rules for a season.
+#[test]
+fn the_default_config_has_the_official_hash() {
+    let expected = [
+        0x4b, 0x29, 0x51, 0x84, 0xed, 0x31, 0xdb, 0x86,
+        0x5f, 0xb0, 0x25, 0x6a, 0xa6, 0x8c, 0xb7, 0xe1,
+        0xad, 0x75, 0x42, 0x9c, 0x05, 0x8b, 0x5f, 0x78,
+        0xac, 0x07, 0xc5, 0x0e, 0x36, 0xf1, 0x03, 0x37,
+    ];
+    assert_eq!(config_hash(&GameConfig::default()), expected);
+}
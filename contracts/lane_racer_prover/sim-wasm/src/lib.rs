@@ -0,0 +1,87 @@
+//! WebAssembly bindings of the simulation, so the browser runs the same code the guest
+//! proves instead of a JavaScript port of it.
+//!
+//! Values cross the boundary in the forms the frontend already uses: action codes as
+//! numbers (0 stay, 1 left, 2 right, 3 pause), difficulty by its journal code, and the
+//! config and results as JSON with the field names of the Rust types. Errors are thrown
+//! as strings.
+
+use lane_racer_sim::{
+    simulate_game, Action, Difficulty, GameConfig, GameInput, GameResult, PackedActions, INPUT_VERSION,
+};
+use wasm_bindgen::prelude::*;
+
+/// Parses the difficulty code and config JSON. An empty config means the default one.
+fn rules(difficulty: u8, config_json: &str) -> Result<(Difficulty, GameConfig), String> {
+    let difficulty = Difficulty::from_code(difficulty).ok_or_else(|| format!("unknown difficulty {difficulty}"))?;
+    let config = if config_json.trim().is_empty() {
+        GameConfig::default()
+    } else {
+        serde_json::from_str(config_json).map_err(|e| format!("invalid config: {e}"))?
+    };
+    if !config.is_valid() {
+        return Err("config is outside the supported ranges".to_string());
+    }
+    Ok((difficulty, config))
+}
+
+fn action(code: u8) -> Result<Action, String> {
+    Action::try_from(code as u32).map_err(|code| format!("unknown action code {code}"))
+}
+
+/// A run driven tick by tick, as the game loop plays it.
+#[wasm_bindgen]
+pub struct Game {
+    game: lane_racer_sim::Game,
+}
+
+#[wasm_bindgen]
+impl Game {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u64, difficulty: u8, config_json: &str) -> Result<Game, String> {
+        let (difficulty, config) = rules(difficulty, config_json)?;
+        Ok(Game { game: lane_racer_sim::Game::new(seed, config, difficulty) })
+    }
+
+    /// Applies one tick's action and returns the `TickState` after it as JSON.
+    pub fn step(&mut self, action_code: u8) -> Result<String, String> {
+        let state = self.game.step(action(action_code)?);
+        serde_json::to_string(&state).map_err(|e| e.to_string())
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+}
+
+/// Simulates a whole trace. The player, game and session fields of the result are left
+/// empty; only the proof request carries them.
+fn run(seed: u64, difficulty: u8, config_json: &str, actions: &[u8]) -> Result<GameResult, String> {
+    let (difficulty, config) = rules(difficulty, config_json)?;
+    let actions = actions.iter().map(|&code| action(code)).collect::<Result<Vec<_>, _>>()?;
+    let input = GameInput {
+        version: INPUT_VERSION,
+        seed,
+        actions: PackedActions::pack(&actions),
+        player_address: String::new(),
+        game_id: 0,
+        session_id: 0,
+        contract_id: [0; 32],
+        config,
+        difficulty,
+    };
+    Ok(simulate_game(&input))
+}
+
+/// Simulates a whole trace of action codes and returns the `GameResult` as JSON.
+#[wasm_bindgen]
+pub fn simulate(seed: u64, difficulty: u8, config_json: &str, actions: &[u8]) -> Result<String, String> {
+    serde_json::to_string(&run(seed, difficulty, config_json, actions)?).map_err(|e| e.to_string())
+}
+
+/// The score a trace of action codes ends with.
+#[wasm_bindgen]
+pub fn score(seed: u64, difficulty: u8, config_json: &str, actions: &[u8]) -> Result<u32, String> {
+    Ok(run(seed, difficulty, config_json, actions)?.score)
+}
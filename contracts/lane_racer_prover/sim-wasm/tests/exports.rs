@@ -0,0 +1,49 @@
+use lane_racer_sim::{simulate_game, Action, Difficulty, GameConfig, GameInput, PackedActions, INPUT_VERSION};
+use lane_racer_sim_wasm::{score, simulate, Game};
+
+const CODES: &str = include_str!("../../../lane-racer-journal/fixtures/trace.txt");
+
+fn codes() -> Vec<u8> {
+    CODES.trim().bytes().map(|c| c - b'0').collect()
+}
+
+fn native() -> lane_racer_sim::GameResult {
+    let actions: Vec<Action> = codes().into_iter().map(|code| Action::try_from(code as u32).unwrap()).collect();
+    simulate_game(&GameInput {
+        version: INPUT_VERSION,
+        seed: 42,
+        actions: PackedActions::pack(&actions),
+        player_address: String::new(),
+        game_id: 0,
+        session_id: 0,
+        contract_id: [0; 32],
+        config: GameConfig::default(),
+        difficulty: Difficulty::Hard,
+    })
+}
+
+#[test]
+fn exports_match_the_native_sim() {
+    let expected = native();
+    assert_eq!(score(42, Difficulty::Hard.code(), "", &codes()).unwrap(), expected.score);
+    let result: lane_racer_sim::GameResult = serde_json::from_str(&simulate(42, 2, "{}", &codes()).unwrap()).unwrap();
+    assert_eq!(result, expected);
+
+    let mut game = Game::new(42, 2, r#"{"lanes": 3}"#).unwrap();
+    let mut last = String::new();
+    for code in codes() {
+        last = game.step(code).unwrap();
+    }
+    let state: serde_json::Value = serde_json::from_str(&last).unwrap();
+    assert_eq!(state["score"], expected.score);
+    assert_eq!(game.is_over(), expected.collision_occurred);
+}
+
+#[test]
+fn bad_arguments_are_errors() {
+    assert!(Game::new(1, 3, "").is_err());
+    assert!(Game::new(1, 1, r#"{"lanes": 9}"#).is_err());
+    assert!(Game::new(1, 1, "not json").is_err());
+    assert!(Game::new(1, 1, "").unwrap().step(4).is_err());
+    assert!(score(1, 1, "", &[0, 1, 7]).is_err());
+}
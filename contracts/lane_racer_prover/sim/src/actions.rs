@@ -0,0 +1,95 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// What the player does in one tick. This is the one definition of the action codes: the
+/// host converts request codes with `TryFrom<u32>` and traces carry them as `code()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Stay,
+    Left,
+    Right,
+    /// Nothing moves, spawns or scores, and the magnet does not run down, so a paused run
+    /// re-simulates exactly like an unpaused one.
+    Pause,
+}
+
+impl Action {
+    /// Every action, in code order.
+    pub const ALL: [Action; 4] = [Action::Stay, Action::Left, Action::Right, Action::Pause];
+
+    /// Two-bit code, as packed into traces.
+    pub fn code(self) -> u8 {
+        match self {
+            Action::Stay => 0,
+            Action::Left => 1,
+            Action::Right => 2,
+            Action::Pause => 3,
+        }
+    }
+
+    /// Decodes the low two bits of `code`, so every packed code is an action.
+    fn from_bits(code: u8) -> Self {
+        Self::ALL[(code & 0b11) as usize]
+    }
+}
+
+impl TryFrom<u32> for Action {
+    /// The code that is not an action.
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        Self::ALL.into_iter().find(|action| action.code() as u32 == code).ok_or(code)
+    }
+}
+
+/// An action trace packed two bits per tick, four ticks per byte, least significant bits
+/// first. The zkVM reads every serialized byte as a whole word, so packing cuts the input
+/// the guest reads, and the cycles spent reading it, by four.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedActions {
+    len: u32,
+    bytes: Vec<u8>,
+}
+
+impl PackedActions {
+    /// Packs one action per tick.
+    pub fn pack(actions: &[Action]) -> Self {
+        let mut bytes = alloc::vec![0u8; actions.len().div_ceil(4)];
+        for (tick, action) in actions.iter().enumerate() {
+            bytes[tick / 4] |= action.code() << (2 * (tick % 4));
+        }
+        Self { len: actions.len() as u32, bytes }
+    }
+
+    /// Number of ticks in the trace.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bytes, as hashed into the journal.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Whether the bytes hold exactly `len` ticks with zeroed padding, so each trace has
+    /// a single encoding. Deserialized traces must be checked before they are iterated.
+    pub fn is_well_formed(&self) -> bool {
+        if self.bytes.len() != self.len().div_ceil(4) {
+            return false;
+        }
+        let used_bits = 2 * (self.len() % 4);
+        match self.bytes.last() {
+            Some(&last) if used_bits > 0 => last >> used_bits == 0,
+            _ => true,
+        }
+    }
+
+    /// The action of every tick, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Action> + '_ {
+        (0..self.len()).map(|tick| Action::from_bits(self.bytes[tick / 4] >> (2 * (tick % 4))))
+    }
+}
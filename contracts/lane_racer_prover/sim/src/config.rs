@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+/// Lane counts supported by the game modes: the classic 3, plus 4 and 5.
+pub const MIN_LANES: u8 = 3;
+pub const MAX_LANES: u8 = 5;
+
+/// Highest `base_speed_px`, which keeps per-tick movement well within `i32`.
+pub const MAX_BASE_SPEED_PX: u32 = 100;
+
+/// Highest `points_per_dodge`, `points_per_gem` and `near_miss_points`, so a config cannot
+/// reach `MAX_SCORE` within a few ticks.
+pub const MAX_POINTS: u32 = 1_000;
+
+/// Length of `GameConfig::to_bytes`.
+pub const CONFIG_BYTES_LEN: usize = 53;
+
+/// Tunable rules of a run. `GameConfig::default()` is the official configuration; special
+/// events may use variants, which the contract tells apart by the hash committed in the
+/// journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct GameConfig {
+    pub lanes: u8,
+    /// Distance objects fall per tick at 1.00x speed, in game units.
+    pub base_speed_px: u32,
+    /// Speed added per speed-up, in hundredths of the base speed.
+    pub speed_increment: u32,
+    pub obstacles_per_speed_up: u32,
+    /// Obstacle spawn chance per tick at 1.00x speed, in per mille; scales with speed.
+    pub obstacle_spawn_per_mille: u32,
+    /// Chance that a spawn places two obstacles instead of one, in percent.
+    pub double_spawn_percent: u32,
+    /// Gem spawn chance per tick, in per mille.
+    pub gem_spawn_per_mille: u32,
+    pub points_per_dodge: u32,
+    pub points_per_gem: u32,
+    /// Shield or magnet spawn chance per tick, in per mille; each kind is equally likely.
+    pub power_up_spawn_per_mille: u32,
+    /// How long a magnet lasts, in ticks.
+    pub magnet_ticks: u32,
+    /// Bonus for dodging an obstacle from an adjacent lane.
+    pub near_miss_points: u32,
+    /// Consecutive gems needed per step of the gem multiplier.
+    pub combo_step: u32,
+    pub max_combo_multiplier: u32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            lanes: 3,
+            base_speed_px: 6,
+            speed_increment: crate::SPEED_INCREMENT,
+            obstacles_per_speed_up: crate::OBSTACLES_PER_SPEED_UP,
+            obstacle_spawn_per_mille: crate::OBSTACLE_SPAWN_PER_MILLE,
+            double_spawn_percent: crate::DOUBLE_SPAWN_PERCENT,
+            gem_spawn_per_mille: crate::GEM_SPAWN_PER_MILLE,
+            points_per_dodge: 2,
+            points_per_gem: 10,
+            power_up_spawn_per_mille: 3,
+            magnet_ticks: 300,
+            near_miss_points: 1,
+            combo_step: 5,
+            max_combo_multiplier: 4,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Whether the simulation can run with this configuration: a supported lane count,
+    /// a bounded base speed, speed-up and point values, and chances that are within
+    /// their scale.
+    pub fn is_valid(&self) -> bool {
+        (MIN_LANES..=MAX_LANES).contains(&self.lanes)
+            && self.base_speed_px <= MAX_BASE_SPEED_PX
+            && self.speed_increment <= crate::MAX_SPEED
+            && self.obstacles_per_speed_up > 0
+            && self.obstacle_spawn_per_mille <= 1000
+            && self.double_spawn_percent <= 100
+            && self.gem_spawn_per_mille <= 1000
+            && self.points_per_dodge <= MAX_POINTS
+            && self.points_per_gem <= MAX_POINTS
+            && self.near_miss_points <= MAX_POINTS
+            && self.power_up_spawn_per_mille <= 1000
+            && self.combo_step > 0
+            && self.max_combo_multiplier > 0
+    }
+
+    /// Gem points multiplier for the `combo`-th consecutive gem: 1x for the first
+    /// `combo_step` gems, then one more per `combo_step`, up to `max_combo_multiplier`.
+    pub fn combo_multiplier(&self, combo: u32) -> u32 {
+        (1 + combo.saturating_sub(1) / self.combo_step).min(self.max_combo_multiplier)
+    }
+
+    /// Canonical encoding, hashed into the journal: `lanes` then every other field as
+    /// u32 LE, in declaration order.
+    pub fn to_bytes(&self) -> [u8; CONFIG_BYTES_LEN] {
+        let mut out = [0u8; CONFIG_BYTES_LEN];
+        out[0] = self.lanes;
+        let words = [
+            self.base_speed_px,
+            self.speed_increment,
+            self.obstacles_per_speed_up,
+            self.obstacle_spawn_per_mille,
+            self.double_spawn_percent,
+            self.gem_spawn_per_mille,
+            self.points_per_dodge,
+            self.points_per_gem,
+            self.power_up_spawn_per_mille,
+            self.magnet_ticks,
+            self.near_miss_points,
+            self.combo_step,
+            self.max_combo_multiplier,
+        ];
+        for (i, word) in words.iter().enumerate() {
+            out[1 + 4 * i..5 + 4 * i].copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+}
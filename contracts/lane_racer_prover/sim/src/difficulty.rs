@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Difficulty tier of a run, applied on top of the `GameConfig`: it scales obstacle
+/// density and how quickly the game speeds up. Committed to the journal so leaderboards
+/// can be kept per tier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Journal encoding.
+    pub fn code(self) -> u8 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Difficulty::Easy),
+            1 => Some(Difficulty::Normal),
+            2 => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// Obstacle spawn chance relative to the config, in percent.
+    pub fn density_percent(self) -> u32 {
+        match self {
+            Difficulty::Easy => 70,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 140,
+        }
+    }
+
+    /// Obstacles dodged per speed-up relative to the config, in percent. Lower speeds up
+    /// sooner.
+    pub fn speed_up_interval_percent(self) -> u32 {
+        match self {
+            Difficulty::Easy => 150,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 70,
+        }
+    }
+}
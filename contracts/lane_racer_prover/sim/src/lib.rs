@@ -0,0 +1,543 @@
+// ─────────────────────────────────────────────────────────────────────────────
+// Lane Racer – deterministic game simulation
+//
+// Used by the zkVM guest, which proves it, and the host, which runs it natively
+// to preview results. Both must produce bit-identical GameResults, so this crate
+// is no_std and free of floating point. The `schemars` feature, used only by the host's
+// API schema, links std because the JsonSchema derives need it.
+// ─────────────────────────────────────────────────────────────────────────────
+
+#![cfg_attr(not(feature = "schemars"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+mod actions;
+mod config;
+mod difficulty;
+mod pool;
+pub mod rng;
+
+pub use actions::{Action, PackedActions};
+pub use config::{GameConfig, CONFIG_BYTES_LEN, MAX_BASE_SPEED_PX, MAX_LANES, MAX_POINTS, MIN_LANES};
+pub use difficulty::Difficulty;
+use pool::Pool;
+use rng::Rng;
+
+/// Format version of `GameInput` and `GameResult`.
+///
+/// `version` is always the first field, and a new version may only append fields. The zkVM
+/// serialization has no field names and ignores trailing words, so a guest can always
+/// decode the prefix of a newer input and reject it by version instead of misparsing it.
+pub const INPUT_VERSION: u32 = 1;
+
+fn input_version() -> u32 {
+    INPUT_VERSION
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameInput {
+    /// `INPUT_VERSION` of the code that built the input. Defaults to 1 in JSON, for
+    /// inputs stored before the field existed.
+    #[serde(default = "input_version")]
+    pub version: u32,
+    pub seed: u64,
+    pub actions: PackedActions,
+    pub player_address: String,
+    pub game_id: u64,
+    /// Soroban session the run was played in. With `contract_id`, it binds a proof to one
+    /// session of one deployment, so it cannot be replayed against another.
+    #[serde(default)]
+    pub session_id: u32,
+    /// ID (hash) of the lane-racer contract the proof is meant for.
+    #[serde(default)]
+    pub contract_id: [u8; 32],
+    #[serde(default)]
+    pub config: GameConfig,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct GameResult {
+    /// Version of the input the result was simulated from.
+    pub version: u32,
+    pub player_address: String,
+    pub game_id: u64,
+    pub session_id: u32,
+    pub contract_id: [u8; 32],
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub speed_reached: u32,
+    pub collision_occurred: bool,
+    pub shields_collected: u32,
+    /// Collisions a shield absorbed.
+    pub shields_used: u32,
+    pub magnets_collected: u32,
+    /// Obstacles dodged from an adjacent lane.
+    pub near_misses: u32,
+    /// Longest run of gems collected without missing one.
+    pub best_combo: u32,
+    /// Obstacles and gems that entered the screen, bounding what could be dodged and
+    /// collected.
+    pub obstacles_spawned: u32,
+    pub gems_spawned: u32,
+    /// Ticks played, pauses excluded; short of the trace when a collision ended the run.
+    pub ticks: u32,
+}
+
+const BASE_SPEED_SCALE: u32 = 100; // 1.00x = 100
+
+// Defaults of the official GameConfig.
+const SPEED_INCREMENT: u32 = 25;   // 0.25x per 15 obstacles
+const OBSTACLES_PER_SPEED_UP: u32 = 15;
+
+// Spawn rates are integer per-mille / percent chances so the guest, the host and the
+// frontend compute identical outcomes without floating point. The frontend must use
+// these exact values and the same integer operations.
+
+/// Obstacle spawn chance per tick at 1.00x speed, in per mille; scales linearly with speed.
+pub const OBSTACLE_SPAWN_PER_MILLE: u32 = 15;
+/// Chance that a spawn places two obstacles instead of one, in percent.
+pub const DOUBLE_SPAWN_PERCENT: u32 = 40;
+/// Gem spawn chance per tick, in per mille.
+pub const GEM_SPAWN_PER_MILLE: u32 = 8;
+
+/// Version of the simulation rules, committed in every journal. Bump it whenever a change
+/// alters the result of any input, so proofs from outdated guests can be told apart
+/// during image ID migrations.
+pub const SIM_VERSION: u32 = 9;
+
+/// Longest action trace the guest simulates. At 60 ticks per second this is almost half
+/// an hour of play, far beyond any real run, while bounding the cycles (and so the
+/// proving cost) of a single proof.
+pub const MAX_TICKS: usize = 100_000;
+
+// Caps on the values the journal reports. Arithmetic saturates at these bounds instead
+// of wrapping, whatever the trace or configuration, so the contract can rely on them in
+// its plausibility checks. Counters are only saturated, as each is bounded by the tick
+// count well before `u32::MAX`.
+
+/// Highest speed a run reaches, in hundredths of the base speed (10.00x).
+pub const MAX_SPEED: u32 = 1_000;
+/// Highest score a run can report.
+pub const MAX_SCORE: u32 = 100_000_000;
+
+/// Adds `points` to `score`, saturating at `MAX_SCORE`.
+fn add_score(score: u32, points: u32) -> u32 {
+    score.saturating_add(points).min(MAX_SCORE)
+}
+
+// Entity capacities. A lane only spawns an obstacle once its previous one has left the
+// screen, so there is at most one obstacle per lane. Gems and power-ups that would
+// exceed their capacity are not spawned; with the default config that never happens.
+const MAX_OBSTACLES: usize = MAX_LANES as usize;
+const MAX_GEMS: usize = 32;
+const MAX_POWER_UPS: usize = 8;
+
+/// Longest single pause, in ticks: one minute at 60 ticks per second, matching the
+/// frontend, which ends the run when a pause lasts longer.
+pub const MAX_PAUSE_TICKS: usize = 3_600;
+
+/// First tick of a pause longer than `MAX_PAUSE_TICKS`, if the trace has one.
+pub fn find_overlong_pause(actions: impl IntoIterator<Item = Action>) -> Option<usize> {
+    let mut run_start = 0;
+    let mut run_len = 0;
+    for (tick, action) in actions.into_iter().enumerate() {
+        if action != Action::Pause {
+            run_len = 0;
+            continue;
+        }
+        if run_len == 0 {
+            run_start = tick;
+        }
+        run_len += 1;
+        if run_len > MAX_PAUSE_TICKS {
+            return Some(run_start);
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Obstacle {
+    lane: usize,
+    /// y position in game units (starts negative, moves down)
+    y: i32,
+    passed: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Gem {
+    lane: usize,
+    y: i32,
+    collected: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerUpKind {
+    /// Absorbs the next collision.
+    Shield,
+    /// Collects gems in adjacent lanes for `GameConfig::magnet_ticks` ticks.
+    Magnet,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PowerUp {
+    lane: usize,
+    y: i32,
+    kind: PowerUpKind,
+    collected: bool,
+}
+
+/// Observable state after one tick, used to compare the simulation against the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TickState {
+    /// Zero-based index into the action trace.
+    pub tick: u32,
+    pub lane: u8,
+    pub score: u32,
+    pub obstacles_dodged: u32,
+    pub gems_collected: u32,
+    pub speed: u32,
+    pub collision: bool,
+    pub shield: bool,
+    pub magnet_ticks_left: u32,
+    pub near_misses: u32,
+    pub combo: u32,
+    pub paused: bool,
+}
+
+/// Simulates a whole trace. An empty trace is a run that never started: score 0, zero
+/// ticks and every counter at its starting value.
+pub fn simulate_game(input: &GameInput) -> GameResult {
+    simulate_game_with(input, |_| {})
+}
+
+/// Runs the simulation, reporting the state after every tick to `on_tick`. The final
+/// tick of a run that ends in a collision is reported too. `input.config` must be valid
+/// (see `GameConfig::is_valid`).
+pub fn simulate_game_with(input: &GameInput, mut on_tick: impl FnMut(&TickState)) -> GameResult {
+    let mut game = Game::new(input.seed, input.config, input.difficulty);
+    for action in input.actions.iter() {
+        on_tick(&game.step(action));
+        if game.is_over() {
+            break;
+        }
+    }
+    game.result(input)
+}
+
+// Canvas constants (match frontend)
+const CANVAS_HEIGHT: i32 = 600;
+const PLAYER_Y: i32 = CANVAS_HEIGHT - 200;
+const PLAYER_HEIGHT: i32 = 100;
+
+/// A run in progress, advanced one action at a time. `simulate_game` is this loop over a
+/// whole trace; the frontend drives it tick by tick through the WASM build, so the game
+/// it shows is the one that gets proven.
+#[derive(Debug, Clone)]
+pub struct Game {
+    rng: Rng,
+    config: GameConfig,
+    lanes: usize,
+    obstacle_spawn_per_mille: u32,
+    obstacles_per_speed_up: u32,
+    /// Actions applied so far.
+    ticks: u32,
+
+    player_lane: usize,
+    score: u32,
+    obstacles_dodged: u32,
+    gems_collected: u32,
+    speed: u32,
+
+    obstacles: Pool<Obstacle, MAX_OBSTACLES>,
+    gems: Pool<Gem, MAX_GEMS>,
+    power_ups: Pool<PowerUp, MAX_POWER_UPS>,
+    collision: bool,
+    shield: bool,
+    magnet_ticks_left: u32,
+    shields_collected: u32,
+    shields_used: u32,
+    magnets_collected: u32,
+    near_misses: u32,
+    combo: u32,
+    best_combo: u32,
+    obstacles_spawned: u32,
+    gems_spawned: u32,
+    /// Actions applied so far, pauses excluded.
+    ticks_played: u32,
+}
+
+impl Game {
+    /// Starts a run. `config` must be valid (see `GameConfig::is_valid`).
+    pub fn new(seed: u64, config: GameConfig, difficulty: Difficulty) -> Self {
+        let lanes = config.lanes as usize;
+        Self {
+            rng: Rng::new(seed),
+            config,
+            lanes,
+            // Difficulty scales the configured density and speed-up interval
+            obstacle_spawn_per_mille: config.obstacle_spawn_per_mille * difficulty.density_percent() / 100,
+            obstacles_per_speed_up: (config.obstacles_per_speed_up.saturating_mul(difficulty.speed_up_interval_percent())
+                / 100)
+                .max(1),
+            ticks: 0,
+            player_lane: lanes / 2,
+            score: 0,
+            obstacles_dodged: 0,
+            gems_collected: 0,
+            speed: BASE_SPEED_SCALE, // 100 = 1.00x
+            obstacles: Pool::new(),
+            gems: Pool::new(),
+            power_ups: Pool::new(),
+            collision: false,
+            shield: false,
+            magnet_ticks_left: 0,
+            shields_collected: 0,
+            shields_used: 0,
+            magnets_collected: 0,
+            near_misses: 0,
+            combo: 0,
+            best_combo: 0,
+            obstacles_spawned: 0,
+            gems_spawned: 0,
+            ticks_played: 0,
+        }
+    }
+
+    /// Whether the run has ended in a collision.
+    pub fn is_over(&self) -> bool {
+        self.collision
+    }
+
+    /// Applies one tick's action and returns the state after it. Once the run is over,
+    /// further steps change nothing and report the final state again.
+    pub fn step(&mut self, action: Action) -> TickState {
+        if self.collision {
+            return self.state(self.ticks - 1, false);
+        }
+        let tick = self.ticks;
+        self.ticks = self.ticks.saturating_add(1);
+        let config = &self.config;
+        let lanes = self.lanes;
+
+        // ── Pause ───────────────────────────────────────────────────────────
+        if action == Action::Pause {
+            return self.state(tick, true);
+        }
+        self.ticks_played = self.ticks_played.saturating_add(1);
+
+        // ── Player movement ─────────────────────────────────────────────────
+        match action {
+            Action::Left if self.player_lane > 0 => self.player_lane -= 1,
+            Action::Right if self.player_lane < lanes - 1 => self.player_lane += 1,
+            _ => {}
+        }
+        let player_lane = self.player_lane;
+
+        let effective_speed = (config.base_speed_px as i32 * self.speed as i32) / BASE_SPEED_SCALE as i32;
+
+        // ── Move obstacles ──────────────────────────────────────────────────
+        for obs in self.obstacles.iter_mut() {
+            obs.y += effective_speed;
+
+            // Collision check; a shield absorbs the hit and removes the obstacle
+            // without crediting a dodge
+            if !obs.passed
+                && obs.y + 20 > PLAYER_Y
+                && obs.y - 20 < PLAYER_Y + PLAYER_HEIGHT
+                && obs.lane == player_lane
+            {
+                if self.shield {
+                    self.shield = false;
+                    self.shields_used = self.shields_used.saturating_add(1);
+                    obs.passed = true;
+                    continue;
+                }
+                self.collision = true;
+            }
+
+            // Passed check
+            if !obs.passed && obs.y > PLAYER_Y + PLAYER_HEIGHT {
+                obs.passed = true;
+                self.obstacles_dodged = self.obstacles_dodged.saturating_add(1);
+                self.score = add_score(self.score, config.points_per_dodge);
+
+                if self.obstacles_dodged % self.obstacles_per_speed_up == 0 {
+                    self.speed = self.speed.saturating_add(config.speed_increment).min(MAX_SPEED);
+                }
+
+                // Near miss: the obstacle went past in a lane next to the player
+                if obs.lane.abs_diff(player_lane) == 1 {
+                    self.near_misses = self.near_misses.saturating_add(1);
+                    self.score = add_score(self.score, config.near_miss_points);
+                }
+            }
+        }
+
+        if self.collision {
+            return self.state(tick, false);
+        }
+
+        // ── Move gems ───────────────────────────────────────────────────────
+        for gem in self.gems.iter_mut() {
+            gem.y += effective_speed;
+
+            // The magnet extends pickup to the neighbouring lanes
+            let in_reach =
+                gem.lane == player_lane || (self.magnet_ticks_left > 0 && gem.lane.abs_diff(player_lane) == 1);
+            if !gem.collected
+                && gem.y + 20 > PLAYER_Y
+                && gem.y - 20 < PLAYER_Y + PLAYER_HEIGHT
+                && in_reach
+            {
+                gem.collected = true;
+                self.gems_collected = self.gems_collected.saturating_add(1);
+                self.combo = self.combo.saturating_add(1);
+                self.best_combo = self.best_combo.max(self.combo);
+                self.score = add_score(
+                    self.score,
+                    config.points_per_gem.saturating_mul(config.combo_multiplier(self.combo)),
+                );
+            }
+        }
+
+        // A gem leaving the screen uncollected breaks the combo
+        if self.gems.iter().any(|g| !g.collected && g.y > CANVAS_HEIGHT + 50) {
+            self.combo = 0;
+        }
+
+        // ── Move power-ups ──────────────────────────────────────────────────
+        for power_up in self.power_ups.iter_mut() {
+            power_up.y += effective_speed;
+
+            if !power_up.collected
+                && power_up.y + 20 > PLAYER_Y
+                && power_up.y - 20 < PLAYER_Y + PLAYER_HEIGHT
+                && power_up.lane == player_lane
+            {
+                power_up.collected = true;
+                match power_up.kind {
+                    PowerUpKind::Shield => {
+                        self.shield = true;
+                        self.shields_collected = self.shields_collected.saturating_add(1);
+                    }
+                    PowerUpKind::Magnet => {
+                        self.magnet_ticks_left = config.magnet_ticks;
+                        self.magnets_collected = self.magnets_collected.saturating_add(1);
+                    }
+                }
+            }
+        }
+
+        // ── Remove off-screen objects ───────────────────────────────────────
+        self.obstacles.retain(|o| o.y <= CANVAS_HEIGHT + 50);
+        self.gems.retain(|g| !g.collected && g.y <= CANVAS_HEIGHT + 50);
+        self.power_ups.retain(|p| !p.collected && p.y <= CANVAS_HEIGHT + 50);
+
+        // ── Spawn obstacles (probabilistic, seeded) ─────────────────────────
+        // 1.5% chance per tick (by default) * speed multiplier, rounded down to whole per mille
+        let spawn_prob = (self.obstacle_spawn_per_mille * self.speed / BASE_SPEED_SCALE) as u64;
+        if self.rng.next_u64() % 1000 < spawn_prob {
+            // Ensure available lanes
+            let mut available = [0usize; MAX_LANES as usize];
+            let mut num_available = 0;
+            for l in 0..lanes {
+                if !self.obstacles.iter().any(|o| o.lane == l && o.y > -350) {
+                    available[num_available] = l;
+                    num_available += 1;
+                }
+            }
+            let available = &mut available[..num_available];
+
+            if available.len() >= 2 {
+                // Shuffle
+                for i in (1..available.len()).rev() {
+                    let j = self.rng.next_usize(i + 1);
+                    available.swap(i, j);
+                }
+
+                let num_spawn = if self.rng.next_u64() % 100 < config.double_spawn_percent as u64 { 2 } else { 1 };
+                let num_spawn = num_spawn.min(available.len() - 1); // always leave one lane clear
+
+                for &lane in available.iter().take(num_spawn) {
+                    if self.obstacles.push(Obstacle { lane, y: -50, passed: false }) {
+                        self.obstacles_spawned = self.obstacles_spawned.saturating_add(1);
+                    }
+                }
+            }
+        }
+
+        // ── Spawn gems (0.8% chance per tick by default) ────────────────────
+        if self.rng.next_u64() % 1000 < config.gem_spawn_per_mille as u64 {
+            let lane = self.rng.next_usize(lanes);
+            let has_nearby = self.obstacles.iter().any(|o| o.lane == lane && o.y > -200 && o.y < 100);
+            if !has_nearby && self.gems.push(Gem { lane, y: -50, collected: false }) {
+                self.gems_spawned = self.gems_spawned.saturating_add(1);
+            }
+        }
+
+        // ── Spawn power-ups (0.3% chance per tick by default) ───────────────
+        if self.rng.next_u64() % 1000 < config.power_up_spawn_per_mille as u64 {
+            let lane = self.rng.next_usize(lanes);
+            let kind = if self.rng.next_u64() % 2 == 0 { PowerUpKind::Shield } else { PowerUpKind::Magnet };
+            let has_nearby = self.obstacles.iter().any(|o| o.lane == lane && o.y > -200 && o.y < 100);
+            if !has_nearby {
+                self.power_ups.push(PowerUp { lane, y: -50, kind, collected: false });
+            }
+        }
+
+        self.magnet_ticks_left = self.magnet_ticks_left.saturating_sub(1);
+
+        self.state(tick, false)
+    }
+
+    /// Observable state, reported as the state after `tick`.
+    fn state(&self, tick: u32, paused: bool) -> TickState {
+        TickState {
+            tick,
+            lane: self.player_lane as u8,
+            score: self.score,
+            obstacles_dodged: self.obstacles_dodged,
+            gems_collected: self.gems_collected,
+            speed: self.speed,
+            collision: self.collision,
+            shield: self.shield,
+            magnet_ticks_left: self.magnet_ticks_left,
+            near_misses: self.near_misses,
+            combo: self.combo,
+            paused,
+        }
+    }
+
+    /// Final results, attributed to the player and session of `input`.
+    pub fn result(&self, input: &GameInput) -> GameResult {
+        GameResult {
+            version: input.version,
+            player_address: input.player_address.clone(),
+            game_id: input.game_id,
+            session_id: input.session_id,
+            contract_id: input.contract_id,
+            score: self.score,
+            obstacles_dodged: self.obstacles_dodged,
+            gems_collected: self.gems_collected,
+            speed_reached: self.speed,
+            collision_occurred: self.collision,
+            shields_collected: self.shields_collected,
+            shields_used: self.shields_used,
+            magnets_collected: self.magnets_collected,
+            near_misses: self.near_misses,
+            best_combo: self.best_combo,
+            obstacles_spawned: self.obstacles_spawned,
+            gems_spawned: self.gems_spawned,
+            ticks: self.ticks_played,
+        }
+    }
+}
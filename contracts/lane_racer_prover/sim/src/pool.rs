@@ -0,0 +1,41 @@
+/// Fixed-capacity entity storage: an array of slots, each active or free. Avoids heap
+/// allocation and `Vec::retain` shuffling, which cost cycles in the zkVM. The simulation
+/// only sums and flags over entities, so slot order does not affect results.
+#[derive(Debug, Clone)]
+pub(crate) struct Pool<T: Copy, const N: usize> {
+    slots: [Option<T>; N],
+}
+
+impl<T: Copy, const N: usize> Pool<T, N> {
+    pub(crate) fn new() -> Self {
+        Self { slots: [None; N] }
+    }
+
+    /// Places `item` in a free slot. Returns `false`, dropping it, when the pool is full.
+    pub(crate) fn push(&mut self, item: T) -> bool {
+        match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(item);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().flatten()
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().flatten()
+    }
+
+    /// Frees the slots whose items fail `keep`.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        for slot in self.slots.iter_mut() {
+            if slot.as_ref().is_some_and(|item| !keep(item)) {
+                *slot = None;
+            }
+        }
+    }
+}
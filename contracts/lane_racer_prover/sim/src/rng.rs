@@ -0,0 +1,45 @@
+//! The simulation's random number generator: xoshiro256**, seeded from the game seed with
+//! splitmix64. Both algorithms are fully specified by their reference implementations,
+//! so the frontend can reproduce every draw exactly. `tests/rng_vectors.txt` holds
+//! outputs for a few seeds that every implementation is checked against.
+
+/// xoshiro256** generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    s: [u64; 4],
+}
+
+impl Rng {
+    /// Fills the state with four successive splitmix64 outputs of `seed`, which is never
+    /// all zero.
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut s = [0u64; 4];
+        for word in s.iter_mut() {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *word = z ^ (z >> 31);
+        }
+        Self { s }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// A value in `0..max`, as `next_u64() % max`.
+    pub fn next_usize(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+}
@@ -0,0 +1,181 @@
+use lane_racer_sim::{
+    find_overlong_pause, simulate_game, simulate_game_with, Action, Difficulty, Game, GameConfig, GameInput, PackedActions,
+    TickState, INPUT_VERSION, MAX_LANES, MAX_PAUSE_TICKS, MAX_POINTS, MAX_SCORE, MAX_SPEED, MIN_LANES,
+};
+use proptest::prelude::*;
+
+fn input(seed: u64, actions: Vec<Action>) -> GameInput {
+    with_lanes(seed, actions, GameConfig::default().lanes)
+}
+
+fn with_lanes(seed: u64, actions: Vec<Action>, lanes: u8) -> GameInput {
+    GameInput {
+        version: INPUT_VERSION,
+        seed,
+        actions: PackedActions::pack(&actions),
+        player_address: "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ".to_string(),
+        game_id: 1,
+        session_id: 1,
+        contract_id: [7; 32],
+        config: GameConfig { lanes, ..GameConfig::default() },
+        difficulty: Difficulty::Normal,
+    }
+}
+
+fn actions() -> impl Strategy<Value = Vec<Action>> {
+    prop::collection::vec(prop::sample::select(vec![Action::Stay, Action::Left, Action::Right]), 0..3_000)
+}
+
+proptest! {
+    #[test]
+    fn same_input_same_result(seed in any::<u64>(), actions in actions()) {
+        let input = input(seed, actions);
+        prop_assert_eq!(simulate_game(&input), simulate_game(&input));
+    }
+
+    #[test]
+    fn score_is_bounded_by_dodges_and_gems(seed in any::<u64>(), actions in actions()) {
+        let result = simulate_game(&input(seed, actions));
+        let config = GameConfig::default();
+        let base = config.points_per_dodge * result.obstacles_dodged
+            + config.near_miss_points * result.near_misses
+            + config.points_per_gem * result.gems_collected;
+        prop_assert!(result.score >= base);
+        prop_assert!(result.score - base <= config.points_per_gem * (config.max_combo_multiplier - 1) * result.gems_collected);
+        prop_assert!(result.near_misses <= result.obstacles_dodged);
+        prop_assert!(result.best_combo <= result.gems_collected);
+    }
+
+    #[test]
+    fn shields_are_only_used_once_collected(seed in any::<u64>(), actions in actions()) {
+        let result = simulate_game(&input(seed, actions));
+        prop_assert!(result.shields_used <= result.shields_collected);
+    }
+
+    #[test]
+    fn counters_are_bounded_by_spawns_and_ticks(seed in any::<u64>(), actions in actions()) {
+        let result = simulate_game(&input(seed, actions.clone()));
+        let played = actions.iter().filter(|action| **action != Action::Pause).count();
+        prop_assert!(result.obstacles_dodged <= result.obstacles_spawned);
+        prop_assert!(result.gems_collected <= result.gems_spawned);
+        prop_assert!(result.ticks as usize <= played);
+        if !result.collision_occurred {
+            prop_assert_eq!(result.ticks as usize, played);
+        }
+    }
+
+    #[test]
+    fn player_stays_within_the_lanes(seed in any::<u64>(), actions in actions(), lanes in MIN_LANES..=MAX_LANES) {
+        let mut max_lane = 0;
+        simulate_game_with(&with_lanes(seed, actions, lanes), |state| max_lane = max_lane.max(state.lane));
+        prop_assert!(max_lane < lanes);
+    }
+
+    #[test]
+    fn extreme_configs_stay_within_the_caps(seed in any::<u64>(), actions in actions(), points in 0..=MAX_POINTS) {
+        let mut input = input(seed, actions);
+        input.config = GameConfig {
+            speed_increment: MAX_SPEED,
+            obstacles_per_speed_up: 1,
+            points_per_dodge: points,
+            points_per_gem: points,
+            near_miss_points: points,
+            max_combo_multiplier: u32::MAX,
+            combo_step: 1,
+            ..input.config
+        };
+        prop_assert!(input.config.is_valid());
+        let result = simulate_game(&input);
+        prop_assert!(result.speed_reached <= MAX_SPEED);
+        prop_assert!(result.score <= MAX_SCORE);
+    }
+
+    #[test]
+    fn configs_with_unbounded_points_are_invalid(points in MAX_POINTS + 1.., field in 0..3usize) {
+        let mut config = GameConfig::default();
+        *[&mut config.points_per_dodge, &mut config.points_per_gem, &mut config.near_miss_points][field] = points;
+        prop_assert!(!config.is_valid());
+    }
+
+    #[test]
+    fn pauses_do_not_change_the_result(
+        seed in any::<u64>(),
+        actions in actions(),
+        pauses in prop::collection::vec((any::<prop::sample::Index>(), 1usize..50), 0..5),
+    ) {
+        let mut paused = actions.clone();
+        for (at, len) in pauses {
+            let at = at.index(paused.len() + 1);
+            paused.splice(at..at, std::iter::repeat_n(Action::Pause, len));
+        }
+        prop_assert_eq!(simulate_game(&input(seed, paused)), simulate_game(&input(seed, actions)));
+    }
+
+    #[test]
+    fn overlong_pauses_are_found(before in 0usize..10, len in 1usize..=MAX_PAUSE_TICKS + 1) {
+        let actions = std::iter::repeat_n(Action::Stay, before).chain(std::iter::repeat_n(Action::Pause, len));
+        let expected = (len > MAX_PAUSE_TICKS).then_some(before);
+        prop_assert_eq!(find_overlong_pause(actions), expected);
+    }
+
+    #[test]
+    fn packed_actions_round_trip(actions in prop::collection::vec(prop::sample::select(Action::ALL.to_vec()), 0..64)) {
+        let packed = PackedActions::pack(&actions);
+        prop_assert!(packed.is_well_formed());
+        prop_assert_eq!(packed.len(), actions.len());
+        prop_assert_eq!(packed.iter().collect::<Vec<Action>>(), actions);
+    }
+
+    #[test]
+    fn ticks_after_a_collision_are_ignored(seed in any::<u64>(), actions in actions(), tail in actions()) {
+        let mut states = Vec::new();
+        let result = simulate_game_with(&input(seed, actions.clone()), |state| states.push(state.clone()));
+        prop_assume!(result.collision_occurred);
+        let mut extended = actions;
+        extended.extend(tail);
+        prop_assert_eq!(simulate_game(&input(seed, extended)), result);
+    }
+
+    #[test]
+    fn tick_states_track_the_result(seed in any::<u64>(), actions in actions()) {
+        let mut states: Vec<TickState> = Vec::new();
+        let result = simulate_game_with(&input(seed, actions.clone()), |state| states.push(state.clone()));
+        if result.collision_occurred {
+            prop_assert!(states.len() <= actions.len());
+        } else {
+            prop_assert_eq!(states.len(), actions.len());
+        }
+        for (tick, state) in states.iter().enumerate() {
+            prop_assert_eq!(state.tick as usize, tick);
+            prop_assert!(state.lane < GameConfig::default().lanes);
+        }
+        if let Some(last) = states.last() {
+            prop_assert_eq!(last.score, result.score);
+            prop_assert_eq!(last.obstacles_dodged, result.obstacles_dodged);
+            prop_assert_eq!(last.gems_collected, result.gems_collected);
+            prop_assert_eq!(last.speed, result.speed_reached);
+            prop_assert_eq!(last.collision, result.collision_occurred);
+        }
+    }
+
+    #[test]
+    fn stepping_matches_simulate(seed in any::<u64>(), actions in actions(), tail in actions()) {
+        let input = input(seed, actions.clone());
+        let mut states = Vec::new();
+        let result = simulate_game_with(&input, |state| states.push(state.clone()));
+        let mut game = Game::new(seed, input.config, input.difficulty);
+        let stepped: Vec<TickState> = actions.iter().map(|&action| game.step(action)).collect();
+        prop_assert_eq!(&stepped[..states.len()], &states[..]);
+        prop_assert_eq!(game.result(&input), result);
+        // Once the run is over, steps change nothing
+        let mut final_state = stepped.last().filter(|state| state.collision).cloned();
+        for action in tail {
+            let state = game.step(action);
+            match &final_state {
+                Some(end) => prop_assert_eq!(&state, end),
+                None if state.collision => final_state = Some(state),
+                None => {}
+            }
+        }
+    }
+}
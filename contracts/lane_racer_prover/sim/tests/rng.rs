@@ -0,0 +1,17 @@
+use lane_racer_sim::rng::Rng;
+
+#[test]
+fn matches_the_shared_test_vectors() {
+    let vectors = include_str!("rng_vectors.txt");
+    let mut checked = 0;
+    for line in vectors.lines().filter(|line| !line.starts_with('#') && !line.is_empty()) {
+        let mut numbers = line.split(' ').map(|n| n.parse::<u64>().unwrap());
+        let seed = numbers.next().unwrap();
+        let mut rng = Rng::new(seed);
+        for (draw, expected) in numbers.enumerate() {
+            assert_eq!(rng.next_u64(), expected, "seed {} draw {}", seed, draw);
+        }
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
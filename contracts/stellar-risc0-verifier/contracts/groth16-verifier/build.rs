@@ -1,18 +1,39 @@
 // This build script helps us generate the VerificationKey for the
 // RiscZeroGroth16Verifier during the compilation. The key is fetched from
-// `config.json` and makes it available as a const to the contract. This way, the
-// verification key gets included in the contract at compile time, so we don't
-// have to initialize the contract and spend resources on reading from the
+// `parameters.json` and makes it available as a const to the contract. This way,
+// the verification key gets included in the contract at compile time, so we
+// don't have to initialize the contract and spend resources on reading from the
 // ledger the verification key.
+//
+// Setting `RISC0_VK_JSON` to the path of a verification key in either the
+// RISC Zero (`parameters.json` `verification_key` object) or snarkjs
+// (`verification_key.json`) format overrides the vendored key, so verifiers for
+// custom circuits can be built without editing this script.
+//
+// `RISC0_MAX_JOURNAL_LEN` overrides the longest journal, in bytes, that
+// `verify_journal` accepts.
 
 use std::{env, fs, path::PathBuf, str::FromStr};
 
-use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
-use ark_ec::AffineRepr;
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, PrimeField};
 use build_utils::{Sha256Digest, hash_g1_point, hash_g2_point, tagged_iter, tagged_struct};
 use serde::Deserialize;
 
+/// Environment variable holding the path of an external verification key JSON.
+const VK_JSON_ENV: &str = "RISC0_VK_JSON";
+
+/// Environment variable overriding the longest journal `verify_journal` hashes.
+const MAX_JOURNAL_LEN_ENV: &str = "RISC0_MAX_JOURNAL_LEN";
+
+/// Longest journal `verify_journal` hashes unless overridden: 16 KiB keeps the hashing cost
+/// well inside the transaction budget.
+const DEFAULT_MAX_JOURNAL_LEN: u32 = 16 * 1024;
+
+/// Number of IC points of the RISC Zero receipt circuit (five public inputs).
+const RISC0_IC_LEN: usize = 6;
+
 struct VerificationKey {
     alpha: G1Affine,
     beta: G2Affine,
@@ -64,6 +85,56 @@ impl VerificationKeyJson {
     }
 }
 
+/// JSON representation of a Groth16 verification key as exported by snarkjs
+/// (`snarkjs zkey export verificationkey`).
+///
+/// Points are given in projective coordinates as decimal strings. G2
+/// coordinates are `[c0, c1]` pairs, i.e. the real part comes first.
+#[derive(Deserialize)]
+struct SnarkjsVerificationKeyJson {
+    vk_alpha_1: Vec<String>,
+    vk_beta_2: Vec<Vec<String>>,
+    vk_gamma_2: Vec<Vec<String>>,
+    vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    ic: Vec<Vec<String>>,
+}
+
+impl SnarkjsVerificationKeyJson {
+    pub fn to_verification_key_json(&self) -> VerificationKeyJson {
+        VerificationKeyJson {
+            alpha: PointG1Json::from_snarkjs(&self.vk_alpha_1),
+            beta: PointG2Json::from_snarkjs(&self.vk_beta_2),
+            gamma: PointG2Json::from_snarkjs(&self.vk_gamma_2),
+            delta: PointG2Json::from_snarkjs(&self.vk_delta_2),
+            ic: self
+                .ic
+                .iter()
+                .map(|p| PointG1Json::from_snarkjs(p))
+                .collect(),
+        }
+    }
+}
+
+/// Verification key formats accepted through `RISC0_VK_JSON`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExternalVerificationKeyJson {
+    /// The `verification_key` object used in `parameters.json`.
+    Risc0(Box<VerificationKeyJson>),
+    /// A snarkjs `verification_key.json`.
+    Snarkjs(SnarkjsVerificationKeyJson),
+}
+
+impl ExternalVerificationKeyJson {
+    pub fn into_verification_key_json(self) -> VerificationKeyJson {
+        match self {
+            Self::Risc0(vk) => *vk,
+            Self::Snarkjs(vk) => vk.to_verification_key_json(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct PointG1Json {
     x: String,
@@ -71,6 +142,18 @@ struct PointG1Json {
 }
 
 impl PointG1Json {
+    pub fn from_snarkjs(point: &[String]) -> Self {
+        assert!(point.len() >= 2, "snarkjs G1 point must have x and y");
+        assert!(
+            point.get(2).is_none_or(|z| z == "1"),
+            "snarkjs G1 point must be normalized (z = 1)"
+        );
+        Self {
+            x: point[0].clone(),
+            y: point[1].clone(),
+        }
+    }
+
     pub fn to_g1_affine(&self) -> G1Affine {
         let x = Fq::from_str(&self.x).expect("Invalid field element for G1.x");
         let y = Fq::from_str(&self.y).expect("Invalid field element for G1.y");
@@ -90,6 +173,28 @@ struct PointG2Json {
 }
 
 impl PointG2Json {
+    pub fn from_snarkjs(point: &[Vec<String>]) -> Self {
+        assert!(point.len() >= 2, "snarkjs G2 point must have x and y");
+        assert!(
+            point[0].len() == 2 && point[1].len() == 2,
+            "snarkjs G2 coordinates must be [c0, c1] pairs"
+        );
+        assert!(
+            point
+                .get(2)
+                .is_none_or(|z| z.len() == 2 && z[0] == "1" && z[1] == "0"),
+            "snarkjs G2 point must be normalized (z = [1, 0])"
+        );
+        // snarkjs lists the real part first, while `x1`/`y1` hold the
+        // imaginary part (Solidity ordering).
+        Self {
+            x1: point[0][1].clone(),
+            x2: point[0][0].clone(),
+            y1: point[1][1].clone(),
+            y2: point[1][0].clone(),
+        }
+    }
+
     pub fn to_g2_affine(&self) -> G2Affine {
         let x_im = Fq::from_str(&self.x1).expect("Invalid field element for G2.x_im");
         let x_re = Fq::from_str(&self.x2).expect("Invalid field element for G2.x_re");
@@ -176,6 +281,37 @@ fn compute_control_roots(control_root: &str) -> ([u8; 16], [u8; 16]) {
     (control_root_0, control_root_1)
 }
 
+/// Folds the constant public inputs of the RISC Zero circuit into `IC[0]`.
+///
+/// The public inputs are `[control_root_0, control_root_1, claim_0, claim_1,
+/// bn254_control_id]`. Three of them are fixed for a given verifier build, so
+/// their share of `vk_x` is computed here rather than paying a `g1_mul` and a
+/// `g1_add` for each of them on every verification. Keys that don't follow the
+/// RISC Zero layout get `IC[0]` unchanged; the contract rejects receipts for
+/// them anyway.
+fn compute_ic_constant(
+    vk: &VerificationKey,
+    control_root_0: &[u8; 16],
+    control_root_1: &[u8; 16],
+    bn254_control_id: &[u8; 32],
+) -> G1Affine {
+    if vk.ic.len() != RISC0_IC_LEN {
+        return vk.ic[0];
+    }
+
+    let terms: [(usize, &[u8]); 3] = [
+        (1, control_root_0),
+        (2, control_root_1),
+        (5, bn254_control_id),
+    ];
+    terms
+        .iter()
+        .fold(vk.ic[0].into_group(), |acc, (i, input)| {
+            acc + vk.ic[*i] * Fr::from_be_bytes_mod_order(input)
+        })
+        .into_affine()
+}
+
 fn fq_to_be_bytes(f: &Fq) -> Vec<u8> {
     let num = f.into_bigint();
     num.to_bytes_be()
@@ -212,12 +348,55 @@ fn serialize_g2_point(p: &G2Affine) -> [u8; 128] {
     buf
 }
 
+/// Loads the external verification key pointed to by `RISC0_VK_JSON`, if set.
+fn load_external_verification_key() -> Option<(PathBuf, VerificationKeyJson)> {
+    println!("cargo:rerun-if-env-changed={VK_JSON_ENV}");
+
+    let path = PathBuf::from(env::var_os(VK_JSON_ENV)?);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let data = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {VK_JSON_ENV} ({}): {e}", path.display()));
+    let vk: ExternalVerificationKeyJson = serde_json::from_str(&data).unwrap_or_else(|e| {
+        panic!(
+            "{VK_JSON_ENV} ({}) is neither a RISC Zero nor a snarkjs verification key: {e}",
+            path.display()
+        )
+    });
+
+    Some((path, vk.into_verification_key_json()))
+}
+
+/// Reads the journal length limit from `RISC0_MAX_JOURNAL_LEN`, if set.
+fn max_journal_len() -> u32 {
+    println!("cargo:rerun-if-env-changed={MAX_JOURNAL_LEN_ENV}");
+
+    match env::var(MAX_JOURNAL_LEN_ENV) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|e| panic!("{MAX_JOURNAL_LEN_ENV} ({value}) is not a u32: {e}")),
+        Err(_) => DEFAULT_MAX_JOURNAL_LEN,
+    }
+}
+
 fn main() {
+    println!("cargo:rerun-if-changed=parameters.json");
+
     let path = PathBuf::from("parameters.json");
     let data = fs::read_to_string(path).unwrap();
     let params: VerifierParameters = serde_json::from_str(&data).unwrap();
 
-    let vk = params.verification_key.to_verification_key();
+    let (vk_source, vk) = match load_external_verification_key() {
+        Some((path, vk)) => (path.display().to_string(), vk.to_verification_key()),
+        None => (
+            "parameters.json".to_string(),
+            params.verification_key.to_verification_key(),
+        ),
+    };
+    assert!(
+        !vk.ic.is_empty(),
+        "verification key must have at least one IC point"
+    );
 
     // Compute all parameters (this will print intermediate values)
     let vk_digest = compute_vk_digest(&vk);
@@ -257,6 +436,10 @@ fn main() {
         hex::encode(vk_digest)
     );
     println!("cargo:warning=VERSION:             {}", &params.version);
+    println!("cargo:warning=VERIFICATION_KEY:    {}", vk_source);
+    println!("cargo:warning=IC_POINTS:           {}", vk.ic.len());
+    let max_journal_len = max_journal_len();
+    println!("cargo:warning=MAX_JOURNAL_LEN:     {}", max_journal_len);
     println!("cargo:warning===========================================");
 
     // Generate the VerificationKey IC array
@@ -281,24 +464,27 @@ fn main() {
         format_byte_array::<128>(&serialize_g2_point(&vk.delta)),
         ic
     );
+    let ic_constant = compute_ic_constant(&vk, &control_root_0, &control_root_1, &bn254_control_id);
+    let ic_constant_code = format_byte_array::<64>(&serialize_g1_point(&ic_constant));
     let selector_code = format_byte_array(&selector);
-    let control_root_0_code = format_byte_array(&control_root_0);
-    let control_root_1_code = format_byte_array(&control_root_1);
-    let bn254_control_id_code = format_byte_array(&bn254_control_id);
     let version_code = format!("\"{}\"", params.version);
 
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
     fs::write(out_dir.join("verification_key.rs"), vk_code)
         .expect("failed to write verification_key.rs");
 
+    fs::write(out_dir.join("ic_len.rs"), vk.ic.len().to_string())
+        .expect("failed to write ic_len.rs");
+    fs::write(out_dir.join("risc0_ic_len.rs"), RISC0_IC_LEN.to_string())
+        .expect("failed to write risc0_ic_len.rs");
+    fs::write(out_dir.join("ic_constant.rs"), ic_constant_code)
+        .expect("failed to write ic_constant.rs");
+
     fs::write(out_dir.join("version.rs"), version_code).expect("failed to write version.rs");
     fs::write(out_dir.join("selector.rs"), selector_code).expect("failed to write selector.rs");
-
-    fs::write(out_dir.join("control_root_0.rs"), control_root_0_code)
-        .expect("failed to write control_root_0.rs");
-    fs::write(out_dir.join("control_root_1.rs"), control_root_1_code)
-        .expect("failed to write control_root_1.rs");
-
-    fs::write(out_dir.join("bn254_control_id.rs"), bn254_control_id_code)
-        .expect("failed to write bn254_control_id.rs");
+    fs::write(
+        out_dir.join("max_journal_len.rs"),
+        format!("{max_journal_len}u32"),
+    )
+    .expect("failed to write max_journal_len.rs");
 }
@@ -0,0 +1,447 @@
+//! BN254 point validation for decoded seals.
+//!
+//! The BN254 host functions trap on points that are off the curve or outside the prime-order
+//! subgroup, which reaches the caller as an opaque host error. This module carries just enough
+//! base field arithmetic to run those checks inside the contract before the pairing, so every
+//! verification path, and `check_seal`, returns a [`VerifierError`] that tells an encoding bug
+//! apart from a corrupted proof.
+//!
+//! Field elements are kept in Montgomery form over four little-endian `u64` limbs.
+
+use risc0_interface::VerifierError;
+
+type Limbs = [u64; 4];
+
+/// Base field modulus `p`.
+const MODULUS: Limbs = [
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// `-p^-1 mod 2^64`.
+const INV: u64 = 0x87d20782e4866389;
+
+/// `2^512 mod p`, used to move canonical values into Montgomery form.
+const R2: Limbs = [
+    0xf32cfc5b538afa89,
+    0xb5e71911d44501fb,
+    0x47ab1eff0a417ff6,
+    0x06d89f71cab8351f,
+];
+
+/// `b = 3` of the G1 curve `y^2 = x^3 + 3`.
+const G1_B: Fq = Fq([
+    0x7a17caa950ad28d7,
+    0x1f6ac17ae15521b9,
+    0x334bea4e696bd284,
+    0x2a1f6744ce179d8e,
+]);
+
+/// `b' = 3 / (u + 9)` of the G2 twist `y^2 = x^3 + b'`.
+const G2_B: Fq2 = Fq2 {
+    c0: Fq([
+        0x3bf938e377b802a8,
+        0x020b1b273633535d,
+        0x26b7edf049755260,
+        0x2514c6324384a86d,
+    ]),
+    c1: Fq([
+        0x38e7ecccd1dcff67,
+        0x65f0b37d93ce0d3e,
+        0xd749d0dd22ac00aa,
+        0x0141b9ce4a688d4d,
+    ]),
+};
+
+/// `(u + 9)^((p - 1) / 3)`, the x coefficient of the p-power endomorphism `psi`.
+const PSI_X: Fq2 = Fq2 {
+    c0: Fq([
+        0xb5773b104563ab30,
+        0x347f91c8a9aa6454,
+        0x7a007127242e0991,
+        0x1956bcd8118214ec,
+    ]),
+    c1: Fq([
+        0x6e849f1ea0aa4757,
+        0xaa1c7b6d89f89141,
+        0xb6e713cdfae0ca3a,
+        0x26694fbb4e82ebc3,
+    ]),
+};
+
+/// `(u + 9)^((p - 1) / 2)`, the y coefficient of the p-power endomorphism `psi`.
+const PSI_Y: Fq2 = Fq2 {
+    c0: Fq([
+        0xe4bbdd0c2936b629,
+        0xbb30f162e133bacb,
+        0x31a9d1b6f9645366,
+        0x253570bea500f8dd,
+    ]),
+    c1: Fq([
+        0xa1d77ce45ffe77c7,
+        0x07affd117826d1db,
+        0x6d16bd27bb7edc6b,
+        0x2c87200285defecc,
+    ]),
+};
+
+/// `6x^2` for the BN254 curve parameter `x`, little-endian limbs.
+const SIX_X_SQUARED: [u64; 2] = [0xf83e9682e87cfd46, 0x6f4d8248eeb859fb];
+
+/// Checks that `bytes` encodes a G1 point (`x || y`, big-endian) on the curve.
+///
+/// G1 has cofactor one, so every point on the curve is in the prime-order subgroup. The
+/// all-zero encoding is the point at infinity.
+pub fn check_g1(bytes: &[u8; 64]) -> Result<(), VerifierError> {
+    if is_zero(bytes) {
+        return Ok(());
+    }
+
+    let x = Fq::from_be_slice(&bytes[0..32])?;
+    let y = Fq::from_be_slice(&bytes[32..64])?;
+
+    if y.square() != x.square().mul(&x).add(&G1_B) {
+        return Err(VerifierError::PointNotOnCurve);
+    }
+    Ok(())
+}
+
+/// Checks that `bytes` encodes a G2 point (`x_1 || x_0 || y_1 || y_0`, big-endian with the
+/// imaginary part first) on the twist and in the prime-order subgroup.
+pub fn check_g2(bytes: &[u8; 128]) -> Result<(), VerifierError> {
+    if is_zero(bytes) {
+        return Ok(());
+    }
+
+    let x = Fq2 {
+        c1: Fq::from_be_slice(&bytes[0..32])?,
+        c0: Fq::from_be_slice(&bytes[32..64])?,
+    };
+    let y = Fq2 {
+        c1: Fq::from_be_slice(&bytes[64..96])?,
+        c0: Fq::from_be_slice(&bytes[96..128])?,
+    };
+
+    if y.square() != x.square().mul(&x).add(&G2_B) {
+        return Err(VerifierError::PointNotOnCurve);
+    }
+
+    if !is_in_g2_subgroup(&x, &y) {
+        return Err(VerifierError::PointNotInSubgroup);
+    }
+    Ok(())
+}
+
+/// Negates an encoded G1 point, `(x, y) -> (x, p - y)`.
+///
+/// The point at infinity, and any point with `y = 0`, is its own negation. `y` is assumed to
+/// be reduced, as the host rejects encodings that are not.
+pub fn neg_g1(bytes: &[u8; 64]) -> [u8; 64] {
+    let mut out = *bytes;
+    if is_zero(&bytes[32..64]) {
+        return out;
+    }
+
+    let (neg_y, _) = sub_limbs(&MODULUS, &be_limbs(&bytes[32..64]));
+    let (words, _) = out[32..64].as_chunks_mut::<8>();
+    for (word, limb) in words.iter_mut().rev().zip(neg_y) {
+        *word = limb.to_be_bytes();
+    }
+    out
+}
+
+/// Reads a big-endian 256-bit value into little-endian limbs.
+///
+/// Works on fixed-size words so that no length checks, and none of the panic formatting
+/// behind them, end up in the wasm.
+fn be_limbs(bytes: &[u8]) -> Limbs {
+    let mut limbs = [0u64; 4];
+    let (words, _) = bytes.as_chunks::<8>();
+    for (limb, word) in limbs.iter_mut().zip(words.iter().rev()) {
+        *limb = u64::from_be_bytes(*word);
+    }
+    limbs
+}
+
+fn is_zero(bytes: &[u8]) -> bool {
+    bytes.iter().all(|b| *b == 0)
+}
+
+/// Subgroup check from section 4.3 of <https://eprint.iacr.org/2022/352.pdf>, as used by
+/// arkworks: a point on the twist is in G2 iff `[6x^2]P == psi(P)`.
+fn is_in_g2_subgroup(x: &Fq2, y: &Fq2) -> bool {
+    let mut acc = G2Jacobian::from_affine(x, y);
+    let bits = SIX_X_SQUARED
+        .iter()
+        .rev()
+        .flat_map(|limb| (0..64).rev().map(move |i| (limb >> i) & 1 == 1))
+        .skip_while(|bit| !bit)
+        .skip(1);
+    for bit in bits {
+        acc = acc.double();
+        if bit {
+            acc = acc.add_affine(x, y);
+        }
+    }
+
+    // psi(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y); compare in Jacobian coordinates.
+    if acc.z.is_zero() {
+        return false;
+    }
+    let psi_x = x.conjugate().mul(&PSI_X);
+    let psi_y = y.conjugate().mul(&PSI_Y);
+    let z2 = acc.z.square();
+    let z3 = z2.mul(&acc.z);
+    acc.x == psi_x.mul(&z2) && acc.y == psi_y.mul(&z3)
+}
+
+/// Element of the base field in Montgomery form.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fq(Limbs);
+
+impl Fq {
+    const ZERO: Self = Self([0; 4]);
+
+    /// `2^256 mod p`, the Montgomery form of one.
+    const ONE: Self = Self([
+        0xd35d438dc58f0d9d,
+        0x0a78eb28f5c70b3d,
+        0x666ea36f7879462c,
+        0x0e0a77c19a07df2f,
+    ]);
+
+    /// Parses a big-endian field element, rejecting values that are not below the modulus.
+    fn from_be_slice(bytes: &[u8]) -> Result<Self, VerifierError> {
+        let limbs = be_limbs(bytes);
+        if sub_modulus(&limbs).is_some() {
+            return Err(VerifierError::PointNotOnCurve);
+        }
+        Ok(Self(limbs).mul(&Self(R2)))
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        // p < 2^254, so the sum of two reduced elements cannot overflow 256 bits.
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for ((limb, a), b) in out.iter_mut().zip(self.0).zip(rhs.0) {
+            let (sum, c1) = a.overflowing_add(b);
+            let (sum, c2) = sum.overflowing_add(u64::from(carry));
+            *limb = sum;
+            carry = c1 | c2;
+        }
+        Self(sub_modulus(&out).unwrap_or(out))
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        let (diff, borrow) = sub_limbs(&self.0, &rhs.0);
+        if !borrow {
+            return Self(diff);
+        }
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for ((limb, d), m) in out.iter_mut().zip(diff).zip(MODULUS) {
+            let (sum, c1) = d.overflowing_add(m);
+            let (sum, c2) = sum.overflowing_add(u64::from(carry));
+            *limb = sum;
+            carry = c1 | c2;
+        }
+        Self(out)
+    }
+
+    fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    /// Montgomery multiplication (CIOS).
+    fn mul(&self, rhs: &Self) -> Self {
+        let (a, b) = (&self.0, &rhs.0);
+        let mut t = [0u64; 6];
+        for b_i in b {
+            let mut carry = 0;
+            for (j, a_j) in a.iter().enumerate() {
+                (t[j], carry) = mac(t[j], *a_j, *b_i, carry);
+            }
+            let (sum, overflow) = t[4].overflowing_add(carry);
+            t[4] = sum;
+            t[5] = u64::from(overflow);
+
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+            for j in 1..4 {
+                (t[j - 1], carry) = mac(t[j], m, MODULUS[j], carry);
+            }
+            let (sum, overflow) = t[4].overflowing_add(carry);
+            t[3] = sum;
+            t[4] = t[5].wrapping_add(u64::from(overflow));
+        }
+
+        let out = [t[0], t[1], t[2], t[3]];
+        Self(sub_modulus(&out).unwrap_or(out))
+    }
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+}
+
+/// `a + b * c + carry`, returned as `(low, high)` words.
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let wide = u128::from(a)
+        .wrapping_add(u128::from(b).wrapping_mul(u128::from(c)))
+        .wrapping_add(u128::from(carry));
+    (wide as u64, (wide >> 64) as u64)
+}
+
+fn sub_limbs(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for ((limb, a), b) in out.iter_mut().zip(a).zip(b) {
+        let (diff, b1) = a.overflowing_sub(*b);
+        let (diff, b2) = diff.overflowing_sub(u64::from(borrow));
+        *limb = diff;
+        borrow = b1 | b2;
+    }
+    (out, borrow)
+}
+
+/// Returns `value - p` when `value >= p`.
+fn sub_modulus(value: &Limbs) -> Option<Limbs> {
+    match sub_limbs(value, &MODULUS) {
+        (diff, false) => Some(diff),
+        (_, true) => None,
+    }
+}
+
+/// Element `c0 + c1 * u` of the quadratic extension `Fq[u] / (u^2 + 1)`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fq2 {
+    c0: Fq,
+    c1: Fq,
+}
+
+impl Fq2 {
+    const ZERO: Self = Self {
+        c0: Fq::ZERO,
+        c1: Fq::ZERO,
+    };
+
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        Self {
+            c0: self.c0.add(&rhs.c0),
+            c1: self.c1.add(&rhs.c1),
+        }
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        Self {
+            c0: self.c0.sub(&rhs.c0),
+            c1: self.c1.sub(&rhs.c1),
+        }
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    /// The Frobenius map `x -> x^p`.
+    fn conjugate(&self) -> Self {
+        Self {
+            c0: self.c0,
+            c1: self.c1.neg(),
+        }
+    }
+
+    /// Karatsuba multiplication.
+    fn mul(&self, rhs: &Self) -> Self {
+        let v0 = self.c0.mul(&rhs.c0);
+        let v1 = self.c1.mul(&rhs.c1);
+        let cross = self.c0.add(&self.c1).mul(&rhs.c0.add(&rhs.c1));
+        Self {
+            c0: v0.sub(&v1),
+            c1: cross.sub(&v0).sub(&v1),
+        }
+    }
+
+    fn square(&self) -> Self {
+        Self {
+            c0: self.c0.add(&self.c1).mul(&self.c0.sub(&self.c1)),
+            c1: self.c0.mul(&self.c1).double(),
+        }
+    }
+}
+
+/// G2 point in Jacobian coordinates `(X / Z^2, Y / Z^3)`; `Z = 0` is the point at infinity.
+struct G2Jacobian {
+    x: Fq2,
+    y: Fq2,
+    z: Fq2,
+}
+
+impl G2Jacobian {
+    fn from_affine(x: &Fq2, y: &Fq2) -> Self {
+        Self {
+            x: *x,
+            y: *y,
+            z: Fq2 {
+                c0: Fq::ONE,
+                c1: Fq::ZERO,
+            },
+        }
+    }
+
+    /// `dbl-2009-l` for `a = 0`.
+    fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let f = e.square();
+        let x = f.sub(&d.double());
+        let y = e.mul(&d.sub(&x)).sub(&c.double().double().double());
+        let z = self.y.mul(&self.z).double();
+        Self { x, y, z }
+    }
+
+    /// `madd-2007-bl`, adding the affine point `(x2, y2)`.
+    fn add_affine(&self, x2: &Fq2, y2: &Fq2) -> Self {
+        if self.z.is_zero() {
+            return Self::from_affine(x2, y2);
+        }
+
+        let z1z1 = self.z.square();
+        let u2 = x2.mul(&z1z1);
+        let s2 = y2.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y).double();
+        if h.is_zero() {
+            if r.is_zero() {
+                return self.double();
+            }
+            return Self {
+                x: Fq2::ZERO,
+                y: Fq2::ZERO,
+                z: Fq2::ZERO,
+            };
+        }
+
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h.mul(&i);
+        let v = self.x.mul(&i);
+        let x = r.square().sub(&j).sub(&v.double());
+        let y = r.mul(&v.sub(&x)).sub(&self.y.mul(&j).double());
+        let z = self.z.add(&h).square().sub(&z1z1).sub(&hh);
+        Self { x, y, z }
+    }
+}
@@ -1,12 +1,18 @@
 #![no_std]
 
-use risc0_interface::{Receipt, ReceiptClaim, RiscZeroVerifierInterface, VerifierError};
+use risc0_interface::{
+    FullReceipt, Receipt, ReceiptClaim, RiscZeroFullReceiptVerifierInterface,
+    RiscZeroVerifierInterface, VerifierError,
+};
 use soroban_sdk::{
-    Bytes, BytesN, Env, String, Vec, contract, contractimpl, crypto::bn254::Fr, vec,
+    Bytes, BytesN, Env, String, Vec, contract, contractimpl,
+    crypto::bn254::{Bn254G1Affine as G1Affine, Bn254G2Affine as G2Affine, Fr},
+    vec,
 };
 
-use types::{Groth16Proof, Groth16Seal, VerificationKeyBytes};
+use types::{Groth16Proof, Groth16Seal, IC_LEN, RISC0_IC_LEN, VerificationKeyBytes};
 
+mod curve;
 #[cfg(test)]
 mod test;
 mod types;
@@ -22,26 +28,131 @@ pub struct RiscZeroGroth16Verifier;
 impl RiscZeroGroth16Verifier {
     /// Groth16 verification key for the RISC Zero system.
     ///
-    /// This verification key is generated at build time from `vk.json`
+    /// This verification key is generated at build time from `parameters.json`, or from the
+    /// file pointed to by `RISC0_VK_JSON` when set.
     const VERIFICATION_KEY: VerificationKeyBytes =
         include!(concat!(env!("OUT_DIR"), "/verification_key.rs"));
 
+    /// `IC[0]` with the constant public inputs (control root halves and BN254 control ID)
+    /// already folded in at build time. See [`Self::verify_claim`].
+    const IC_CONSTANT: [u8; 64] = include!(concat!(env!("OUT_DIR"), "/ic_constant.rs"));
+
     const VERSION: &'static str = include!(concat!(env!("OUT_DIR"), "/version.rs"));
-    const CONTROL_ROOT_0: [u8; 16] = include!(concat!(env!("OUT_DIR"), "/control_root_0.rs"));
-    const CONTROL_ROOT_1: [u8; 16] = include!(concat!(env!("OUT_DIR"), "/control_root_1.rs"));
-    const BN254_CONTROL_ID: [u8; 32] = include!(concat!(env!("OUT_DIR"), "/bn254_control_id.rs"));
     const SELECTOR: [u8; 4] = include!(concat!(env!("OUT_DIR"), "/selector.rs"));
 
+    /// Whether `verify` and `verify_integrity` compare the seal selector with
+    /// [`Self::SELECTOR`]; off only in builds with the `skip-selector-check` feature.
+    const CHECK_SELECTOR: bool = !cfg!(feature = "skip-selector-check");
+
+    /// Longest journal [`Self::verify_journal`] hashes, set at build time through
+    /// `RISC0_MAX_JOURNAL_LEN`.
+    const MAX_JOURNAL_LEN: u32 = include!(concat!(env!("OUT_DIR"), "/max_journal_len.rs"));
+
     /// Returns the verifier's selector
     pub fn selector(env: Env) -> BytesN<4> {
         BytesN::from_array(&env, &Self::SELECTOR)
     }
 
+    /// Returns whether this build rejects seals carrying another verifier's selector.
+    ///
+    /// Builds that skip the check must only be reachable through a router, which already
+    /// dispatches on the selector; check this before registering one standalone.
+    pub fn checks_selector() -> bool {
+        Self::CHECK_SELECTOR
+    }
+
     /// Returns the RISC Zero verifier version
     pub fn version(env: Env) -> String {
         String::from_str(&env, Self::VERSION)
     }
 
+    /// Returns the longest journal, in bytes, that [`Self::verify_journal`] accepts.
+    pub fn max_journal_len() -> u32 {
+        Self::MAX_JOURNAL_LEN
+    }
+
+    /// Verifies a receipt from the journal bytes rather than their digest.
+    ///
+    /// Hashes `journal` on-chain, so callers do not have to, and otherwise behaves like
+    /// [`RiscZeroVerifierInterface::verify`]. Journals longer than [`Self::max_journal_len`]
+    /// are rejected before hashing, so an oversized journal fails with a clear error instead
+    /// of exhausting the transaction budget.
+    ///
+    /// # Errors
+    ///
+    /// - [`VerifierError::JournalTooLarge`] - The journal is longer than the limit
+    /// - Any error of [`RiscZeroVerifierInterface::verify`]
+    pub fn verify_journal(
+        env: Env,
+        seal: Bytes,
+        image_id: BytesN<32>,
+        journal: Bytes,
+    ) -> Result<(), VerifierError> {
+        if journal.len() > Self::MAX_JOURNAL_LEN {
+            return Err(VerifierError::JournalTooLarge);
+        }
+        let journal_digest = env.crypto().sha256(&journal).into();
+        Self::verify(env, seal, image_id, journal_digest)
+    }
+
+    /// Decodes `seal` and runs every point check without verifying the proof.
+    ///
+    /// Intended for provers simulating a submission: `verify` runs the same checks before
+    /// the pairing, so this tells an encoding bug apart from a proof that does not verify
+    /// without paying for the pairing.
+    ///
+    /// # Errors
+    ///
+    /// - [`VerifierError::InvalidSealLength`] - The seal is not 260 bytes long
+    /// - [`VerifierError::InvalidSelector`] - The selector in the seal doesn't match this verifier
+    /// - [`VerifierError::PointNotOnCurve`] - A coordinate is not below the field modulus or a
+    ///   point does not satisfy its curve equation
+    /// - [`VerifierError::PointNotInSubgroup`] - `b` is on the twist but not in G2
+    pub fn check_seal(seal: Bytes) -> Result<(), VerifierError> {
+        let seal = Groth16Seal::try_from(seal)?;
+
+        if seal.selector != Self::SELECTOR {
+            return Err(VerifierError::InvalidSelector);
+        }
+
+        check_points(&seal.proof)
+    }
+
+    /// Verifies a receipt given its 256-byte proof without the 4-byte selector prefix.
+    ///
+    /// For contracts that call this verifier directly rather than through the router, where
+    /// the selector carries no routing information. Otherwise identical to
+    /// [`RiscZeroVerifierInterface::verify`].
+    ///
+    /// # Errors
+    ///
+    /// - [`VerifierError::InvalidSealLength`] - The proof is not 256 bytes long
+    /// - [`VerifierError::PointNotOnCurve`] - A proof point is not a valid curve point
+    /// - [`VerifierError::PointNotInSubgroup`] - `b` is on the twist but not in G2
+    /// - [`VerifierError::InvalidProof`] - The cryptographic verification fails
+    pub fn verify_unprefixed(
+        env: Env,
+        proof: Bytes,
+        image_id: BytesN<32>,
+        journal: BytesN<32>,
+    ) -> Result<(), VerifierError> {
+        let claim = ReceiptClaim::new(&env, image_id, journal);
+        let receipt = Receipt {
+            seal: proof,
+            claim_digest: claim.digest(&env),
+        };
+        Self::verify_integrity_unprefixed(env, receipt)
+    }
+
+    /// Verifies a receipt whose seal is the 256-byte proof without the selector prefix.
+    ///
+    /// Selector-less counterpart of [`RiscZeroVerifierInterface::verify_integrity`]; see
+    /// [`Self::verify_unprefixed`].
+    pub fn verify_integrity_unprefixed(env: Env, receipt: Receipt) -> Result<(), VerifierError> {
+        let proof = Groth16Proof::try_from(receipt.seal)?;
+        Self::verify_claim_digest(&env, proof, receipt.claim_digest)
+    }
+
     /// Verifies a Groth16 proof with the given public signals.
     ///
     /// This function implements the core Groth16 verification algorithm using the BN254
@@ -62,26 +173,103 @@ impl RiscZeroGroth16Verifier {
         proof: Groth16Proof,
         pub_signals: Vec<Fr>,
     ) -> Result<bool, VerifierError> {
-        let vk = Self::VERIFICATION_KEY.verification_key(&env);
+        let vk = &Self::VERIFICATION_KEY;
         let bn = env.crypto().bn254();
 
-        if pub_signals.len() + 1 != vk.ic.len() as u32 {
+        if pub_signals.len() + 1 != IC_LEN as u32 {
             return Err(VerifierError::MalformedPublicInputs);
         }
+        check_points(&proof)?;
 
-        let mut vk_x = vk.ic[0].clone();
-        for (s, v) in pub_signals.iter().zip(vk.ic.iter().skip(1)) {
-            let prod = bn.g1_mul(v, &s);
+        // IC points are decoded one at a time as they are used rather than as a whole key.
+        let mut vk_x = G1Affine::from_array(&env, &vk.ic[0]);
+        for (s, v) in pub_signals.try_iter().zip(vk.ic.iter().skip(1)) {
+            let s = s.map_err(|_| VerifierError::MalformedPublicInputs)?;
+            let prod = bn.g1_mul(&G1Affine::from_array(&env, v), &s);
             vk_x = bn.g1_add(&vk_x, &prod);
         }
 
-        // Compute the pairing check:
-        // e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
-        let neg_a = -proof.a;
-        let g1_points = vec![&env, neg_a, vk.alpha, vk_x, proof.c];
-        let g2_points = vec![&env, proof.b, vk.beta, vk.gamma, vk.delta];
+        Ok(Self::pairing_check(&env, proof, vk_x))
+    }
+
+    /// Verifies a decoded proof against a receipt claim digest.
+    fn verify_claim_digest(
+        env: &Env,
+        proof: Groth16Proof,
+        claim_digest: BytesN<32>,
+    ) -> Result<(), VerifierError> {
+        check_points(&proof)?;
+        let (claim_0, claim_1) = split_digest(env, claim_digest);
+
+        // The control root and BN254 control ID inputs are folded into IC_CONSTANT, so only
+        // the claim digest halves are combined at runtime.
+        match Self::verify_claim(env, proof, Fr::from_bytes(claim_0), Fr::from_bytes(claim_1))? {
+            true => Ok(()),
+            false => Err(VerifierError::InvalidProof),
+        }
+    }
+
+    /// Verifies a proof of the RISC Zero receipt circuit for the given claim digest halves.
+    ///
+    /// Equivalent to [`Self::verify_proof`] with the public inputs `[control_root_0,
+    /// control_root_1, claim_0, claim_1, bn254_control_id]`, except that the three constant
+    /// inputs are pre-combined into [`Self::IC_CONSTANT`], leaving two `g1_mul`/`g1_add`
+    /// pairs per call instead of five.
+    ///
+    /// The pairing side cannot be cached the same way: the BN254 host functions expose
+    /// neither a multi-scalar multiplication nor a way to feed a precomputed
+    /// `e(alpha, beta)` into `pairing_check`, so all four pairings are still evaluated.
+    fn verify_claim(
+        env: &Env,
+        proof: Groth16Proof,
+        claim_0: Fr,
+        claim_1: Fr,
+    ) -> Result<bool, VerifierError> {
+        // Keys loaded through `RISC0_VK_JSON` for other circuits have no RISC Zero layout.
+        if IC_LEN != RISC0_IC_LEN {
+            return Err(VerifierError::MalformedPublicInputs);
+        }
+
+        let vk = &Self::VERIFICATION_KEY;
+        let (Some(ic_claim_0), Some(ic_claim_1)) = (vk.ic.get(3), vk.ic.get(4)) else {
+            return Err(VerifierError::MalformedPublicInputs);
+        };
+
+        let bn = env.crypto().bn254();
+        let ic_constant = G1Affine::from_array(env, &Self::IC_CONSTANT);
+        let ic_claim_0 = G1Affine::from_array(env, ic_claim_0);
+        let ic_claim_1 = G1Affine::from_array(env, ic_claim_1);
+
+        let vk_x = bn.g1_add(&ic_constant, &bn.g1_mul(&ic_claim_0, &claim_0));
+        let vk_x = bn.g1_add(&vk_x, &bn.g1_mul(&ic_claim_1, &claim_1));
+
+        Ok(Self::pairing_check(env, proof, vk_x))
+    }
+
+    /// Checks `e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1`.
+    ///
+    /// `-A` is negated by [`curve::neg_g1`] on the encoded point: the SDK's `Neg` goes through
+    /// its generic big-integer arithmetic, which pulls about a kilobyte of code and the
+    /// `core::fmt` panic machinery into the wasm.
+    fn pairing_check(env: &Env, proof: Groth16Proof, vk_x: G1Affine) -> bool {
+        let vk = &Self::VERIFICATION_KEY;
+        let neg_a = curve::neg_g1(&proof.a.to_array());
+        let g1_points = vec![
+            env,
+            G1Affine::from_array(env, &neg_a),
+            G1Affine::from_array(env, &vk.alpha),
+            vk_x,
+            proof.c,
+        ];
+        let g2_points = vec![
+            env,
+            proof.b,
+            G2Affine::from_array(env, &vk.beta),
+            G2Affine::from_array(env, &vk.gamma),
+            G2Affine::from_array(env, &vk.delta),
+        ];
 
-        Ok(bn.pairing_check(g1_points, g2_points))
+        env.crypto().bn254().pairing_check(g1_points, g2_points)
     }
 }
 
@@ -106,43 +294,43 @@ impl RiscZeroVerifierInterface for RiscZeroGroth16Verifier {
     fn verify_integrity(env: Env, receipt: Receipt) -> Result<(), VerifierError> {
         let seal = Self::Proof::try_from(receipt.seal)?;
 
-        if seal.selector != Self::SELECTOR {
+        if Self::CHECK_SELECTOR && seal.selector != Self::SELECTOR {
             return Err(VerifierError::InvalidSelector);
         }
 
-        let (claim_0, claim_1) = split_digest(&env, receipt.claim_digest);
-
-        let control_root_0 = {
-            let mut bytes = [0u8; 32];
-            bytes[16..32].copy_from_slice(&Self::CONTROL_ROOT_0);
-            BytesN::from_array(&env, &bytes)
-        };
-
-        let control_root_1 = {
-            let mut bytes = [0u8; 32];
-            bytes[16..32].copy_from_slice(&Self::CONTROL_ROOT_1);
-            BytesN::from_array(&env, &bytes)
-        };
-
-        // Convert BN254_CONTROL_ID to BytesN<32>
-        let bn254_control_id: BytesN<32> = BytesN::from_array(&env, &Self::BN254_CONTROL_ID);
-
-        // Create public signals as Fr field elements
-        let mut pub_signals = Vec::new(&env);
-        pub_signals.push_back(Fr::from_bytes(control_root_0));
-        pub_signals.push_back(Fr::from_bytes(control_root_1));
-        pub_signals.push_back(Fr::from_bytes(claim_0));
-        pub_signals.push_back(Fr::from_bytes(claim_1));
-        pub_signals.push_back(Fr::from_bytes(bn254_control_id));
+        Self::verify_claim_digest(&env, seal.proof, receipt.claim_digest)
+    }
+}
 
-        // Verify the proof and panic if invalid
-        match Self::verify_proof(env, seal.proof, pub_signals)? {
-            true => Ok(()),
-            false => Err(VerifierError::InvalidProof),
+#[contractimpl]
+impl RiscZeroFullReceiptVerifierInterface for RiscZeroGroth16Verifier {
+    /// Journals longer than [`Self::max_journal_len`] are rejected with
+    /// [`VerifierError::JournalTooLarge`] before hashing, as in [`Self::verify_journal`].
+    fn verify_full_receipt(
+        env: Env,
+        image_id: BytesN<32>,
+        receipt: FullReceipt,
+    ) -> Result<(), VerifierError> {
+        if receipt.journal.len() > Self::MAX_JOURNAL_LEN {
+            return Err(VerifierError::JournalTooLarge);
+        }
+        if !receipt.claims(&env, &image_id) {
+            return Err(VerifierError::JournalMismatch);
         }
+        Self::verify_integrity(env, receipt.into())
     }
 }
 
+/// Runs the on-curve and G2 subgroup checks on the proof points.
+///
+/// The host traps on an invalid point during the pairing, so every verification path runs
+/// these first to fail with a [`VerifierError`] instead.
+fn check_points(proof: &Groth16Proof) -> Result<(), VerifierError> {
+    curve::check_g1(&proof.a.to_array())?;
+    curve::check_g2(&proof.b.to_array())?;
+    curve::check_g1(&proof.c.to_array())
+}
+
 /// Splits a digest into two 32-byte parts after reversing byte order.
 ///
 /// This function reverses the byte order of the input digest and splits it into
@@ -168,10 +356,16 @@ fn split_digest(env: &Env, digest: BytesN<32>) -> (BytesN<32>, BytesN<32>) {
     let mut claim_0 = [0u8; 32];
     let mut claim_1 = [0u8; 32];
 
-    // Copy the upper 16 bytes to claim_0 (zero-pad left)
-    claim_0[16..32].copy_from_slice(&bytes[16..32]);
-    // Copy the lower 16 bytes to claim_1 (zero-pad left)
-    claim_1[16..32].copy_from_slice(&bytes[0..16]);
+    // Copy the upper 16 bytes to claim_0 and the lower 16 bytes to claim_1 (zero-pad left).
+    // Byte by byte rather than `copy_from_slice`, whose length check is not optimized out at
+    // `opt-level = "z"` and brings the panic formatting code into the wasm.
+    let (lower, upper) = bytes.split_at(16);
+    for (dst, src) in claim_0[16..].iter_mut().zip(upper) {
+        *dst = *src;
+    }
+    for (dst, src) in claim_1[16..].iter_mut().zip(lower) {
+        *dst = *src;
+    }
 
     (
         BytesN::from_array(env, &claim_0),
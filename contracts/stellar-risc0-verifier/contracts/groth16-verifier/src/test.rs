@@ -1,9 +1,17 @@
 extern crate std;
 
+use ark_bn254::{Fq, Fq2, G1Affine as ArkG1Affine, G2Affine as ArkG2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use proptest::{collection::vec, prelude::*};
+use risc0_interface::{
+    ExitCode, FullReceipt, Receipt, ReceiptClaim, SystemExitCode, VerifierError, testutils,
+};
+use serde::Deserialize;
 use soroban_sdk::{Bytes, BytesN, Env};
-use std::println;
+use std::{println, string::String, vec::Vec};
 
-use crate::{RiscZeroGroth16Verifier, RiscZeroGroth16VerifierClient};
+use crate::{RiscZeroGroth16Verifier, RiscZeroGroth16VerifierClient, curve, types::Groth16Seal};
 
 /// Test seal data for benchmarks
 const TEST_SEAL: [u8; 260] = [
@@ -55,9 +63,683 @@ fn test_verify_proof() {
     assert_eq!(client.verify(&seal, &image_id, &journal_digest), ());
 }
 
+#[test]
+fn test_verify_unprefixed() {
+    let (env, client) = setup_test();
+    let (_, image_id, journal_digest) = prepare_inputs(&env);
+    let proof = Bytes::from_slice(&env, &TEST_SEAL[4..]);
+
+    assert_eq!(
+        client.verify_unprefixed(&proof, &image_id, &journal_digest),
+        ()
+    );
+
+    let receipt = Receipt {
+        seal: proof,
+        claim_digest: ReceiptClaim::new(&env, image_id, journal_digest).digest(&env),
+    };
+    assert_eq!(client.verify_integrity_unprefixed(&receipt), ());
+}
+
+#[test]
+fn test_selector_check_follows_the_build_option() {
+    let (env, client) = setup_test();
+    let (_, image_id, journal_digest) = prepare_inputs(&env);
+    let mut seal = TEST_SEAL;
+    seal[0] ^= 1;
+
+    let expected = if cfg!(feature = "skip-selector-check") {
+        Ok(Ok(()))
+    } else {
+        Err(Ok(VerifierError::InvalidSelector))
+    };
+    assert_eq!(
+        client.try_verify(&Bytes::from_slice(&env, &seal), &image_id, &journal_digest),
+        expected
+    );
+    assert_eq!(
+        client.checks_selector(),
+        !cfg!(feature = "skip-selector-check")
+    );
+    // `check_seal` always compares the selector, so provers still catch a mismatch.
+    assert_eq!(
+        client.try_check_seal(&Bytes::from_slice(&env, &seal)),
+        Err(Ok(VerifierError::InvalidSelector))
+    );
+}
+
+#[test]
+fn test_verify_journal_hashes_the_journal() {
+    let (env, client) = setup_test();
+    let (seal, image_id, _) = prepare_inputs(&env);
+
+    assert_eq!(
+        client.verify_journal(&seal, &image_id, &Bytes::from_slice(&env, &TEST_JOURNAL)),
+        ()
+    );
+    assert_eq!(
+        client.try_verify_journal(&seal, &image_id, &Bytes::from_slice(&env, &[0u8; 4])),
+        Err(Ok(VerifierError::InvalidProof))
+    );
+}
+
+#[test]
+fn test_verify_journal_rejects_journals_over_the_limit() {
+    let (env, client) = setup_test();
+    let (seal, image_id, _) = prepare_inputs(&env);
+    let max = client.max_journal_len();
+    let journal = |len: u32| Bytes::from_slice(&env, &std::vec![0u8; len as usize]);
+
+    // At the limit the journal is hashed and only the proof fails.
+    assert_eq!(
+        client.try_verify_journal(&seal, &image_id, &journal(max)),
+        Err(Ok(VerifierError::InvalidProof))
+    );
+    assert_eq!(
+        client.try_verify_journal(&seal, &image_id, &journal(max + 1)),
+        Err(Ok(VerifierError::JournalTooLarge))
+    );
+}
+
+#[test]
+fn test_verify_full_receipt_checks_the_journal() {
+    let (env, client) = setup_test();
+    let (seal, image_id, _) = prepare_inputs(&env);
+    let journal = Bytes::from_slice(&env, &TEST_JOURNAL);
+
+    let receipt = FullReceipt::new(&env, seal.clone(), image_id.clone(), journal);
+    assert_eq!(client.verify_full_receipt(&image_id, &receipt), ());
+
+    // The journal or image ID no longer hashes to the claim digest.
+    let mut tampered = receipt.clone();
+    tampered.journal = Bytes::from_slice(&env, &[0u8; 4]);
+    assert_eq!(
+        client.try_verify_full_receipt(&image_id, &tampered),
+        Err(Ok(VerifierError::JournalMismatch))
+    );
+    assert_eq!(
+        client.try_verify_full_receipt(&BytesN::from_array(&env, &[0u8; 32]), &receipt),
+        Err(Ok(VerifierError::JournalMismatch))
+    );
+
+    // A consistent envelope for another journal still needs a proof of it.
+    let other = FullReceipt::new(
+        &env,
+        seal,
+        image_id.clone(),
+        Bytes::from_slice(&env, &[0u8; 4]),
+    );
+    assert_eq!(
+        client.try_verify_full_receipt(&image_id, &other),
+        Err(Ok(VerifierError::InvalidProof))
+    );
+
+    tampered.journal =
+        Bytes::from_slice(&env, &std::vec![0u8; client.max_journal_len() as usize + 1]);
+    assert_eq!(
+        client.try_verify_full_receipt(&image_id, &tampered),
+        Err(Ok(VerifierError::JournalTooLarge))
+    );
+}
+
+#[test]
+fn test_verify_unprefixed_rejects_prefixed_seal() {
+    let (env, client) = setup_test();
+    let (seal, image_id, journal_digest) = prepare_inputs(&env);
+
+    assert_eq!(
+        client.try_verify_unprefixed(&seal, &image_id, &journal_digest),
+        Err(Ok(VerifierError::InvalidSealLength))
+    );
+}
+
+#[test]
+fn test_verify_unprefixed_wrong_journal() {
+    let (env, client) = setup_test();
+    let (_, image_id, _) = prepare_inputs(&env);
+    let proof = Bytes::from_slice(&env, &TEST_SEAL[4..]);
+    let journal_digest = BytesN::from_array(&env, &[0u8; 32]);
+
+    assert_eq!(
+        client.try_verify_unprefixed(&proof, &image_id, &journal_digest),
+        Err(Ok(VerifierError::InvalidProof))
+    );
+}
+
+// ============================================================================
+// RECEIPT FIXTURES
+// ============================================================================
+
+/// Recorded receipt from `fixtures/receipts.json`; see `fixtures/README.md`.
+#[derive(Deserialize)]
+struct ReceiptFixture {
+    name: String,
+    image_id: String,
+    journal: String,
+    /// User exit code the guest halted with; zero when absent.
+    #[serde(default)]
+    exit_code: u32,
+    seal: String,
+}
+
+impl ReceiptFixture {
+    fn all() -> Vec<Self> {
+        serde_json::from_str(include_str!("../fixtures/receipts.json"))
+            .expect("fixtures/receipts.json is valid")
+    }
+
+    fn seal(&self) -> Vec<u8> {
+        hex::decode(&self.seal).expect("seal is hex")
+    }
+
+    fn image_id(&self) -> [u8; 32] {
+        hex::decode(&self.image_id)
+            .expect("image_id is hex")
+            .try_into()
+            .expect("image_id is 32 bytes")
+    }
+
+    fn journal(&self) -> Vec<u8> {
+        hex::decode(&self.journal).expect("journal is hex")
+    }
+
+    /// Digest of the claim a receipt of this fixture's guest run would prove for `image_id`
+    /// and `journal`.
+    fn claim_digest(&self, env: &Env, image_id: &[u8; 32], journal: &[u8]) -> BytesN<32> {
+        let journal_digest = env.crypto().sha256(&Bytes::from_slice(env, journal));
+        ReceiptClaim::new(
+            env,
+            BytesN::from_array(env, image_id),
+            journal_digest.into(),
+        )
+        .with_exit_code(ExitCode::new(env, SystemExitCode::Halted, self.exit_code))
+        .digest(env)
+    }
+}
+
+/// Verifies `seal` through `verify_integrity` against the claim of `fixture`'s run with
+/// `image_id` and `journal` swapped in.
+fn try_verify_parts(
+    env: &Env,
+    client: &RiscZeroGroth16VerifierClient,
+    fixture: &ReceiptFixture,
+    seal: &[u8],
+    image_id: &[u8; 32],
+    journal: &[u8],
+) -> Result<(), VerifierError> {
+    let receipt = Receipt {
+        seal: Bytes::from_slice(env, seal),
+        claim_digest: fixture.claim_digest(env, image_id, journal),
+    };
+    match client.try_verify_integrity(&receipt) {
+        Ok(Ok(())) => Ok(()),
+        Err(Ok(error)) => Err(error),
+        other => panic!("verify_integrity did not return a VerifierError: {other:?}"),
+    }
+}
+
+#[test]
+fn test_receipt_fixtures_verify() {
+    let (env, client) = setup_test();
+
+    for fixture in ReceiptFixture::all() {
+        let (seal, image_id, journal) = (fixture.seal(), fixture.image_id(), fixture.journal());
+        assert_eq!(
+            try_verify_parts(&env, &client, &fixture, &seal, &image_id, &journal),
+            Ok(()),
+            "fixture {}",
+            fixture.name
+        );
+
+        let receipt = Receipt {
+            seal: Bytes::from_slice(&env, &seal[4..]),
+            claim_digest: fixture.claim_digest(&env, &image_id, &journal),
+        };
+        assert_eq!(
+            client.try_verify_integrity_unprefixed(&receipt),
+            Ok(Ok(())),
+            "fixture {}",
+            fixture.name
+        );
+        let seal = Bytes::from_slice(&env, &seal);
+        assert_eq!(
+            client.try_check_seal(&seal),
+            Ok(Ok(())),
+            "fixture {}",
+            fixture.name
+        );
+
+        // The entrypoints that take the journal assume the guest halted with exit code zero.
+        let image_id = BytesN::from_array(&env, &image_id);
+        let journal = Bytes::from_slice(&env, &journal);
+        let full_receipt = FullReceipt {
+            seal: seal.clone(),
+            claim_digest: receipt.claim_digest,
+            journal: journal.clone(),
+        };
+        let (expected, expected_full) = match fixture.exit_code {
+            0 => (Ok(Ok(())), Ok(Ok(()))),
+            _ => (
+                Err(Ok(VerifierError::InvalidProof)),
+                Err(Ok(VerifierError::JournalMismatch)),
+            ),
+        };
+        assert_eq!(
+            client.try_verify_journal(&seal, &image_id, &journal),
+            expected,
+            "fixture {}",
+            fixture.name
+        );
+        assert_eq!(
+            client.try_verify_full_receipt(&image_id, &full_receipt),
+            expected_full,
+            "fixture {}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn test_receipt_fixtures_reject_single_bit_corruptions() {
+    let (env, client) = setup_test();
+
+    for fixture in ReceiptFixture::all() {
+        let (seal, image_id, journal) = (fixture.seal(), fixture.image_id(), fixture.journal());
+
+        if RiscZeroGroth16Verifier::CHECK_SELECTOR {
+            let mut corrupted = seal.clone();
+            corrupted[0] ^= 1;
+            assert_eq!(
+                try_verify_parts(&env, &client, &fixture, &corrupted, &image_id, &journal),
+                Err(VerifierError::InvalidSelector),
+                "fixture {} with selector flipped",
+                fixture.name
+            );
+        }
+
+        // The lowest bit of every proof coordinate moves its point off the curve.
+        let claim_digest = fixture.claim_digest(&env, &image_id, &journal);
+        for index in (A_OFFSET..seal.len()).step_by(32).map(|word| word + 31) {
+            let mut corrupted = seal.clone();
+            corrupted[index] ^= 1;
+            let receipt = Receipt {
+                seal: Bytes::from_slice(&env, &corrupted),
+                claim_digest: claim_digest.clone(),
+            };
+            assert_point_error(&client, &receipt, VerifierError::PointNotOnCurve);
+        }
+
+        let mut corrupted = image_id;
+        corrupted[0] ^= 1;
+        assert_eq!(
+            try_verify_parts(&env, &client, &fixture, &seal, &corrupted, &journal),
+            Err(VerifierError::InvalidProof),
+            "fixture {} with image ID flipped",
+            fixture.name
+        );
+
+        // Journals can be empty, which leaves nothing to flip or truncate.
+        let mut journals = std::vec![[&journal[..], &[0]].concat()];
+        if let Some((last, rest)) = journal.split_last() {
+            journals.push([rest, &[last ^ 1]].concat());
+            journals.push(rest.to_vec());
+        }
+        for journal in &journals {
+            assert_eq!(
+                try_verify_parts(&env, &client, &fixture, &seal, &image_id, journal),
+                Err(VerifierError::InvalidProof),
+                "fixture {} with journal {journal:02x?}",
+                fixture.name
+            );
+        }
+    }
+}
+
+/// The interface vectors with a Groth16 seal; the others are in the mock format.
+fn groth16_vectors() -> impl Iterator<Item = &'static testutils::TestVector> {
+    testutils::VECTORS
+        .iter()
+        .filter(|vector| vector.kind == testutils::SealKind::Groth16)
+}
+
+#[test]
+fn test_interface_vectors_verify() {
+    let (env, client) = setup_test();
+
+    for vector in groth16_vectors() {
+        client.verify_integrity(&vector.receipt(&env));
+        if vector.exit_code == 0 {
+            client.verify(
+                &vector.seal(&env),
+                &vector.image_id(&env),
+                &vector.journal_digest(&env),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_interface_vectors_match_receipt_fixtures() {
+    let fixtures = ReceiptFixture::all();
+
+    for vector in groth16_vectors() {
+        let fixture = fixtures
+            .iter()
+            .find(|fixture| fixture.name == vector.name)
+            .expect("every vector is a recorded fixture");
+        assert_eq!(fixture.seal(), vector.seal, "vector {}", vector.name);
+        assert_eq!(
+            fixture.image_id(),
+            vector.image_id,
+            "vector {}",
+            vector.name
+        );
+        assert_eq!(fixture.journal(), vector.journal, "vector {}", vector.name);
+        assert_eq!(
+            fixture.exit_code, vector.exit_code,
+            "vector {}",
+            vector.name
+        );
+    }
+}
+
+// ============================================================================
+// POINT VALIDATION
+// ============================================================================
+
+/// Offset of `a`, `b` and `c` in the seal.
+const A_OFFSET: usize = 4;
+const B_OFFSET: usize = A_OFFSET + 64;
+const C_OFFSET: usize = B_OFFSET + 128;
+
+/// BN254 base field modulus, big-endian.
+const FQ_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+fn fq_bytes(value: &Fq) -> Vec<u8> {
+    value.into_bigint().to_bytes_be()
+}
+
+/// Encodes a G2 point the way seals do: imaginary part first.
+fn g2_bytes(point: &ArkG2Affine) -> Vec<u8> {
+    let (x, y) = point.xy().expect("finite point");
+    [x.c1, x.c0, y.c1, y.c0].iter().flat_map(fq_bytes).collect()
+}
+
+/// Returns a copy of `TEST_SEAL` with `bytes` written at `offset`.
+fn patched_seal(env: &Env, offset: usize, bytes: &[u8]) -> Bytes {
+    let mut seal = TEST_SEAL;
+    seal[offset..offset + bytes.len()].copy_from_slice(bytes);
+    Bytes::from_slice(env, &seal)
+}
+
+fn assert_verify_error(
+    env: &Env,
+    client: &RiscZeroGroth16VerifierClient,
+    seal: &Bytes,
+    expected: VerifierError,
+) {
+    let image_id = BytesN::from_array(env, &TEST_IMAGE_ID);
+    let journal_digest: BytesN<32> = env
+        .crypto()
+        .sha256(&Bytes::from_slice(env, &TEST_JOURNAL))
+        .into();
+    assert_eq!(
+        client.try_verify(seal, &image_id, &journal_digest),
+        Err(Ok(expected))
+    );
+    assert_eq!(client.try_check_seal(seal), Err(Ok(expected)));
+}
+
+/// Asserts that `verify_integrity`, its unprefixed variant and `check_seal` all reject
+/// `receipt`, whose seal has an invalid point, with `expected` rather than a host trap.
+fn assert_point_error(
+    client: &RiscZeroGroth16VerifierClient,
+    receipt: &Receipt,
+    expected: VerifierError,
+) {
+    assert_eq!(client.try_verify_integrity(receipt), Err(Ok(expected)));
+    let unprefixed = Receipt {
+        seal: receipt.seal.slice(4..),
+        claim_digest: receipt.claim_digest.clone(),
+    };
+    assert_eq!(
+        client.try_verify_integrity_unprefixed(&unprefixed),
+        Err(Ok(expected))
+    );
+    assert_eq!(client.try_check_seal(&receipt.seal), Err(Ok(expected)));
+}
+
+/// [`assert_point_error`] for `seal` and the claim behind `TEST_SEAL`, also through `verify`.
+fn assert_test_seal_point_error(
+    env: &Env,
+    client: &RiscZeroGroth16VerifierClient,
+    seal: &Bytes,
+    expected: VerifierError,
+) {
+    let (_, image_id, journal_digest) = prepare_inputs(env);
+    assert_eq!(
+        client.try_verify(seal, &image_id, &journal_digest),
+        Err(Ok(expected))
+    );
+    let receipt = Receipt {
+        seal: seal.clone(),
+        claim_digest: ReceiptClaim::new(env, image_id, journal_digest).digest(env),
+    };
+    assert_point_error(client, &receipt, expected);
+}
+
+#[test]
+fn test_check_seal_accepts_valid_seal() {
+    let (env, client) = setup_test();
+    let (seal, _, _) = prepare_inputs(&env);
+
+    assert_eq!(client.check_seal(&seal), ());
+}
+
+#[test]
+fn test_wrong_seal_length() {
+    let (env, client) = setup_test();
+
+    for len in [0, 4, TEST_SEAL.len() - 1] {
+        let seal = Bytes::from_slice(&env, &TEST_SEAL[..len]);
+        assert_verify_error(&env, &client, &seal, VerifierError::InvalidSealLength);
+    }
+
+    let mut seal = Bytes::from_slice(&env, &TEST_SEAL);
+    seal.push_back(0);
+    assert_verify_error(&env, &client, &seal, VerifierError::InvalidSealLength);
+}
+
+#[test]
+fn test_g1_point_not_on_curve() {
+    let (env, client) = setup_test();
+
+    // Flipping the low bit of a's y coordinate keeps it in range but off the curve.
+    let mut y = [0u8; 32];
+    y.copy_from_slice(&TEST_SEAL[A_OFFSET + 32..A_OFFSET + 64]);
+    y[31] ^= 1;
+    let seal = patched_seal(&env, A_OFFSET + 32, &y);
+    assert_test_seal_point_error(&env, &client, &seal, VerifierError::PointNotOnCurve);
+
+    // The G1 generator (1, 2) with its coordinates swapped.
+    let generator = ArkG1Affine::generator();
+    let mut swapped = fq_bytes(&generator.y);
+    swapped.extend(fq_bytes(&generator.x));
+    let seal = patched_seal(&env, C_OFFSET, &swapped);
+    assert_test_seal_point_error(&env, &client, &seal, VerifierError::PointNotOnCurve);
+}
+
+#[test]
+fn test_coordinate_outside_field() {
+    let (env, client) = setup_test();
+
+    for offset in [A_OFFSET, B_OFFSET + 32, C_OFFSET + 32] {
+        let seal = patched_seal(&env, offset, &FQ_MODULUS);
+        assert_test_seal_point_error(&env, &client, &seal, VerifierError::PointNotOnCurve);
+    }
+}
+
+#[test]
+fn test_g2_point_with_swapped_components_not_on_curve() {
+    let (env, client) = setup_test();
+
+    // Encoding the real part first is the most common G2 encoding bug.
+    let mut b = [0u8; 128];
+    b.copy_from_slice(&TEST_SEAL[B_OFFSET..B_OFFSET + 128]);
+    let (x, y) = b.split_at_mut(64);
+    let (x_1, x_0) = x.split_at_mut(32);
+    x_1.swap_with_slice(x_0);
+    let (y_1, y_0) = y.split_at_mut(32);
+    y_1.swap_with_slice(y_0);
+
+    let seal = patched_seal(&env, B_OFFSET, &b);
+    assert_test_seal_point_error(&env, &client, &seal, VerifierError::PointNotOnCurve);
+}
+
+#[test]
+fn test_g2_point_not_in_subgroup() {
+    let (env, client) = setup_test();
+
+    let point = (1u64..)
+        .filter_map(|i| {
+            ArkG2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(i), Fq::from(1u64)), true)
+        })
+        .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+        .expect("the twist has points outside G2");
+
+    let seal = patched_seal(&env, B_OFFSET, &g2_bytes(&point));
+    assert_test_seal_point_error(&env, &client, &seal, VerifierError::PointNotInSubgroup);
+}
+
+#[test]
+fn test_g2_subgroup_points_pass_check_seal() {
+    let (env, client) = setup_test();
+    let generator = ArkG2Affine::generator();
+
+    for scalar in [1u64, 2, 7, 0xdead_beef] {
+        let point: ArkG2Affine = (generator * ark_bn254::Fr::from(scalar)).into();
+        let seal = patched_seal(&env, B_OFFSET, &g2_bytes(&point));
+        assert_eq!(client.check_seal(&seal), ());
+    }
+}
+
+#[test]
+fn test_neg_g1_matches_arkworks() {
+    let generator = ArkG1Affine::generator();
+
+    for scalar in [1u64, 2, 7, 0xdead_beef] {
+        let point: ArkG1Affine = (generator * ark_bn254::Fr::from(scalar)).into();
+        let (x, y) = point.xy().expect("finite point");
+        let (neg_x, neg_y) = (-point).xy().expect("finite point");
+        let bytes: [u8; 64] = [x, y]
+            .iter()
+            .flat_map(fq_bytes)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let neg: Vec<u8> = [neg_x, neg_y].iter().flat_map(fq_bytes).collect();
+        assert_eq!(curve::neg_g1(&bytes).to_vec(), neg);
+    }
+    assert_eq!(curve::neg_g1(&[0; 64]), [0; 64]);
+}
+
+// ============================================================================
+// PROPERTY TESTS - Seal Parsing
+// ============================================================================
+
+/// Each case registers a fresh contract, so keep the case count modest.
+const PROPTEST_CASES: u32 = 64;
+
+/// Runs `verify`, `verify_integrity` and `check_seal` on `seal` and returns the error, which
+/// must be the same structured `VerifierError` on every path rather than a host trap or panic.
+fn expect_structured_error(seal: &[u8]) -> VerifierError {
+    let (env, client) = setup_test();
+    let (_, image_id, journal_digest) = prepare_inputs(&env);
+    let seal = Bytes::from_slice(&env, seal);
+
+    let Err(Ok(error)) = client.try_verify(&seal, &image_id, &journal_digest) else {
+        panic!("verify did not return a VerifierError");
+    };
+
+    let receipt = Receipt {
+        seal: seal.clone(),
+        claim_digest: ReceiptClaim::new(&env, image_id, journal_digest).digest(&env),
+    };
+    assert_eq!(client.try_verify_integrity(&receipt), Err(Ok(error)));
+
+    // `check_seal` shares the decoding path and only adds the point checks.
+    assert_eq!(client.try_check_seal(&seal), Err(Ok(error)));
+
+    error
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(PROPTEST_CASES))]
+
+    #[test]
+    fn prop_seal_parsing_never_panics(bytes in vec(any::<u8>(), 0..600)) {
+        let env = Env::default();
+        let result = Groth16Seal::try_from(Bytes::from_slice(&env, &bytes));
+
+        if bytes.len() != TEST_SEAL.len() {
+            prop_assert_eq!(result.err(), Some(VerifierError::InvalidSealLength));
+        }
+    }
+
+    #[test]
+    fn prop_wrong_length_is_rejected(len in (0usize..600).prop_filter("not seal size", |len| *len != 260)) {
+        let seal: Vec<u8> = TEST_SEAL.iter().copied().cycle().take(len).collect();
+        prop_assert_eq!(expect_structured_error(&seal), VerifierError::InvalidSealLength);
+    }
+
+    #[test]
+    fn prop_random_proof_is_rejected(proof in vec(any::<u8>(), 256)) {
+        let mut seal = TEST_SEAL[..4].to_vec();
+        seal.extend(proof);
+
+        // Random coordinates land on the curve with negligible probability.
+        prop_assert_eq!(expect_structured_error(&seal), VerifierError::PointNotOnCurve);
+    }
+
+    #[test]
+    fn prop_mutated_seal_is_rejected(index in 0usize..260, mask in 1u8..=255) {
+        prop_assume!(index >= 4 || RiscZeroGroth16Verifier::CHECK_SELECTOR);
+        let mut seal = TEST_SEAL;
+        seal[index] ^= mask;
+
+        if index < 4 {
+            prop_assert_eq!(expect_structured_error(&seal), VerifierError::InvalidSelector);
+        } else {
+            prop_assert_eq!(expect_structured_error(&seal), VerifierError::PointNotOnCurve);
+        }
+    }
+}
+
 // ============================================================================
 // BENCHMARKS - Gas Consumption Tracking
 // ============================================================================
+//
+// Each benchmark fails once its cost exceeds a ceiling set about 5% above the measured cost,
+// so a regression in claim digesting or proof decoding shows up as a failing test instead of
+// higher fees. Lower the ceilings when an optimization lands. Native tests do not meter the
+// contract's own wasm, so these track host function costs (hashing, BN254 operations,
+// object conversions).
+
+/// `verify()`: measured 31.74M CPU instructions, 212K bytes.
+const VERIFY_MAX_CPU: u64 = 33_300_000;
+const VERIFY_MAX_MEM: u64 = 223_000;
+
+/// `verify_integrity()`: measured 31.69M CPU instructions, 208K bytes.
+const VERIFY_INTEGRITY_MAX_CPU: u64 = 33_300_000;
+const VERIFY_INTEGRITY_MAX_MEM: u64 = 219_000;
+
+/// `ReceiptClaim::digest()` including contract registration: measured 97K CPU instructions,
+/// 14K bytes.
+const CLAIM_DIGEST_MAX_CPU: u64 = 102_000;
+const CLAIM_DIGEST_MAX_MEM: u64 = 15_000;
 
 /// Prints full budget in a formatted way
 fn print_budget(env: &Env, label: &str) {
@@ -68,6 +750,23 @@ fn print_budget(env: &Env, label: &str) {
     println!("==========================================\n");
 }
 
+/// Prints the budget and asserts it stays within the given ceilings.
+fn assert_budget(env: &Env, label: &str, max_cpu: u64, max_mem: u64) {
+    print_budget(env, label);
+
+    let budget = env.cost_estimate().budget();
+    let cpu = budget.cpu_instruction_cost();
+    let mem = budget.memory_bytes_cost();
+    assert!(
+        cpu <= max_cpu,
+        "{label} used {cpu} CPU instructions, ceiling is {max_cpu}"
+    );
+    assert!(
+        mem <= max_mem,
+        "{label} used {mem} memory bytes, ceiling is {max_mem}"
+    );
+}
+
 #[test]
 fn bench_verify() {
     let (env, client) = setup_test();
@@ -76,18 +775,20 @@ fn bench_verify() {
     // Run verification
     assert_eq!(client.verify(&seal, &image_id, &journal_digest), ());
 
-    // Print results
-    print_budget(&env, "verify()");
+    assert_budget(&env, "verify()", VERIFY_MAX_CPU, VERIFY_MAX_MEM);
 }
 
+/// The claim-only linear combination (constant public inputs folded into `IC[0]` at build
+/// time, see `verify_claim`) brought this from ~35.2M to ~31.7M CPU instructions: three
+/// `Bn254G1Mul`/`Bn254G1Add` pairs fewer per verification.
 #[test]
 fn bench_verify_integrity() {
     let (env, client) = setup_test();
     let (seal, image_id, journal_digest) = prepare_inputs(&env);
 
     // Build receipt manually
-    let claim = risc0_interface::ReceiptClaim::new(&env, image_id, journal_digest);
-    let receipt = risc0_interface::Receipt {
+    let claim = ReceiptClaim::new(&env, image_id, journal_digest);
+    let receipt = Receipt {
         seal,
         claim_digest: claim.digest(&env),
     };
@@ -95,8 +796,12 @@ fn bench_verify_integrity() {
     // Run verification
     assert_eq!(client.verify_integrity(&receipt), ());
 
-    // Print results
-    print_budget(&env, "verify_integrity()");
+    assert_budget(
+        &env,
+        "verify_integrity()",
+        VERIFY_INTEGRITY_MAX_CPU,
+        VERIFY_INTEGRITY_MAX_MEM,
+    );
 }
 
 #[test]
@@ -109,9 +814,57 @@ fn bench_receipt_claim_digest() {
         .into();
 
     // Build claim and compute digest
-    let claim = risc0_interface::ReceiptClaim::new(&env, image_id, journal_digest);
+    let claim = ReceiptClaim::new(&env, image_id, journal_digest);
     let _digest = claim.digest(&env);
 
-    // Print results
-    print_budget(&env, "ReceiptClaim::digest()");
+    assert_budget(
+        &env,
+        "ReceiptClaim::digest()",
+        CLAIM_DIGEST_MAX_CPU,
+        CLAIM_DIGEST_MAX_MEM,
+    );
+}
+
+// ============================================================================
+// WASM SIZE - Deployment Footprint Tracking
+// ============================================================================
+
+/// Release `groth16_verifier.wasm` for the vendored key: measured 32,808 bytes (down from
+/// 39,862 before the verification key points were decoded lazily and the SDK's G1 negation
+/// and panic formatting paths were dropped).
+const WASM_MAX_BYTES: u64 = 34_500;
+
+/// Builds the contract for `wasm32v1-none` with the release profile and fails once the wasm
+/// outgrows [`WASM_MAX_BYTES`]. Ignored by default since it needs the wasm target installed;
+/// CI runs it with `--ignored`.
+#[test]
+#[ignore = "builds the contract for wasm32v1-none"]
+fn wasm_size_within_ceiling() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    // A target directory of its own, so the nested build does not wait on the one running
+    // this test.
+    let target_dir = std::format!("{manifest_dir}/../../target/wasm-size");
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+
+    let status = std::process::Command::new(cargo)
+        .args([
+            "build",
+            "--release",
+            "--target",
+            "wasm32v1-none",
+            "--manifest-path",
+        ])
+        .arg(std::format!("{manifest_dir}/Cargo.toml"))
+        .args(["--target-dir", &target_dir])
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "wasm build failed");
+
+    let wasm = std::format!("{target_dir}/wasm32v1-none/release/groth16_verifier.wasm");
+    let size = std::fs::metadata(&wasm).expect("wasm was built").len();
+    println!("groth16_verifier.wasm: {size} bytes");
+    assert!(
+        size <= WASM_MAX_BYTES,
+        "groth16_verifier.wasm is {size} bytes, ceiling is {WASM_MAX_BYTES}"
+    );
 }
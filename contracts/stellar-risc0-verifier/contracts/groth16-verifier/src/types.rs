@@ -1,7 +1,5 @@
-use core::array;
-
 use soroban_sdk::{
-    Bytes, BytesN, Env, contracttype,
+    Bytes, BytesN, contracttype,
     crypto::bn254::{Bn254G1Affine as G1Affine, Bn254G2Affine as G2Affine},
 };
 
@@ -14,47 +12,34 @@ const G2_SIZE: usize = FIELD_ELEMENT_SIZE * 4; // x_0, x_1, y_0, y_1
 const PROOF_SIZE: usize = G1_SIZE + G2_SIZE + G1_SIZE; // a, b, c
 const SEAL_SIZE: usize = SELECTOR_SIZE + PROOF_SIZE;
 
-/// Groth16 verification key for BN254 curve.
+/// Number of IC points in the embedded verification key (public inputs + 1).
+///
+/// Generated at build time, so keys loaded through `RISC0_VK_JSON` may use a
+/// different number of public inputs than the vendored RISC Zero key.
+pub const IC_LEN: usize = include!(concat!(env!("OUT_DIR"), "/ic_len.rs"));
+
+/// Number of IC points of the RISC Zero receipt circuit (five public inputs).
+///
+/// Generated from the build script's value, which also decides whether the
+/// constant public inputs get folded into `IC_CONSTANT`.
+pub const RISC0_IC_LEN: usize = include!(concat!(env!("OUT_DIR"), "/risc0_ic_len.rs"));
+
+/// Groth16 verification key for BN254 curve, generated at build time.
 ///
 /// Contains the public parameters needed to verify a Groth16 proof:
 /// - `alpha`, `beta`, `gamma`, `delta`: Fixed elliptic curve points from the trusted setup
 /// - `ic`: Array of G1 points used for computing the public input component
 ///
-/// This structure uses arkworks types internally and is not serializable for contract storage.
-#[derive(Clone)]
-pub struct VerificationKey {
-    pub alpha: G1Affine,
-    pub beta: G2Affine,
-    pub gamma: G2Affine,
-    pub delta: G2Affine,
-    pub ic: [G1Affine; 6],
-}
-
-/// Byte-oriented version of the verification key generated at build time.
-///
 /// Soroban's BN254 affine types are not `const` constructible, so we emit the
-/// key as raw byte arrays in `build.rs` and reconstruct the affine points at
-/// runtime inside the contract via [`verification_key`]. This keeps the key
-/// embeddable with `include!` while still avoiding any serialization support on
-/// the `VerificationKey` itself.
+/// key as raw byte arrays in `build.rs` and the contract turns each point into an
+/// affine type only where it uses it. Building the whole key up front kept every
+/// point's conversion in the wasm even on paths that need two of them.
 pub struct VerificationKeyBytes {
     pub alpha: [u8; G1_SIZE],
     pub beta: [u8; G2_SIZE],
     pub gamma: [u8; G2_SIZE],
     pub delta: [u8; G2_SIZE],
-    pub ic: [[u8; G1_SIZE]; 6],
-}
-
-impl VerificationKeyBytes {
-    pub fn verification_key(&self, env: &Env) -> VerificationKey {
-        VerificationKey {
-            alpha: G1Affine::from_array(env, &self.alpha),
-            beta: G2Affine::from_array(env, &self.beta),
-            gamma: G2Affine::from_array(env, &self.gamma),
-            delta: G2Affine::from_array(env, &self.delta),
-            ic: array::from_fn(|i| G1Affine::from_array(env, &self.ic[i])),
-        }
-    }
+    pub ic: [[u8; G1_SIZE]; IC_LEN],
 }
 
 /// Groth16 proof with XDR serialization support.
@@ -82,7 +67,7 @@ impl TryFrom<Bytes> for Groth16Seal {
 
     fn try_from(value: Bytes) -> Result<Self, Self::Error> {
         if value.len() != SEAL_SIZE as u32 {
-            return Err(VerifierError::MalformedSeal);
+            return Err(VerifierError::InvalidSealLength);
         }
 
         let selector = value
@@ -96,33 +81,34 @@ impl TryFrom<Bytes> for Groth16Seal {
     }
 }
 
+/// Decodes the proof points, checking only the proof length.
+///
+/// The points are validated by the contract right before the pairing, which the host would
+/// otherwise trap in.
 impl TryFrom<Bytes> for Groth16Proof {
     type Error = VerifierError;
 
     fn try_from(value: Bytes) -> Result<Self, Self::Error> {
         if value.len() != PROOF_SIZE as u32 {
-            return Err(VerifierError::MalformedSeal);
+            return Err(VerifierError::InvalidSealLength);
         }
 
-        let a = G1Affine::from_bytes(
-            value
-                .slice(0..G1_SIZE as u32)
-                .try_into()
-                .map_err(|_| VerifierError::MalformedSeal)?,
-        );
-        let b = G2Affine::from_bytes(
-            value
-                .slice(G1_SIZE as u32..G1_SIZE as u32 + G2_SIZE as u32)
-                .try_into()
-                .map_err(|_| VerifierError::MalformedSeal)?,
-        );
-        let c = G1Affine::from_bytes(
-            value
-                .slice(G1_SIZE as u32 + G2_SIZE as u32..)
-                .try_into()
-                .map_err(|_| VerifierError::MalformedSeal)?,
-        );
-
-        Ok(Self { a, b, c })
+        let mut a = [0u8; G1_SIZE];
+        let mut b = [0u8; G2_SIZE];
+        let mut c = [0u8; G1_SIZE];
+        value.slice(0..G1_SIZE as u32).copy_into_slice(&mut a);
+        value
+            .slice(G1_SIZE as u32..G1_SIZE as u32 + G2_SIZE as u32)
+            .copy_into_slice(&mut b);
+        value
+            .slice(G1_SIZE as u32 + G2_SIZE as u32..)
+            .copy_into_slice(&mut c);
+
+        let env = value.env();
+        Ok(Self {
+            a: G1Affine::from_array(env, &a),
+            b: G2Affine::from_array(env, &b),
+            c: G1Affine::from_array(env, &c),
+        })
     }
 }
@@ -0,0 +1,58 @@
+//! # Streaming Journal Digests
+//!
+//! The claim of a receipt commits to the SHA-256 digest of its journal. Soroban's
+//! `env.crypto().sha256` hashes a single [`Bytes`] value, so contracts that receive a large
+//! journal in pieces would have to append every piece into one buffer first.
+//! [`JournalHasher`] hashes the pieces as they come instead.
+
+use sha2::{Digest, Sha256};
+use soroban_sdk::{Bytes, BytesN, Env};
+
+/// Bytes copied out of a chunk per hashing step.
+const BLOCK_LEN: u32 = 256;
+
+/// Incremental SHA-256 over journal chunks.
+///
+/// Feeding the chunks of a journal in order produces the same digest as
+/// `env.crypto().sha256` over the whole journal. Hashing runs in the contract rather than on
+/// the host, so it costs more instructions per byte; prefer the host function when the
+/// journal is already a single [`Bytes`] value.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut hasher = JournalHasher::new();
+/// for chunk in chunks.iter() {
+///     hasher.update(&chunk);
+/// }
+/// let claim = ReceiptClaim::new(&env, image_id, hasher.finalize(&env));
+/// ```
+#[derive(Clone, Default)]
+pub struct JournalHasher {
+    state: Sha256,
+}
+
+impl JournalHasher {
+    /// Starts a digest of an empty journal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the next chunk of the journal.
+    pub fn update(&mut self, chunk: &Bytes) {
+        let mut block = [0u8; BLOCK_LEN as usize];
+        let mut start = 0;
+        while start < chunk.len() {
+            let end = start.saturating_add(BLOCK_LEN).min(chunk.len());
+            let block = &mut block[..(end - start) as usize];
+            chunk.slice(start..end).copy_into_slice(block);
+            self.state.update(block);
+            start = end;
+        }
+    }
+
+    /// Returns the SHA-256 digest of all the chunks appended so far.
+    pub fn finalize(self, env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &self.state.finalize().into())
+    }
+}
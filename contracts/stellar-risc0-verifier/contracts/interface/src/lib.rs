@@ -5,20 +5,33 @@
 //! ## Core Components
 //!
 //! - [`Receipt`]: Contains a seal (cryptographic proof) and a claim digest
+//! - [`FullReceipt`]: A receipt carrying its journal bytes
 //! - [`RiscZeroVerifierInterface`]: Verifier contract interface
 //! - [`RiscZeroVerifierRouterInterface`]: Router contract interface
+//! - [`RiscZeroFullReceiptVerifierInterface`]: Optional interface for [`FullReceipt`]s
+//! - [`JournalHasher`]: Journal digests computed over chunks of a large journal
+//! - `testutils` (feature): Known-good receipts every verifier and caller can test against
 
 #![no_std]
 
 use soroban_sdk::{Address, Bytes, BytesN, Env, contractclient};
 
 // Re-export types at crate root for convenience
+pub use digest::JournalHasher;
 pub use types::{
-    ExitCode, Output, Receipt, ReceiptClaim, SystemExitCode, VerifierEntry, VerifierError,
+    ExitCode, FullReceipt, Output, Receipt, ReceiptClaim, SystemExitCode, VerifierEntry,
+    VerifierError,
 };
 
+mod digest;
 mod types;
 
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+
+#[cfg(test)]
+mod test;
+
 /// Verifier interface for RISC Zero zkVM receipts of execution.
 ///
 /// This trait defines the standard interface that all RISC Zero verifier contracts must
@@ -60,7 +73,10 @@ pub trait RiscZeroVerifierInterface {
     ///
     /// Returns an error if any of the following occur:
     /// - [`VerifierError::MalformedSeal`] - The seal is malformed or cannot be decoded
+    /// - [`VerifierError::InvalidSealLength`] - The seal does not have the expected length
     /// - [`VerifierError::InvalidSelector`] - The selector in the seal doesn't match this verifier
+    /// - [`VerifierError::PointNotOnCurve`] - A proof point is not a valid curve point
+    /// - [`VerifierError::PointNotInSubgroup`] - A proof point is outside the prime-order subgroup
     /// - [`VerifierError::MalformedPublicInputs`] - The public inputs are invalid
     /// - [`VerifierError::InvalidProof`] - The cryptographic verification fails
     ///
@@ -123,7 +139,10 @@ pub trait RiscZeroVerifierInterface {
     ///
     /// Returns an error if any of the following occur:
     /// - [`VerifierError::MalformedSeal`] - The seal is malformed or cannot be decoded
+    /// - [`VerifierError::InvalidSealLength`] - The seal does not have the expected length
     /// - [`VerifierError::InvalidSelector`] - The selector in the seal doesn't match this verifier
+    /// - [`VerifierError::PointNotOnCurve`] - A proof point is not a valid curve point
+    /// - [`VerifierError::PointNotInSubgroup`] - A proof point is outside the prime-order subgroup
     /// - [`VerifierError::MalformedPublicInputs`] - The public inputs are invalid
     /// - [`VerifierError::InvalidProof`] - The cryptographic verification fails or the claim digest doesn't match
     ///
@@ -145,6 +164,28 @@ pub trait RiscZeroVerifierInterface {
     fn verify_integrity(env: Env, receipt: Receipt) -> Result<(), VerifierError>;
 }
 
+/// Optional interface for verifiers that take the journal bytes with the receipt.
+///
+/// Contracts implement it next to [`RiscZeroVerifierInterface`] when they check journals
+/// themselves, so callers can hand over a [`FullReceipt`] as they received it.
+#[contractclient(name = "RiscZeroFullReceiptVerifierClient")]
+pub trait RiscZeroFullReceiptVerifierInterface {
+    /// Verifies a receipt of `image_id` together with its journal bytes.
+    ///
+    /// Implementations check [`FullReceipt::claims`] for `image_id` before verifying the
+    /// seal against the claim digest, as `verify_integrity` does.
+    ///
+    /// # Errors
+    ///
+    /// - [`VerifierError::JournalMismatch`] - The journal does not hash to the claim digest
+    /// - Any error of `verify_integrity` for the receipt
+    fn verify_full_receipt(
+        env: Env,
+        image_id: BytesN<32>,
+        receipt: FullReceipt,
+    ) -> Result<(), VerifierError>;
+}
+
 /// Router interface for a `RiscZeroVerifierRouter` contract.
 ///
 /// This interface exposes verification entrypoints alongside read-only routing helpers.
@@ -153,7 +194,8 @@ pub trait RiscZeroVerifierRouterInterface {
     /// Verifies a receipt from its components using the selector embedded in the seal.
     ///
     /// The router uses the first 4 bytes of the seal as a selector to dispatch to the
-    /// appropriate verifier.
+    /// appropriate verifier. Routers may first try an 8-byte versioned selector (a 4-byte
+    /// family followed by a 4-byte version) and fall back to the 4-byte one.
     fn verify(
         env: Env,
         seal: Bytes,
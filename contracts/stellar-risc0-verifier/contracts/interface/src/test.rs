@@ -0,0 +1,129 @@
+use soroban_sdk::{Bytes, BytesN, Env};
+
+use crate::{
+    FullReceipt, JournalHasher, Receipt,
+    testutils::{GROTH16_SELECTOR, MOCK_SELECTOR, SealKind, VECTORS, mock_receipt},
+};
+
+fn journal(env: &Env, len: u32) -> Bytes {
+    let mut journal = Bytes::new(env);
+    for i in 0..len {
+        journal.push_back((i % 251) as u8);
+    }
+    journal
+}
+
+#[test]
+fn test_journal_hasher_matches_host_sha256() {
+    let env = Env::default();
+
+    for len in [0, 1, 255, 256, 257, 1_000] {
+        let journal = journal(&env, len);
+        let mut hasher = JournalHasher::new();
+        hasher.update(&journal);
+        assert_eq!(
+            hasher.finalize(&env),
+            env.crypto().sha256(&journal).to_bytes(),
+            "journal of {len} bytes"
+        );
+    }
+}
+
+#[test]
+fn test_journal_hasher_is_independent_of_chunking() {
+    let env = Env::default();
+    let journal = journal(&env, 1_000);
+    let expected = env.crypto().sha256(&journal).to_bytes();
+
+    for chunk_len in [1, 7, 64, 300, 999] {
+        let mut hasher = JournalHasher::new();
+        let mut start = 0;
+        while start < journal.len() {
+            let end = (start + chunk_len).min(journal.len());
+            hasher.update(&journal.slice(start..end));
+            start = end;
+        }
+        hasher.update(&Bytes::new(&env));
+        assert_eq!(
+            hasher.finalize(&env),
+            expected,
+            "chunks of {chunk_len} bytes"
+        );
+    }
+}
+
+#[test]
+fn test_vectors_match_receipt_claim_digests() {
+    let env = Env::default();
+
+    for vector in VECTORS {
+        assert_eq!(
+            vector.claim(&env).digest(&env),
+            vector.claim_digest(&env),
+            "vector {}",
+            vector.name
+        );
+        match vector.kind {
+            SealKind::Groth16 => {
+                assert_eq!(vector.seal[..4], GROTH16_SELECTOR, "vector {}", vector.name)
+            }
+            SealKind::Mock => assert_eq!(
+                vector.seal,
+                [&MOCK_SELECTOR[..], &vector.claim_digest].concat(),
+                "vector {}",
+                vector.name
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_mock_receipt_prefixes_the_claim_digest() {
+    let env = Env::default();
+    let selector = BytesN::from_array(&env, &MOCK_SELECTOR);
+
+    // `mock_receipt` builds the claim of a guest that exited with code zero.
+    for vector in VECTORS.iter().filter(|vector| vector.exit_code == 0) {
+        let receipt = mock_receipt(
+            &env,
+            &selector,
+            &vector.image_id(&env),
+            &vector.journal(&env),
+        );
+        assert_eq!(
+            receipt.claim_digest,
+            vector.claim_digest(&env),
+            "vector {}",
+            vector.name
+        );
+        assert_eq!(
+            receipt.seal,
+            Bytes::from_slice(&env, &[&MOCK_SELECTOR[..], &vector.claim_digest].concat()),
+            "vector {}",
+            vector.name
+        );
+    }
+}
+
+#[test]
+fn test_full_receipts_tie_the_journal_to_the_claim() {
+    let env = Env::default();
+    let vector = VECTORS[0];
+    let image_id = vector.image_id(&env);
+    let seal = Bytes::from_slice(&env, vector.seal);
+
+    let full = FullReceipt::new(&env, seal.clone(), image_id.clone(), vector.journal(&env));
+    assert_eq!(full.claim_digest, vector.claim_digest(&env));
+    assert!(full.claims(&env, &image_id));
+    assert!(!full.claims(&env, &BytesN::from_array(&env, &[0; 32])));
+
+    let tampered = FullReceipt {
+        journal: journal(&env, 4),
+        ..full.clone()
+    };
+    assert!(!tampered.claims(&env, &image_id));
+
+    let receipt: Receipt = full.into();
+    assert_eq!(receipt.seal, seal);
+    assert_eq!(receipt.claim_digest, vector.claim_digest(&env));
+}
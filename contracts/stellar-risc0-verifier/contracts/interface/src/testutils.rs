@@ -0,0 +1,217 @@
+//! # Test Vectors
+//!
+//! Known-good receipts shared by the verifier implementations and the contracts that call
+//! them, so every crate checks digest compatibility against the same data. Enabled by the
+//! `testutils` feature.
+//!
+//! Groth16 vectors come from real proofs (see `groth16-verifier/fixtures`), and there is only
+//! one so far: a four-byte journal, halted with exit code zero. Mock vectors use the mock
+//! verifier's `selector || claim_digest` seal format and cover the claims no recorded proof
+//! has yet: other image IDs, an empty journal and a non-zero exit code. They check claim
+//! digests, not Groth16 verification; Groth16 vectors for those claims will be added with the
+//! receipts listed as missing in the fixtures README. All claim digests were computed
+//! independently of [`ReceiptClaim::digest`] and must match it.
+
+use soroban_sdk::{Bytes, BytesN, Env};
+
+use crate::{ExitCode, Receipt, ReceiptClaim, SystemExitCode};
+
+/// Selector of the Groth16 verifier the Groth16 vectors' seals were produced for.
+pub const GROTH16_SELECTOR: [u8; 4] = [0x73, 0xc4, 0x57, 0xba];
+
+/// Dev-mode selector of the mock vectors' seals, which the mock verifier accepts whatever
+/// selector it was deployed with.
+pub const MOCK_SELECTOR: [u8; 4] = [0xFF; 4];
+
+/// Proof system a [`TestVector`]'s seal is for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SealKind {
+    /// A Groth16 seal for [`GROTH16_SELECTOR`].
+    Groth16,
+    /// A mock seal, [`MOCK_SELECTOR`] followed by the claim digest.
+    Mock,
+}
+
+/// A receipt for an unconditional execution that halted, with no committed input.
+#[derive(Clone, Copy, Debug)]
+pub struct TestVector {
+    /// Short identifier for assertion messages.
+    pub name: &'static str,
+    /// Verifier the seal is for.
+    pub kind: SealKind,
+    /// Image ID of the guest program.
+    pub image_id: [u8; 32],
+    /// Journal bytes, not their digest.
+    pub journal: &'static [u8],
+    /// User exit code the guest halted with.
+    pub exit_code: u32,
+    /// Digest of the [`ReceiptClaim`] for `image_id`, the journal's digest and the exit code.
+    pub claim_digest: [u8; 32],
+    /// Seal, selector included.
+    pub seal: &'static [u8],
+}
+
+/// Every test vector.
+pub const VECTORS: &[TestVector] = &[
+    TestVector {
+        name: "journal_4_bytes",
+        kind: SealKind::Groth16,
+        image_id: [
+            0xa7, 0x7e, 0x54, 0x91, 0x0c, 0x79, 0x2d, 0xdc, 0x3f, 0x14, 0x87, 0x8f, 0x3f, 0x13,
+            0x60, 0xaf, 0x96, 0x61, 0x24, 0x08, 0xd6, 0x90, 0x74, 0xe8, 0x73, 0x89, 0xa2, 0x15,
+            0xf5, 0x75, 0x95, 0xb9,
+        ],
+        journal: &[0x01, 0x00, 0x00, 0x78],
+        exit_code: 0,
+        claim_digest: [
+            0x76, 0xa6, 0xf7, 0x6f, 0x54, 0xd3, 0x37, 0x2b, 0xb6, 0x8e, 0x39, 0x09, 0x02, 0x09,
+            0xcc, 0x9c, 0xc5, 0x1a, 0x0b, 0x79, 0x0b, 0x79, 0x14, 0xf4, 0x93, 0x9a, 0xcb, 0xd7,
+            0x20, 0xa6, 0xd3, 0x6f,
+        ],
+        seal: &[
+            0x73, 0xc4, 0x57, 0xba, 0x00, 0xed, 0x80, 0xeb, 0xea, 0x52, 0xa2, 0xd7, 0x6c, 0xdb,
+            0x53, 0xfd, 0x33, 0x97, 0x68, 0xbe, 0x10, 0x1b, 0xbf, 0x73, 0x34, 0x14, 0xe5, 0x16,
+            0xa8, 0x9b, 0x62, 0xd6, 0x46, 0x6d, 0x8f, 0xa8, 0x27, 0xa3, 0xd9, 0xd7, 0x75, 0x9b,
+            0x77, 0xbd, 0xac, 0x2e, 0xda, 0x08, 0xa4, 0x24, 0x8a, 0xa3, 0x2f, 0x42, 0xb9, 0x33,
+            0x84, 0xba, 0x78, 0x44, 0xdd, 0xad, 0x10, 0x5b, 0x53, 0x9a, 0xec, 0xf0, 0x10, 0x87,
+            0x93, 0xc7, 0xcd, 0x93, 0x47, 0xd4, 0xb3, 0x4a, 0xe3, 0xc5, 0xe3, 0x94, 0x4f, 0xff,
+            0x50, 0x74, 0x3f, 0x3c, 0xaa, 0xae, 0x49, 0x21, 0x9b, 0xbe, 0xb2, 0xd3, 0x28, 0x68,
+            0x56, 0x85, 0x0a, 0x05, 0x60, 0x0f, 0x8f, 0xc3, 0x87, 0xad, 0xcd, 0x0d, 0xb9, 0x57,
+            0x67, 0x8a, 0x00, 0x73, 0x73, 0x70, 0xa1, 0x13, 0x81, 0xfe, 0x92, 0xd8, 0xc6, 0x99,
+            0x32, 0x8b, 0xc8, 0x68, 0xb5, 0x0f, 0x26, 0xef, 0x6c, 0x70, 0xfc, 0x43, 0xb0, 0xdd,
+            0x83, 0x65, 0xa7, 0x2c, 0x0b, 0xc9, 0x87, 0xd8, 0x12, 0x80, 0x21, 0x92, 0x27, 0x1c,
+            0x24, 0x8c, 0xec, 0xf9, 0x0d, 0x46, 0x3a, 0x2f, 0x6f, 0x93, 0x18, 0x1a, 0xf8, 0x97,
+            0x80, 0x1e, 0x05, 0x94, 0x29, 0xac, 0xfc, 0x21, 0xf5, 0x22, 0xa5, 0x3c, 0x61, 0x85,
+            0x80, 0x6f, 0x69, 0xf1, 0x17, 0xb8, 0x6d, 0xbf, 0x56, 0x28, 0xbb, 0xc6, 0x49, 0x75,
+            0x02, 0x6d, 0x1c, 0x84, 0x95, 0x06, 0xf3, 0x07, 0x79, 0x64, 0xd0, 0x7c, 0x1a, 0xcc,
+            0xd5, 0x89, 0x3d, 0x21, 0x53, 0x5d, 0x28, 0xa4, 0xde, 0x56, 0x23, 0xee, 0x63, 0xb1,
+            0x10, 0xa8, 0xf1, 0xd2, 0x08, 0x39, 0xf8, 0x8f, 0x4f, 0x69, 0x56, 0xf8, 0x38, 0x9d,
+            0x29, 0x5a, 0xc0, 0x4e, 0x70, 0x66, 0x87, 0xd9, 0xcc, 0x38, 0x16, 0x39, 0xa8, 0xe6,
+            0x39, 0x21, 0x1e, 0x9b, 0x46, 0x80, 0x31, 0x1b,
+        ],
+    },
+    TestVector {
+        name: "mock_empty_journal",
+        kind: SealKind::Mock,
+        image_id: [
+            0x8d, 0xe9, 0xd0, 0x18, 0xe5, 0xc6, 0x8d, 0x18, 0x5f, 0x8d, 0xbe, 0x6f, 0x16, 0x44,
+            0xd3, 0x23, 0x09, 0x55, 0x7b, 0x95, 0xb5, 0x09, 0x44, 0xb8, 0xaf, 0xcc, 0x5f, 0x13,
+            0x73, 0x76, 0x9c, 0x66,
+        ],
+        journal: &[],
+        exit_code: 0,
+        claim_digest: [
+            0x01, 0xcd, 0x59, 0x39, 0xdf, 0xb7, 0xfc, 0x3c, 0xaf, 0xd0, 0x0e, 0xa7, 0xb0, 0x73,
+            0x5f, 0x74, 0x30, 0x08, 0x72, 0xbf, 0x71, 0x4a, 0x52, 0x71, 0x0a, 0xff, 0x81, 0xe5,
+            0x5d, 0xbb, 0x75, 0x26,
+        ],
+        seal: &[
+            0xff, 0xff, 0xff, 0xff, 0x01, 0xcd, 0x59, 0x39, 0xdf, 0xb7, 0xfc, 0x3c, 0xaf, 0xd0,
+            0x0e, 0xa7, 0xb0, 0x73, 0x5f, 0x74, 0x30, 0x08, 0x72, 0xbf, 0x71, 0x4a, 0x52, 0x71,
+            0x0a, 0xff, 0x81, 0xe5, 0x5d, 0xbb, 0x75, 0x26,
+        ],
+    },
+    TestVector {
+        name: "mock_journal_33_bytes",
+        kind: SealKind::Mock,
+        image_id: [
+            0x88, 0xa0, 0x0f, 0x19, 0x5a, 0x5e, 0xd2, 0x6e, 0xd8, 0xfe, 0x58, 0xa8, 0xb2, 0xe8,
+            0x87, 0x0b, 0x15, 0x0b, 0x93, 0x99, 0x65, 0xbe, 0xab, 0xd9, 0x6b, 0xd6, 0x41, 0xf8,
+            0x79, 0xb3, 0xd7, 0xa5,
+        ],
+        journal: &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+            0x1c, 0x1d, 0x1e, 0x1f, 0x20,
+        ],
+        exit_code: 0,
+        claim_digest: [
+            0x12, 0xa5, 0x71, 0x75, 0xaf, 0xbc, 0x0a, 0x3e, 0x07, 0x94, 0x82, 0xba, 0xf2, 0xd4,
+            0xad, 0x45, 0x22, 0xd4, 0x79, 0x6d, 0x9e, 0x4f, 0xd2, 0x77, 0x57, 0xbd, 0x9d, 0x00,
+            0xe7, 0x9c, 0x87, 0xc2,
+        ],
+        seal: &[
+            0xff, 0xff, 0xff, 0xff, 0x12, 0xa5, 0x71, 0x75, 0xaf, 0xbc, 0x0a, 0x3e, 0x07, 0x94,
+            0x82, 0xba, 0xf2, 0xd4, 0xad, 0x45, 0x22, 0xd4, 0x79, 0x6d, 0x9e, 0x4f, 0xd2, 0x77,
+            0x57, 0xbd, 0x9d, 0x00, 0xe7, 0x9c, 0x87, 0xc2,
+        ],
+    },
+    TestVector {
+        name: "mock_exit_code_1",
+        kind: SealKind::Mock,
+        image_id: [
+            0x84, 0xea, 0xcd, 0x2a, 0xa8, 0xf5, 0x5e, 0x48, 0x8e, 0xa2, 0x95, 0xad, 0x71, 0x71,
+            0x03, 0x5f, 0x8a, 0x5d, 0x08, 0x77, 0x58, 0xce, 0xad, 0xb6, 0x3d, 0x5c, 0x10, 0x51,
+            0x5f, 0xc4, 0x87, 0x23,
+        ],
+        journal: &[0x2a],
+        exit_code: 1,
+        claim_digest: [
+            0x41, 0xcb, 0xb2, 0x6a, 0x0a, 0xe1, 0x8a, 0xc2, 0x9f, 0x24, 0x4f, 0x2f, 0xa3, 0x79,
+            0x2f, 0xd8, 0xe9, 0x3d, 0x50, 0xf5, 0x09, 0x20, 0xcc, 0xfd, 0x14, 0xd0, 0xc3, 0xc0,
+            0x6d, 0x70, 0xcd, 0x5b,
+        ],
+        seal: &[
+            0xff, 0xff, 0xff, 0xff, 0x41, 0xcb, 0xb2, 0x6a, 0x0a, 0xe1, 0x8a, 0xc2, 0x9f, 0x24,
+            0x4f, 0x2f, 0xa3, 0x79, 0x2f, 0xd8, 0xe9, 0x3d, 0x50, 0xf5, 0x09, 0x20, 0xcc, 0xfd,
+            0x14, 0xd0, 0xc3, 0xc0, 0x6d, 0x70, 0xcd, 0x5b,
+        ],
+    },
+];
+
+impl TestVector {
+    /// The image ID as a contract value.
+    pub fn image_id(&self, env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &self.image_id)
+    }
+
+    /// The journal as a contract value.
+    pub fn journal(&self, env: &Env) -> Bytes {
+        Bytes::from_slice(env, self.journal)
+    }
+
+    /// SHA-256 digest of the journal, as passed to `verify`.
+    pub fn journal_digest(&self, env: &Env) -> BytesN<32> {
+        env.crypto().sha256(&self.journal(env)).into()
+    }
+
+    /// The claim the seal proves.
+    pub fn claim(&self, env: &Env) -> ReceiptClaim {
+        let exit_code = ExitCode::new(env, SystemExitCode::Halted, self.exit_code);
+        ReceiptClaim::new(env, self.image_id(env), self.journal_digest(env))
+            .with_exit_code(exit_code)
+    }
+
+    /// The recorded claim digest as a contract value.
+    pub fn claim_digest(&self, env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &self.claim_digest)
+    }
+
+    /// The seal as a contract value.
+    pub fn seal(&self, env: &Env) -> Bytes {
+        Bytes::from_slice(env, self.seal)
+    }
+
+    /// The receipt, as passed to `verify_integrity`.
+    pub fn receipt(&self, env: &Env) -> Receipt {
+        Receipt {
+            seal: self.seal(env),
+            claim_digest: self.claim_digest(env),
+        }
+    }
+}
+
+/// Builds the receipt the mock verifier accepts for `image_id` and `journal`: the seal is
+/// `selector` followed by the claim digest.
+pub fn mock_receipt(
+    env: &Env,
+    selector: &BytesN<4>,
+    image_id: &BytesN<32>,
+    journal: &Bytes,
+) -> Receipt {
+    let claim_digest =
+        ReceiptClaim::new(env, image_id.clone(), env.crypto().sha256(journal).into()).digest(env);
+    let mut seal = Bytes::from_array(env, &selector.to_array());
+    seal.append(&claim_digest.clone().into());
+    Receipt { seal, claim_digest }
+}
@@ -7,6 +7,7 @@
 //! ## Type Overview
 //!
 //! - [`Receipt`]: Complete proof package with seal and claim
+//! - [`FullReceipt`]: A [`Receipt`] that carries its journal bytes
 //! - [`ReceiptClaim`]: Detailed execution claim including state and exit codes
 //!
 //! ## Verification Flow
@@ -39,6 +40,19 @@ pub enum VerifierError {
     SelectorInUse = 6,
     /// The selector is not registered.
     SelectorUnknown = 7,
+    /// The seal does not have the byte length expected by the verifier.
+    InvalidSealLength = 8,
+    /// A proof point has a coordinate outside the base field or does not satisfy the curve
+    /// equation, which usually points at an encoding bug on the prover side.
+    PointNotOnCurve = 9,
+    /// A proof point is on the curve but outside the prime-order subgroup.
+    PointNotInSubgroup = 10,
+    /// The journal is longer than the verifier hashes on-chain.
+    JournalTooLarge = 11,
+    /// The journal does not hash to the receipt's claim for the image ID.
+    JournalMismatch = 12,
+    /// The router's verifier cache has no free slot for another pinned selector.
+    VerifierCacheFull = 13,
 }
 
 /// A receipt attesting to a claim using the RISC Zero proof system.
@@ -83,6 +97,55 @@ pub struct Receipt {
     pub claim_digest: BytesN<32>,
 }
 
+/// A [`Receipt`] together with the journal bytes its claim commits to.
+///
+/// Verifiers that check journals and applications that parse them can pass this one
+/// envelope instead of the journal out-of-band. Only the standard claim of
+/// [`ReceiptClaim::new`] can be tied back to the journal, see [`FullReceipt::claims`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FullReceipt {
+    /// The zero-knowledge proof (SNARK) as raw bytes.
+    pub seal: Bytes,
+    /// SHA-256 digest of the [`ReceiptClaim`] struct.
+    pub claim_digest: BytesN<32>,
+    /// Journal bytes committed by the guest program.
+    pub journal: Bytes,
+}
+
+impl FullReceipt {
+    /// Builds the envelope for a successful, unconditional execution of `image_id` that
+    /// committed `journal`.
+    pub fn new(env: &Env, seal: Bytes, image_id: BytesN<32>, journal: Bytes) -> Self {
+        let claim_digest = Self::standard_claim_digest(env, image_id, &journal);
+        Self {
+            seal,
+            claim_digest,
+            journal,
+        }
+    }
+
+    /// Returns whether the claim digest is the standard claim of `image_id` over the
+    /// journal, i.e. whether verifying the seal also proves the journal bytes.
+    pub fn claims(&self, env: &Env, image_id: &BytesN<32>) -> bool {
+        Self::standard_claim_digest(env, image_id.clone(), &self.journal) == self.claim_digest
+    }
+
+    fn standard_claim_digest(env: &Env, image_id: BytesN<32>, journal: &Bytes) -> BytesN<32> {
+        let journal_digest = env.crypto().sha256(journal).into();
+        ReceiptClaim::new(env, image_id, journal_digest).digest(env)
+    }
+}
+
+impl From<FullReceipt> for Receipt {
+    fn from(receipt: FullReceipt) -> Self {
+        Receipt {
+            seal: receipt.seal,
+            claim_digest: receipt.claim_digest,
+        }
+    }
+}
+
 /// A claim about the execution of a RISC Zero guest program.
 ///
 /// This structure contains all the details about a program execution that the seal
@@ -153,6 +216,21 @@ pub struct ExitCode {
     user: BytesN<8>,
 }
 
+impl ExitCode {
+    /// Creates an exit code from its system part and the guest's user exit code.
+    ///
+    /// Only the low byte of `user` takes part in the claim digest, matching RISC Zero's
+    /// `(user << 24)` encoding.
+    pub fn new(env: &Env, system: SystemExitCode, user: u32) -> Self {
+        let mut bytes = [0u8; 8];
+        bytes[..4].copy_from_slice(&user.to_be_bytes());
+        Self {
+            system,
+            user: BytesN::from_array(env, &bytes),
+        }
+    }
+}
+
 /// System-level exit codes for RISC Zero execution.
 ///
 /// These codes indicate different execution termination modes.
@@ -294,6 +372,22 @@ impl ReceiptClaim {
         }
     }
 
+    /// Replaces the exit code, e.g. for a guest that halted with a non-zero user code.
+    ///
+    /// The post-state digest is left at the halted constant, so claims with
+    /// [`SystemExitCode::Paused`] or [`SystemExitCode::SystemSplit`] only describe real
+    /// executions when the caller also knows they end in that state.
+    pub fn with_exit_code(mut self, exit_code: ExitCode) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+
+    /// Replaces the input digest, which is the zero digest for current zkVM receipts.
+    pub fn with_input(mut self, input: BytesN<32>) -> Self {
+        self.input = input;
+        self
+    }
+
     /// Computes the SHA-256 digest of this [`ReceiptClaim`].
     ///
     /// This digest becomes the `claim_digest` field in a [`Receipt`] and is what the
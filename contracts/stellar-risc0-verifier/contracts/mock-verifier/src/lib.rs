@@ -1,8 +1,19 @@
 #![no_std]
 
-use soroban_sdk::{Bytes, BytesN, Env, contract, contractimpl, contracttype};
+// Scripted outcomes track their position outside the host, where no rollback reaches it.
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
 
-use risc0_interface::{Receipt, ReceiptClaim, RiscZeroVerifierInterface, VerifierError};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Vec, contract, contractevent, contractimpl, contracttype,
+};
+use stellar_access::ownable::{Ownable, set_owner};
+use stellar_macros::only_owner;
+
+use risc0_interface::{
+    ExitCode, FullReceipt, Receipt, ReceiptClaim, RiscZeroFullReceiptVerifierInterface,
+    RiscZeroVerifierInterface, SystemExitCode, VerifierError,
+};
 
 #[cfg(test)]
 mod test;
@@ -11,9 +22,227 @@ const DAY_IN_LEDGERS: u32 = 17_280;
 const VERIFIER_EXTEND_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
 const VERIFIER_TTL_THRESHOLD: u32 = VERIFIER_EXTEND_AMOUNT - DAY_IN_LEDGERS;
 
+/// Selector that `risc0_ethereum_contracts::encode_seal` writes for `risc0_zkvm` dev-mode
+/// (fake) receipts, followed by the claim digest.
+pub const DEV_MODE_SELECTOR: [u8; 4] = [0xFF; 4];
+
+/// Burn rounds that bring a mock verification close to the ~31.7M CPU instructions of a
+/// Groth16 verification (about 7.7K instructions per round, hashing and byte conversions).
+pub const GROTH16_BURN_ITERATIONS: u32 = 4_100;
+
+/// Number of most recent verified calls kept by [`RiscZeroMockVerifier::calls`].
+pub const CALL_HISTORY_LEN: u32 = 8;
+
 #[contracttype]
 enum DataKey {
     Selector,
+    /// Number of verified calls, in temporary storage.
+    CallCount,
+    /// The last [`CALL_HISTORY_LEN`] verified calls, in temporary storage.
+    Calls,
+    /// Whether `verify` only accepts allowlisted image IDs.
+    StrictImageIds,
+    /// Marker for an allowlisted image ID.
+    AllowedImageId(BytesN<32>),
+    /// Number of SHA-256 rounds burned per verification.
+    BurnIterations,
+}
+
+/// Key of the scripted outcomes set by `set_script` and the ID their position is kept under, in
+/// temporary storage.
+#[cfg(any(test, feature = "testutils"))]
+const SCRIPT: soroban_sdk::Symbol = soroban_sdk::symbol_short!("script");
+
+/// Outcome of one `verify` or `verify_integrity` call, scripted ahead of time with
+/// `set_script`. Only built with the `testutils` feature.
+#[cfg(any(test, feature = "testutils"))]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScriptedOutcome {
+    /// Accept the call whatever the seal, recording it like a verified call.
+    Ok,
+    /// Fail the call with the given [`VerifierError`] code, e.g.
+    /// `VerifierError::InvalidProof as u32`.
+    Err(u32),
+    /// Panic, as a verifier that traps would.
+    Panic,
+}
+
+/// Emitted when the owner changes the mock's selector.
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelectorUpdated {
+    /// Selector before the update.
+    #[topic]
+    pub previous: BytesN<4>,
+    /// Selector after the update.
+    pub selector: BytesN<4>,
+}
+
+/// A verified call recorded by the mock for test assertions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyCall {
+    /// Contract that invoked the verification, or `None` when it was invoked directly.
+    ///
+    /// Soroban does not tell a contract who invoked it, so the caller is read from the host's
+    /// call diagnostics, which only native `testutils` builds can see. Wasm builds always
+    /// record `None`.
+    pub caller: Option<Address>,
+    /// Claim digest of the verified receipt.
+    pub claim_digest: BytesN<32>,
+    /// Length of the seal in bytes.
+    pub seal_len: u32,
+}
+
+fn record_call(env: &Env, receipt: &Receipt) {
+    let storage = env.storage().temporary();
+
+    let count: u32 = storage.get(&DataKey::CallCount).unwrap_or(0);
+    storage.set(&DataKey::CallCount, &count.saturating_add(1));
+
+    let mut calls: Vec<VerifyCall> = storage
+        .get(&DataKey::Calls)
+        .unwrap_or_else(|| Vec::new(env));
+    if calls.len() >= CALL_HISTORY_LEN {
+        calls.pop_front();
+    }
+    calls.push_back(VerifyCall {
+        caller: invoker(env),
+        claim_digest: receipt.claim_digest.clone(),
+        seal_len: receipt.seal.len(),
+    });
+    storage.set(&DataKey::Calls, &calls);
+}
+
+/// Returns the contract that invoked this one, from the `fn_call` diagnostic event the host
+/// emits for the current call.
+#[cfg(all(any(test, feature = "testutils"), not(target_family = "wasm")))]
+fn invoker(env: &Env) -> Option<Address> {
+    use soroban_sdk::{
+        TryFromVal,
+        xdr::{ContractEventBody, ScAddress, ScVal},
+    };
+
+    let ScAddress::Contract(this) = ScAddress::from(env.current_contract_address()) else {
+        return None;
+    };
+    let events = env.host().get_diagnostic_events().ok()?;
+    // Contracts cannot be re-entered, so the latest call into this contract is the current one.
+    let call = events.0.into_iter().rev().find(|event| {
+        let ContractEventBody::V0(body) = &event.event.body;
+        matches!(
+            body.topics.as_slice(),
+            [ScVal::Symbol(name), ScVal::Bytes(callee), _]
+                if name.as_slice() == b"fn_call" && callee.as_slice() == this.0.as_slice()
+        )
+    })?;
+    call.event
+        .contract_id
+        .map(|id| Address::try_from_val(env, &ScAddress::Contract(id)).unwrap())
+}
+
+#[cfg(not(all(any(test, feature = "testutils"), not(target_family = "wasm"))))]
+fn invoker(_env: &Env) -> Option<Address> {
+    None
+}
+
+fn write_selector(env: &Env, selector: BytesN<4>) {
+    let key = DataKey::Selector;
+    let selector: Bytes = selector.into();
+    env.storage().persistent().set(&key, &selector);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, VERIFIER_TTL_THRESHOLD, VERIFIER_EXTEND_AMOUNT);
+}
+
+fn is_image_id_allowed(env: &Env, image_id: &BytesN<32>) -> bool {
+    let strict: bool = env
+        .storage()
+        .persistent()
+        .get(&DataKey::StrictImageIds)
+        .unwrap_or(false);
+    !strict
+        || env
+            .storage()
+            .persistent()
+            .has(&DataKey::AllowedImageId(image_id.clone()))
+}
+
+#[cfg(any(test, feature = "testutils"))]
+std::thread_local! {
+    /// Last script ID handed out on this thread.
+    static SCRIPT_IDS: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+    /// Script positions by script ID.
+    static SCRIPT_POSITIONS: core::cell::RefCell<std::collections::BTreeMap<u64, u32>> =
+        const { core::cell::RefCell::new(std::collections::BTreeMap::new()) };
+}
+
+/// Returns the position in script `id` and advances it by one.
+///
+/// A call that fails rolls back this contract's storage writes, so a position kept in storage
+/// would stay on a failing step forever. Only the script's ID is kept in storage, which scopes
+/// the script to this contract in this `Env`; the position itself is kept outside the host,
+/// where no rollback reaches it.
+#[cfg(any(test, feature = "testutils"))]
+fn next_script_position(id: u64) -> u32 {
+    SCRIPT_POSITIONS.with_borrow_mut(|positions| {
+        let position = positions.entry(id).or_insert(0);
+        *position += 1;
+        *position - 1
+    })
+}
+
+/// Hands out a script ID that no other script on this thread uses, in any `Env`.
+#[cfg(any(test, feature = "testutils"))]
+fn new_script_id() -> u64 {
+    SCRIPT_IDS.with(|ids| {
+        ids.set(ids.get() + 1);
+        ids.get()
+    })
+}
+
+#[cfg(any(test, feature = "testutils"))]
+fn forget_script_position(id: u64) {
+    SCRIPT_POSITIONS.with_borrow_mut(|positions| positions.remove(&id));
+}
+
+/// Takes the next scripted outcome, or `None` once the script is used up.
+#[cfg(any(test, feature = "testutils"))]
+fn next_outcome(env: &Env) -> Option<ScriptedOutcome> {
+    let (id, script): (u64, Vec<ScriptedOutcome>) = env.storage().temporary().get(&SCRIPT)?;
+    script.get(next_script_position(id))
+}
+
+/// Plays a scripted outcome for `receipt`, after burning the configured rounds.
+#[cfg(any(test, feature = "testutils"))]
+fn play(env: &Env, outcome: ScriptedOutcome, receipt: &Receipt) -> Result<(), VerifierError> {
+    burn(
+        env,
+        &receipt.claim_digest,
+        RiscZeroMockVerifier::burn_iterations(env.clone()),
+    );
+    match outcome {
+        ScriptedOutcome::Ok => {
+            record_call(env, receipt);
+            Ok(())
+        }
+        ScriptedOutcome::Err(code) => {
+            env.panic_with_error(soroban_sdk::Error::from_contract_error(code))
+        }
+        ScriptedOutcome::Panic => panic!("scripted verifier panic"),
+    }
+}
+
+/// Chains `iterations` SHA-256 hashes over `seed` to spend budget like a real verifier would.
+fn burn(env: &Env, seed: &BytesN<32>, iterations: u32) {
+    let mut digest = seed.clone();
+    for _ in 0..iterations {
+        digest = env
+            .crypto()
+            .sha256(&Bytes::from_array(env, &digest.to_array()))
+            .into();
+    }
 }
 
 fn read_selector(env: &Env) -> Result<Bytes, VerifierError> {
@@ -39,6 +268,10 @@ fn read_selector(env: &Env) -> Result<Bytes, VerifierError> {
 /// is meant for local development, integration tests, and end-to-end testing flows where real
 /// proofs are not yet available or are intentionally bypassed.
 ///
+/// Seals are `selector || claim_digest`, where the selector is either the one configured at
+/// construction or [`DEV_MODE_SELECTOR`], so `risc0_zkvm` dev-mode receipts encoded with
+/// `risc0_ethereum_contracts::encode_seal` verify without reformatting.
+///
 /// Do not deploy or rely on this contract in production environments. It provides no security
 /// guarantees and will accept any receipt that matches the mock format.
 #[contract]
@@ -46,11 +279,21 @@ pub struct RiscZeroMockVerifier;
 
 #[contractimpl]
 impl RiscZeroMockVerifier {
-    pub fn __constructor(env: Env, selector: BytesN<4>) {
-        let selector: Bytes = selector.into();
-        env.storage()
-            .persistent()
-            .set(&DataKey::Selector, &selector);
+    /// Initializes the mock with its selector and the owner allowed to change it.
+    pub fn __constructor(env: Env, selector: BytesN<4>, owner: Address) {
+        set_owner(&env, &owner);
+        write_selector(&env, selector);
+    }
+
+    /// Re-points the mock at a new selector, e.g. after a router reshuffles its selectors.
+    ///
+    /// Seals carrying the previous selector are rejected from then on.
+    #[only_owner]
+    pub fn set_selector(env: Env, selector: BytesN<4>) -> Result<(), VerifierError> {
+        let previous = Self::selector(env.clone())?;
+        write_selector(&env, selector.clone());
+        SelectorUpdated { previous, selector }.publish(&env);
+        Ok(())
     }
 
     /// Returns the configured selector as `BytesN<4>`.
@@ -61,6 +304,89 @@ impl RiscZeroMockVerifier {
         BytesN::try_from(&selector).map_err(|_| VerifierError::InvalidSelector)
     }
 
+    /// Turns strict image-ID mode on or off.
+    ///
+    /// In strict mode `verify` rejects image IDs that are not allowlisted with
+    /// [`VerifierError::InvalidProof`], the error a real verifier returns for a proof of a
+    /// different program. `verify_integrity` only sees the claim digest and is unaffected.
+    #[only_owner]
+    pub fn set_strict_image_ids(env: Env, strict: bool) {
+        let key = DataKey::StrictImageIds;
+        env.storage().persistent().set(&key, &strict);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, VERIFIER_TTL_THRESHOLD, VERIFIER_EXTEND_AMOUNT);
+    }
+
+    /// Adds or removes `image_id` from the strict-mode allowlist.
+    #[only_owner]
+    pub fn set_image_id_allowed(env: Env, image_id: BytesN<32>, allowed: bool) {
+        let key = DataKey::AllowedImageId(image_id);
+        if allowed {
+            env.storage().persistent().set(&key, &());
+            env.storage().persistent().extend_ttl(
+                &key,
+                VERIFIER_TTL_THRESHOLD,
+                VERIFIER_EXTEND_AMOUNT,
+            );
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns whether `verify` currently accepts `image_id`.
+    pub fn image_id_allowed(env: Env, image_id: BytesN<32>) -> bool {
+        is_image_id_allowed(&env, &image_id)
+    }
+
+    /// Sets how many SHA-256 rounds each verification burns before checking the seal.
+    ///
+    /// With [`GROTH16_BURN_ITERATIONS`] the mock costs roughly as many CPU instructions as the
+    /// Groth16 verifier, so budget and fee estimates taken with the mock in the loop stay
+    /// representative. Zero, the default, disables burning.
+    #[only_owner]
+    pub fn set_burn_iterations(env: Env, iterations: u32) {
+        let key = DataKey::BurnIterations;
+        env.storage().persistent().set(&key, &iterations);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, VERIFIER_TTL_THRESHOLD, VERIFIER_EXTEND_AMOUNT);
+    }
+
+    /// Returns the number of SHA-256 rounds burned per verification.
+    pub fn burn_iterations(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BurnIterations)
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of verified calls since deployment or the last [`Self::reset_calls`].
+    ///
+    /// Failed verifications return an error, which rolls back this contract's storage writes,
+    /// so only calls that verified are counted.
+    pub fn call_count(env: Env) -> u32 {
+        env.storage()
+            .temporary()
+            .get(&DataKey::CallCount)
+            .unwrap_or(0)
+    }
+
+    /// Returns the last [`CALL_HISTORY_LEN`] verified calls, oldest first.
+    pub fn calls(env: Env) -> Vec<VerifyCall> {
+        env.storage()
+            .temporary()
+            .get(&DataKey::Calls)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Clears the call counter and history.
+    pub fn reset_calls(env: Env) {
+        let storage = env.storage().temporary();
+        storage.remove(&DataKey::CallCount);
+        storage.remove(&DataKey::Calls);
+    }
+
     /// Build a mock receipt for the given image ID and journal digest.
     ///
     /// The seal format matches the Ethereum mock verifier: `selector || claim_digest`.
@@ -74,6 +400,32 @@ impl RiscZeroMockVerifier {
         Self::mock_prove_claim(env, claim_digest)
     }
 
+    /// Build a mock receipt for a claim with a custom exit code or input digest.
+    ///
+    /// Unset options keep the [`ReceiptClaim::new`] defaults: [`SystemExitCode::Halted`], user
+    /// exit code zero and the zero input digest. The resulting receipt verifies through
+    /// `verify_integrity`; `verify` always assumes the default claim.
+    pub fn mock_prove_custom(
+        env: Env,
+        image_id: BytesN<32>,
+        journal_digest: BytesN<32>,
+        system_exit_code: Option<SystemExitCode>,
+        user_exit_code: Option<u32>,
+        input: Option<BytesN<32>>,
+    ) -> Result<Receipt, VerifierError> {
+        let exit_code = ExitCode::new(
+            &env,
+            system_exit_code.unwrap_or(SystemExitCode::Halted),
+            user_exit_code.unwrap_or(0),
+        );
+        let mut claim = ReceiptClaim::new(&env, image_id, journal_digest).with_exit_code(exit_code);
+        if let Some(input) = input {
+            claim = claim.with_input(input);
+        }
+        let claim_digest = claim.digest(&env);
+        Self::mock_prove_claim(env, claim_digest)
+    }
+
     /// Build a mock receipt for a precomputed claim digest.
     ///
     /// The seal format matches the Ethereum mock verifier: `selector || claim_digest`.
@@ -87,49 +439,129 @@ impl RiscZeroMockVerifier {
     }
 }
 
+/// Scripting, for contract tests only (the `testutils` feature).
+#[cfg(any(test, feature = "testutils"))]
+#[contractimpl]
+impl RiscZeroMockVerifier {
+    /// Scripts the outcomes of the next `verify` and `verify_integrity` calls, one per call.
+    ///
+    /// Each call takes the next outcome in order, whatever its arguments, so application
+    /// contracts can be tested against verifiers that fail and then recover, e.g. `[Ok, Ok,
+    /// Err(InvalidProof), Panic]`. Once the script is used up, calls verify as usual again.
+    /// Setting a new script, possibly empty, starts over from its first outcome.
+    #[only_owner]
+    pub fn set_script(env: Env, outcomes: Vec<ScriptedOutcome>) {
+        let storage = env.storage().temporary();
+        if let Some((id, _)) = storage.get::<_, (u64, Vec<ScriptedOutcome>)>(&SCRIPT) {
+            forget_script_position(id);
+        }
+        storage.set(&SCRIPT, &(new_script_id(), outcomes));
+    }
+
+    /// Returns the scripted outcomes set by [`Self::set_script`], including those already
+    /// played.
+    pub fn script(env: Env) -> Vec<ScriptedOutcome> {
+        env.storage()
+            .temporary()
+            .get(&SCRIPT)
+            .map(|(_, outcomes): (u64, Vec<ScriptedOutcome>)| outcomes)
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+}
+
 #[contractimpl]
 impl RiscZeroVerifierInterface for RiscZeroMockVerifier {
     type Proof = ();
 
     /// Verify a mock seal by reconstructing the claim digest from inputs.
     ///
-    /// Returns a structured [`VerifierError`] on selector mismatch or invalid proof.
+    /// Returns a structured [`VerifierError`] on selector mismatch or invalid proof, unless
+    /// `set_script` scripted this call's outcome in a test build.
     fn verify(
         env: Env,
         seal: Bytes,
         image_id: BytesN<32>,
         journal: BytesN<32>,
     ) -> Result<(), VerifierError> {
+        let allowed = is_image_id_allowed(&env, &image_id);
         let claim = ReceiptClaim::new(&env, image_id, journal);
         let receipt = Receipt {
             seal,
             claim_digest: claim.digest(&env),
         };
-        Self::verify_integrity(env, receipt)
+
+        #[cfg(any(test, feature = "testutils"))]
+        if let Some(outcome) = next_outcome(&env) {
+            return play(&env, outcome, &receipt);
+        }
+        if !allowed {
+            return Err(VerifierError::InvalidProof);
+        }
+        verify_receipt(&env, receipt)
     }
 
     fn verify_integrity(env: Env, receipt: risc0_interface::Receipt) -> Result<(), VerifierError> {
-        if receipt.seal.len() < 4 {
-            return Err(VerifierError::MalformedSeal);
+        #[cfg(any(test, feature = "testutils"))]
+        if let Some(outcome) = next_outcome(&env) {
+            return play(&env, outcome, &receipt);
         }
+        verify_receipt(&env, receipt)
+    }
+}
 
-        let expected_selector = read_selector(&env)?;
-        let selector = receipt.seal.slice(0..4);
-
-        if selector != expected_selector {
-            return Err(VerifierError::InvalidSelector);
+#[contractimpl]
+impl RiscZeroFullReceiptVerifierInterface for RiscZeroMockVerifier {
+    /// Checks the journal like a real verifier, then applies strict image-ID mode as `verify`
+    /// does and verifies the receipt through `verify_integrity`, scripted outcomes included.
+    fn verify_full_receipt(
+        env: Env,
+        image_id: BytesN<32>,
+        receipt: FullReceipt,
+    ) -> Result<(), VerifierError> {
+        if !receipt.claims(&env, &image_id) {
+            return Err(VerifierError::JournalMismatch);
         }
-
-        let seal_hash = env.crypto().keccak256(&receipt.seal.slice(4..)).to_bytes();
-        let claim_hash = env
-            .crypto()
-            .keccak256(&receipt.claim_digest.into())
-            .to_bytes();
-
-        if seal_hash != claim_hash {
+        if !is_image_id_allowed(&env, &image_id) {
             return Err(VerifierError::InvalidProof);
         }
+        Self::verify_integrity(env, receipt.into())
+    }
+}
 
-        Ok(())
+/// Checks a `selector || claim_digest` seal against `receipt`'s claim digest.
+fn verify_receipt(env: &Env, receipt: Receipt) -> Result<(), VerifierError> {
+    burn(
+        env,
+        &receipt.claim_digest,
+        RiscZeroMockVerifier::burn_iterations(env.clone()),
+    );
+
+    if receipt.seal.len() < 4 {
+        return Err(VerifierError::MalformedSeal);
     }
+
+    let expected_selector = read_selector(env)?;
+    let selector = receipt.seal.slice(0..4);
+
+    // Dev-mode seals from the risc0 tooling carry a fixed selector but otherwise use the
+    // same `selector || claim_digest` layout, so they are accepted as they are.
+    if selector != expected_selector && selector != Bytes::from_array(env, &DEV_MODE_SELECTOR) {
+        return Err(VerifierError::InvalidSelector);
+    }
+
+    let seal_hash = env.crypto().keccak256(&receipt.seal.slice(4..)).to_bytes();
+    let claim_hash = env
+        .crypto()
+        .keccak256(&receipt.claim_digest.clone().into())
+        .to_bytes();
+
+    if seal_hash != claim_hash {
+        return Err(VerifierError::InvalidProof);
+    }
+
+    record_call(env, &receipt);
+    Ok(())
 }
+
+#[contractimpl(contracttrait)]
+impl Ownable for RiscZeroMockVerifier {}
@@ -1,9 +1,29 @@
 extern crate std;
 
-use soroban_sdk::{Bytes, BytesN, Env};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Event, contract, contractimpl,
+    testutils::{Address as _, Events},
+};
 
-use crate::{RiscZeroMockVerifier, RiscZeroMockVerifierClient};
-use risc0_interface::{Receipt, ReceiptClaim, VerifierError};
+use crate::{
+    CALL_HISTORY_LEN, DEV_MODE_SELECTOR, GROTH16_BURN_ITERATIONS, RiscZeroMockVerifier,
+    RiscZeroMockVerifierClient, ScriptedOutcome, SelectorUpdated, VerifyCall,
+};
+use risc0_interface::{
+    ExitCode, FullReceipt, Receipt, ReceiptClaim, SystemExitCode, VerifierError,
+    testutils::{SealKind, VECTORS, mock_receipt},
+};
+
+/// Application contract stand-in that verifies receipts through the mock.
+#[contract]
+struct Relay;
+
+#[contractimpl]
+impl Relay {
+    pub fn relay(env: Env, verifier: Address, receipt: Receipt) {
+        RiscZeroMockVerifierClient::new(&env, &verifier).verify_integrity(&receipt);
+    }
+}
 
 fn bytes_from<const N: usize>(env: &Env, value: &BytesN<N>) -> Bytes {
     Bytes::from_array(env, &value.to_array())
@@ -12,7 +32,8 @@ fn bytes_from<const N: usize>(env: &Env, value: &BytesN<N>) -> Bytes {
 fn setup() -> (Env, RiscZeroMockVerifierClient<'static>, BytesN<4>) {
     let env = Env::default();
     let selector = BytesN::from_array(&env, &[0x11, 0x22, 0x33, 0x44]);
-    let contract_id = env.register(RiscZeroMockVerifier, (selector.clone(),));
+    let owner = Address::generate(&env);
+    let contract_id = env.register(RiscZeroMockVerifier, (selector.clone(), owner));
     let client = RiscZeroMockVerifierClient::new(&env, &contract_id);
     (env, client, selector)
 }
@@ -43,6 +64,56 @@ fn test_verify_integrity_ok() {
     assert_eq!(client.verify_integrity(&receipt), ());
 }
 
+#[test]
+fn test_mock_prove_matches_interface_vectors() {
+    let (env, client, selector) = setup();
+
+    for vector in VECTORS {
+        let receipt = client.mock_prove_custom(
+            &vector.image_id(&env),
+            &vector.journal_digest(&env),
+            &None,
+            &Some(vector.exit_code),
+            &None,
+        );
+        assert_eq!(
+            receipt.claim_digest,
+            vector.claim_digest(&env),
+            "vector {}",
+            vector.name
+        );
+        assert_eq!(client.verify_integrity(&receipt), ());
+        if vector.kind == SealKind::Mock {
+            assert_eq!(client.verify_integrity(&vector.receipt(&env)), ());
+        }
+
+        // `mock_prove` and `verify` assume the guest exited with code zero.
+        if vector.exit_code != 0 {
+            let Err(Ok(VerifierError::InvalidProof)) = client.try_verify(
+                &receipt.seal,
+                &vector.image_id(&env),
+                &vector.journal_digest(&env),
+            ) else {
+                panic!("vector {} verified with exit code zero", vector.name);
+            };
+            continue;
+        }
+        let receipt = client.mock_prove(&vector.image_id(&env), &vector.journal_digest(&env));
+        let expected = mock_receipt(
+            &env,
+            &selector,
+            &vector.image_id(&env),
+            &vector.journal(&env),
+        );
+        assert_eq!(receipt.seal, expected.seal, "vector {}", vector.name);
+        client.verify(
+            &receipt.seal,
+            &vector.image_id(&env),
+            &vector.journal_digest(&env),
+        );
+    }
+}
+
 #[test]
 fn test_verify_integrity_invalid_selector() {
     let (env, client, selector) = setup();
@@ -77,3 +148,402 @@ fn test_verify_integrity_invalid_proof() {
         panic!("expected InvalidProof");
     };
 }
+
+#[test]
+fn test_verify_dev_mode_seal() {
+    let (env, client, _selector) = setup();
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+    let claim_digest =
+        ReceiptClaim::new(&env, image_id.clone(), journal_digest.clone()).digest(&env);
+
+    // Layout of `encode_seal` for a fake receipt.
+    let mut seal = Bytes::from_array(&env, &DEV_MODE_SELECTOR);
+    seal.append(&bytes_from(&env, &claim_digest));
+
+    assert_eq!(client.verify(&seal, &image_id, &journal_digest), ());
+
+    let wrong_journal = BytesN::from_array(&env, &[0x03; 32]);
+    let Err(Ok(VerifierError::InvalidProof)) = client.try_verify(&seal, &image_id, &wrong_journal)
+    else {
+        panic!("expected InvalidProof");
+    };
+}
+
+#[test]
+fn test_mock_prove_custom_claim() {
+    let (env, client, _selector) = setup();
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+    let input = BytesN::from_array(&env, &[0x03; 32]);
+
+    let receipt = client.mock_prove_custom(
+        &image_id,
+        &journal_digest,
+        &Some(SystemExitCode::Halted),
+        &Some(7),
+        &Some(input.clone()),
+    );
+    let expected = ReceiptClaim::new(&env, image_id.clone(), journal_digest.clone())
+        .with_exit_code(ExitCode::new(&env, SystemExitCode::Halted, 7))
+        .with_input(input);
+    assert_eq!(receipt.claim_digest, expected.digest(&env));
+    assert_eq!(client.verify_integrity(&receipt), ());
+
+    // The default claim assumed by `verify` differs.
+    let Err(Ok(VerifierError::InvalidProof)) =
+        client.try_verify(&receipt.seal, &image_id, &journal_digest)
+    else {
+        panic!("expected InvalidProof");
+    };
+}
+
+#[test]
+fn test_mock_prove_custom_defaults_match_mock_prove() {
+    let (env, client, _selector) = setup();
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+
+    assert_eq!(
+        client
+            .mock_prove_custom(&image_id, &journal_digest, &None, &None, &None)
+            .claim_digest,
+        client.mock_prove(&image_id, &journal_digest).claim_digest
+    );
+}
+
+#[test]
+fn test_call_history_records_verified_calls() {
+    let (env, client, _selector) = setup();
+    assert_eq!(client.call_count(), 0);
+    assert!(client.calls().is_empty());
+
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+    let receipt = client.mock_prove(&image_id, &journal_digest);
+    client.verify(&receipt.seal, &image_id, &journal_digest);
+    client.verify_integrity(&receipt);
+
+    assert_eq!(client.call_count(), 2);
+    let expected = VerifyCall {
+        caller: None,
+        claim_digest: receipt.claim_digest.clone(),
+        seal_len: 36,
+    };
+    assert_eq!(
+        client.calls(),
+        soroban_sdk::vec![&env, expected.clone(), expected]
+    );
+}
+
+#[test]
+fn test_call_history_records_the_calling_contract() {
+    let (env, client, _selector) = setup();
+    let relay = env.register(Relay, ());
+    let other_relay = env.register(Relay, ());
+    let receipt = client.mock_prove_claim(&BytesN::from_array(&env, &[0xAB; 32]));
+
+    RelayClient::new(&env, &relay).relay(&client.address, &receipt);
+    RelayClient::new(&env, &other_relay).relay(&client.address, &receipt);
+    client.verify_integrity(&receipt);
+
+    let callers: std::vec::Vec<_> = client.calls().iter().map(|call| call.caller).collect();
+    assert_eq!(callers, [Some(relay), Some(other_relay), None]);
+}
+
+#[test]
+fn test_call_history_skips_failed_calls() {
+    let (env, client, _selector) = setup();
+    let receipt = client.mock_prove_claim(&BytesN::from_array(&env, &[0xAA; 32]));
+    let wrong_receipt = Receipt {
+        seal: receipt.seal,
+        claim_digest: BytesN::from_array(&env, &[0xBB; 32]),
+    };
+
+    assert!(client.try_verify_integrity(&wrong_receipt).is_err());
+    assert_eq!(client.call_count(), 0);
+}
+
+#[test]
+fn test_call_history_keeps_most_recent_calls() {
+    let (env, client, _selector) = setup();
+    let total = CALL_HISTORY_LEN + 3;
+
+    for i in 0..total {
+        let mut digest = [0u8; 32];
+        digest[..4].copy_from_slice(&i.to_be_bytes());
+        let receipt = client.mock_prove_claim(&BytesN::from_array(&env, &digest));
+        client.verify_integrity(&receipt);
+    }
+
+    assert_eq!(client.call_count(), total);
+    let calls = client.calls();
+    assert_eq!(calls.len(), CALL_HISTORY_LEN);
+    let oldest = calls.first().unwrap().claim_digest.to_array();
+    assert_eq!(oldest[..4], 3u32.to_be_bytes());
+
+    client.reset_calls();
+    assert_eq!(client.call_count(), 0);
+    assert!(client.calls().is_empty());
+}
+
+#[test]
+fn test_set_selector() {
+    let (env, client, selector) = setup();
+    env.mock_all_auths();
+    let claim_digest = BytesN::from_array(&env, &[0xAB; 32]);
+    let old_receipt = client.mock_prove_claim(&claim_digest);
+
+    let new_selector = BytesN::from_array(&env, &[0x55, 0x66, 0x77, 0x88]);
+    client.set_selector(&new_selector);
+
+    assert_eq!(
+        env.events().all(),
+        std::vec![
+            SelectorUpdated {
+                previous: selector,
+                selector: new_selector.clone(),
+            }
+            .to_xdr(&env, &client.address)
+        ]
+    );
+    assert_eq!(client.selector(), new_selector);
+
+    let Err(Ok(VerifierError::InvalidSelector)) = client.try_verify_integrity(&old_receipt) else {
+        panic!("expected InvalidSelector");
+    };
+    let new_receipt = client.mock_prove_claim(&claim_digest);
+    assert_eq!(
+        new_receipt.seal.slice(0..4),
+        bytes_from(&env, &new_selector)
+    );
+    assert_eq!(client.verify_integrity(&new_receipt), ());
+}
+
+#[test]
+fn test_set_selector_requires_owner() {
+    let (env, client, selector) = setup();
+    let new_selector = BytesN::from_array(&env, &[0x55, 0x66, 0x77, 0x88]);
+
+    assert!(client.try_set_selector(&new_selector).is_err());
+    assert_eq!(client.selector(), selector);
+}
+
+#[test]
+fn test_strict_image_ids() {
+    let (env, client, _selector) = setup();
+    env.mock_all_auths();
+    let allowed = BytesN::from_array(&env, &[0x01; 32]);
+    let other = BytesN::from_array(&env, &[0x09; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+    let allowed_seal = client.mock_prove(&allowed, &journal_digest).seal;
+    let other_seal = client.mock_prove(&other, &journal_digest).seal;
+
+    // Off by default: any image ID verifies.
+    assert!(client.image_id_allowed(&other));
+    assert_eq!(client.verify(&other_seal, &other, &journal_digest), ());
+
+    client.set_strict_image_ids(&true);
+    client.set_image_id_allowed(&allowed, &true);
+
+    assert!(client.image_id_allowed(&allowed));
+    assert!(!client.image_id_allowed(&other));
+    assert_eq!(client.verify(&allowed_seal, &allowed, &journal_digest), ());
+    let Err(Ok(VerifierError::InvalidProof)) =
+        client.try_verify(&other_seal, &other, &journal_digest)
+    else {
+        panic!("expected InvalidProof");
+    };
+
+    client.set_image_id_allowed(&allowed, &false);
+    assert!(!client.image_id_allowed(&allowed));
+
+    client.set_strict_image_ids(&false);
+    assert_eq!(client.verify(&other_seal, &other, &journal_digest), ());
+}
+
+#[test]
+fn test_verify_full_receipt_checks_the_journal() {
+    let (env, client, _selector) = setup();
+    env.mock_all_auths();
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal = Bytes::from_slice(&env, b"journal");
+    let journal_digest = env.crypto().sha256(&journal).into();
+    let seal = client.mock_prove(&image_id, &journal_digest).seal;
+
+    let receipt = FullReceipt::new(&env, seal.clone(), image_id.clone(), journal);
+    assert_eq!(client.verify_full_receipt(&image_id, &receipt), ());
+    assert_eq!(client.call_count(), 1);
+
+    let mut tampered = receipt.clone();
+    tampered.journal = Bytes::from_slice(&env, b"journaL");
+    let Err(Ok(VerifierError::JournalMismatch)) =
+        client.try_verify_full_receipt(&image_id, &tampered)
+    else {
+        panic!("expected JournalMismatch for a tampered journal");
+    };
+    let other = BytesN::from_array(&env, &[0x09; 32]);
+    let Err(Ok(VerifierError::JournalMismatch)) = client.try_verify_full_receipt(&other, &receipt)
+    else {
+        panic!("expected JournalMismatch for another image ID");
+    };
+
+    // A consistent envelope whose seal proves a different claim.
+    let forged = FullReceipt::new(&env, seal, image_id.clone(), tampered.journal);
+    let Err(Ok(VerifierError::InvalidProof)) = client.try_verify_full_receipt(&image_id, &forged)
+    else {
+        panic!("expected InvalidProof for a seal of another claim");
+    };
+
+    // Strict image-ID mode applies as in `verify`.
+    client.set_strict_image_ids(&true);
+    let Err(Ok(VerifierError::InvalidProof)) = client.try_verify_full_receipt(&image_id, &receipt)
+    else {
+        panic!("expected InvalidProof for an image ID outside the allowlist");
+    };
+    assert_eq!(client.call_count(), 1);
+}
+
+#[test]
+fn test_strict_image_ids_requires_owner() {
+    let (_env, client, _selector) = setup();
+
+    assert!(client.try_set_strict_image_ids(&true).is_err());
+}
+
+#[test]
+fn test_burn_iterations_approximate_groth16_cost() {
+    let (env, client, _selector) = setup();
+    env.mock_all_auths();
+    let receipt = client.mock_prove_claim(&BytesN::from_array(&env, &[0xAB; 32]));
+
+    client.verify_integrity(&receipt);
+    let baseline = env.cost_estimate().budget().cpu_instruction_cost();
+
+    client.set_burn_iterations(&GROTH16_BURN_ITERATIONS);
+    assert_eq!(client.burn_iterations(), GROTH16_BURN_ITERATIONS);
+    client.verify_integrity(&receipt);
+    let burned = env.cost_estimate().budget().cpu_instruction_cost();
+
+    std::println!("mock verify_integrity: {baseline} CPU, with burn: {burned} CPU");
+    assert!((28_000_000..36_000_000).contains(&burned));
+}
+
+#[test]
+fn test_set_burn_iterations_requires_owner() {
+    let (_env, client, _selector) = setup();
+
+    assert!(client.try_set_burn_iterations(&1).is_err());
+}
+
+#[test]
+fn test_scripted_outcomes_are_consumed_call_by_call() {
+    let (env, client, _selector) = setup();
+    env.mock_all_auths();
+    let image_id = BytesN::from_array(&env, &[0x01; 32]);
+    let journal_digest = BytesN::from_array(&env, &[0x02; 32]);
+    let receipt = client.mock_prove(&image_id, &journal_digest);
+    let wrong_receipt = Receipt {
+        seal: receipt.seal.clone(),
+        claim_digest: BytesN::from_array(&env, &[0xBB; 32]),
+    };
+
+    let script = soroban_sdk::vec![
+        &env,
+        ScriptedOutcome::Ok,
+        ScriptedOutcome::Ok,
+        ScriptedOutcome::Err(VerifierError::InvalidProof as u32),
+        ScriptedOutcome::Panic,
+    ];
+    client.set_script(&script);
+    assert_eq!(client.script(), script);
+
+    // Scripted outcomes ignore the receipt, and both entrypoints take from the same script.
+    assert_eq!(client.verify_integrity(&wrong_receipt), ());
+    assert_eq!(client.verify(&receipt.seal, &image_id, &journal_digest), ());
+    let Err(Ok(VerifierError::InvalidProof)) = client.try_verify_integrity(&receipt) else {
+        panic!("expected the scripted InvalidProof");
+    };
+    let Err(Err(_)) = client.try_verify_integrity(&receipt) else {
+        panic!("expected the scripted panic");
+    };
+    assert_eq!(client.call_count(), 2);
+
+    // Once the script is used up, receipts are checked again.
+    assert!(client.try_verify_integrity(&wrong_receipt).is_err());
+    assert_eq!(client.verify_integrity(&receipt), ());
+
+    // A new script starts from its first outcome.
+    client.set_script(&soroban_sdk::vec![
+        &env,
+        ScriptedOutcome::Err(VerifierError::MalformedSeal as u32)
+    ]);
+    let Err(Ok(VerifierError::MalformedSeal)) = client.try_verify_integrity(&receipt) else {
+        panic!("expected the scripted MalformedSeal");
+    };
+    assert_eq!(client.verify_integrity(&receipt), ());
+}
+
+#[test]
+fn test_scripts_are_scoped_to_their_env_and_contract() {
+    // Contract addresses are assigned the same way in every `Env`, so both first mocks share
+    // an address.
+    let (env, client, _selector) = setup();
+    let (other_env, other_env_client, _selector) = setup();
+    let other_client = RiscZeroMockVerifierClient::new(
+        &env,
+        &env.register(
+            RiscZeroMockVerifier,
+            (
+                BytesN::from_array(&env, &[0x11, 0x22, 0x33, 0x44]),
+                Address::generate(&env),
+            ),
+        ),
+    );
+    env.mock_all_auths();
+    other_env.mock_all_auths();
+    let claim_digest = [0xAB; 32];
+    let receipt = client.mock_prove_claim(&BytesN::from_array(&env, &claim_digest));
+    let other_env_receipt =
+        other_env_client.mock_prove_claim(&BytesN::from_array(&other_env, &claim_digest));
+
+    client.set_script(&soroban_sdk::vec![
+        &env,
+        ScriptedOutcome::Err(VerifierError::InvalidProof as u32),
+        ScriptedOutcome::Err(VerifierError::MalformedSeal as u32),
+    ]);
+    other_env_client.set_script(&soroban_sdk::vec![
+        &other_env,
+        ScriptedOutcome::Err(VerifierError::InvalidSelector as u32),
+    ]);
+    other_client.set_script(&soroban_sdk::vec![
+        &env,
+        ScriptedOutcome::Err(VerifierError::MalformedSeal as u32),
+    ]);
+
+    let Err(Ok(VerifierError::InvalidProof)) = client.try_verify_integrity(&receipt) else {
+        panic!("expected the first scripted outcome");
+    };
+    let Err(Ok(VerifierError::InvalidSelector)) =
+        other_env_client.try_verify_integrity(&other_env_receipt)
+    else {
+        panic!("expected the other env's own script");
+    };
+    let Err(Ok(VerifierError::MalformedSeal)) = other_client.try_verify_integrity(&receipt) else {
+        panic!("expected the other contract's own script");
+    };
+    let Err(Ok(VerifierError::MalformedSeal)) = client.try_verify_integrity(&receipt) else {
+        panic!("expected the second scripted outcome");
+    };
+    assert_eq!(client.verify_integrity(&receipt), ());
+    assert_eq!(other_env_client.verify_integrity(&other_env_receipt), ());
+}
+
+#[test]
+fn test_set_script_requires_owner() {
+    let (env, client, _selector) = setup();
+
+    let script = soroban_sdk::vec![&env, ScriptedOutcome::Panic];
+    assert!(client.try_set_script(&script).is_err());
+    assert!(client.script().is_empty());
+}
@@ -3,8 +3,10 @@
 use risc0_interface::{
     Receipt, RiscZeroVerifierClient, RiscZeroVerifierRouterInterface, VerifierEntry, VerifierError,
 };
-use soroban_sdk::{Address, Bytes, BytesN, Env, contract, contractimpl, contracttype};
-use stellar_access::ownable::{Ownable, set_owner};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Map, Symbol, Val, Vec, contract, contractimpl, contracttype,
+};
+use stellar_access::ownable::{Ownable, get_owner, set_owner};
 use stellar_macros::only_owner;
 
 #[cfg(test)]
@@ -13,12 +15,75 @@ mod test;
 const DAY_IN_LEDGERS: u32 = 17_280;
 const VERIFIER_EXTEND_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
 const VERIFIER_TTL_THRESHOLD: u32 = VERIFIER_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+/// Most selectors that can be pinned in the instance-storage verifier cache.
+const MAX_CACHED_SELECTORS: u32 = 8;
 
 #[contracttype]
 #[derive(Clone)]
 enum DataKey {
     /// Selector-specific verifier entry.
     Verifier(BytesN<4>),
+    /// Verifier entry for an 8-byte versioned selector (4-byte family + 4-byte version).
+    ExtendedVerifier(BytesN<8>),
+    /// Marks a 4-byte prefix as a versioned family, so it cannot also be a legacy selector.
+    Family(BytesN<4>),
+    /// Whether seals are checked for an 8-byte versioned selector before the 4-byte one.
+    ExtendedSelectors,
+    /// Instance-storage cache of owner-pinned selector (4 or 8 bytes) to verifier mappings.
+    ///
+    /// The owner picks the cached selectors rather than the router counting uses: a use
+    /// counter would add a storage write to every verification, more than a hit saves.
+    VerifierCache,
+    /// Number of entries in the audit log.
+    AuditLen,
+    /// Audit log entry at an index, oldest first.
+    Audit(u32),
+    /// Verification cost class configured for a selector.
+    VerifyCost(BytesN<4>),
+}
+
+/// Rough resource footprint of verifying a seal, as returned by
+/// `estimate_verify`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum VerifyCost {
+    /// No class was configured for the selector.
+    Unknown = 0,
+    /// A mock verifier: a hash comparison, no pairing.
+    Mock = 1,
+    /// A Groth16 verifier: one BN254 pairing check.
+    Groth16 = 2,
+    /// A set verifier: a Merkle inclusion proof, plus a Groth16 check for
+    /// roots it has not seen.
+    SetInclusion = 3,
+}
+
+/// A change to the verifier registry.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum AuditAction {
+    /// A verifier was added for the selector.
+    Added = 0,
+    /// The selector was tombstoned.
+    Removed = 1,
+}
+
+/// One entry of the registry's append-only audit log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    /// What happened to the selector.
+    pub action: AuditAction,
+    /// The 4-byte legacy or 8-byte versioned selector.
+    pub selector: Bytes,
+    /// Verifier added, or the one that was active when the selector was removed.
+    pub verifier: Option<Address>,
+    /// Owner that made the change.
+    pub owner: Address,
+    /// Ledger sequence of the change.
+    pub ledger: u32,
 }
 
 #[contract]
@@ -39,6 +104,97 @@ impl RiscZeroVerifierRouter {
         })
     }
 
+    /// Looks up the entry for a selector, serving pinned verifiers from the
+    /// instance cache.
+    ///
+    /// Hits skip reading and decoding the persistent entry but still extend
+    /// its TTL. The entry stays the source of truth for `get_verifier` and
+    /// for routing once the selector is unpinned, so letting it archive while
+    /// the cache serves it would make the selector unroutable, until the
+    /// entry is restored, the moment it is unpinned. `extend_ttl` only rewrites the TTL once it falls below
+    /// the threshold, about once a day, so most hits pay for a TTL check only.
+    fn lookup_verifier_entry(env: &Env, selector: Bytes, key: &DataKey) -> Option<VerifierEntry> {
+        if let Some(address) = Self::verifier_cache(env).get(selector) {
+            env.storage().persistent().extend_ttl(
+                key,
+                VERIFIER_TTL_THRESHOLD,
+                VERIFIER_EXTEND_AMOUNT,
+            );
+            return Some(VerifierEntry::Active(address));
+        }
+        Self::read_verifier_entry(env, key)
+    }
+
+    /// Pins an active selector's verifier in the instance cache.
+    fn pin(env: &Env, selector: Bytes, key: &DataKey) -> Result<(), VerifierError> {
+        let address = match Self::read_verifier_entry(env, key) {
+            Some(VerifierEntry::Active(address)) => address,
+            Some(VerifierEntry::Tombstone) => return Err(VerifierError::SelectorRemoved),
+            None => return Err(VerifierError::SelectorUnknown),
+        };
+        let mut cache = Self::verifier_cache(env);
+        if !cache.contains_key(selector.clone()) && cache.len() >= MAX_CACHED_SELECTORS {
+            return Err(VerifierError::VerifierCacheFull);
+        }
+        cache.set(selector, address);
+        env.storage()
+            .instance()
+            .set(&DataKey::VerifierCache, &cache);
+        Ok(())
+    }
+
+    fn verifier_cache(env: &Env) -> Map<Bytes, Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::VerifierCache)
+            .unwrap_or(Map::new(env))
+    }
+
+    /// Appends a registry change to the audit log.
+    ///
+    /// Entries live in persistent storage with the verifier TTL policy, so
+    /// the history of trusted verifiers outlives RPC event retention.
+    fn record_audit(env: &Env, action: AuditAction, selector: Bytes, verifier: Option<Address>) {
+        let index: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AuditLen)
+            .unwrap_or(0);
+        let entry = AuditEntry {
+            action,
+            selector,
+            verifier,
+            owner: get_owner(env).expect("registry changes are made by the owner"),
+            ledger: env.ledger().sequence(),
+        };
+        let key = DataKey::Audit(index);
+        env.storage().persistent().set(&key, &entry);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, VERIFIER_TTL_THRESHOLD, VERIFIER_EXTEND_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&DataKey::AuditLen, &(index + 1));
+    }
+
+    /// Returns the active verifier of an entry, if any.
+    fn active_verifier(entry: Option<VerifierEntry>) -> Option<Address> {
+        match entry {
+            Some(VerifierEntry::Active(address)) => Some(address),
+            _ => None,
+        }
+    }
+
+    /// Drops a selector from the verifier cache, e.g. after its entry changed.
+    fn invalidate_cached_verifier(env: &Env, selector: Bytes) {
+        let mut cache = Self::verifier_cache(env);
+        if cache.remove(selector).is_some() {
+            env.storage()
+                .instance()
+                .set(&DataKey::VerifierCache, &cache);
+        }
+    }
+
     /// Initializes the router with the admin that can manage verifiers.
     pub fn __constructor(env: Env, owner: Address) {
         set_owner(&env, &owner);
@@ -51,7 +207,14 @@ impl RiscZeroVerifierRouter {
         selector: BytesN<4>,
         verifier: Address,
     ) -> Result<(), VerifierError> {
-        let key = DataKey::Verifier(selector);
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Family(selector.clone()))
+        {
+            return Err(VerifierError::SelectorInUse);
+        }
+        let key = DataKey::Verifier(selector.clone());
         let verifier_address: Option<VerifierEntry> = env.storage().persistent().get(&key);
 
         if let Some(entry) = verifier_address {
@@ -63,7 +226,9 @@ impl RiscZeroVerifierRouter {
 
         env.storage()
             .persistent()
-            .set(&key, &VerifierEntry::Active(verifier));
+            .set(&key, &VerifierEntry::Active(verifier.clone()));
+        Self::invalidate_cached_verifier(&env, selector.clone().into());
+        Self::record_audit(&env, AuditAction::Added, selector.into(), Some(verifier));
 
         Ok(())
     }
@@ -71,7 +236,7 @@ impl RiscZeroVerifierRouter {
     /// Removes a verifier for the selector, marking it as permanently removed.
     #[only_owner]
     pub fn remove_verifier(env: Env, selector: BytesN<4>) -> Result<(), VerifierError> {
-        let key = DataKey::Verifier(selector);
+        let key = DataKey::Verifier(selector.clone());
         let verifier_address: Option<VerifierEntry> = env.storage().persistent().get(&key);
 
         if verifier_address.is_none() {
@@ -81,14 +246,254 @@ impl RiscZeroVerifierRouter {
         env.storage()
             .persistent()
             .set(&key, &VerifierEntry::Tombstone);
+        Self::invalidate_cached_verifier(&env, selector.clone().into());
+        Self::record_audit(
+            &env,
+            AuditAction::Removed,
+            selector.into(),
+            Self::active_verifier(verifier_address),
+        );
 
         Ok(())
     }
 
+    /// Adds a verifier for an 8-byte versioned selector.
+    ///
+    /// The first 4 bytes name the verifier family and must not be a legacy
+    /// 4-byte selector, so seals of either kind resolve unambiguously.
+    #[only_owner]
+    pub fn add_extended_verifier(
+        env: Env,
+        selector: BytesN<8>,
+        verifier: Address,
+    ) -> Result<(), VerifierError> {
+        let family = family_of(&selector);
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::Verifier(family.clone()))
+        {
+            return Err(VerifierError::SelectorInUse);
+        }
+        let key = DataKey::ExtendedVerifier(selector.clone());
+        let verifier_address: Option<VerifierEntry> = env.storage().persistent().get(&key);
+
+        if let Some(entry) = verifier_address {
+            match entry {
+                VerifierEntry::Tombstone => return Err(VerifierError::SelectorRemoved),
+                VerifierEntry::Active(_) => return Err(VerifierError::SelectorInUse),
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&key, &VerifierEntry::Active(verifier.clone()));
+        env.storage()
+            .persistent()
+            .set(&DataKey::Family(family), &());
+        Self::invalidate_cached_verifier(&env, selector.clone().into());
+        Self::record_audit(&env, AuditAction::Added, selector.into(), Some(verifier));
+
+        Ok(())
+    }
+
+    /// Removes a verifier for an 8-byte versioned selector, marking it as
+    /// permanently removed.
+    #[only_owner]
+    pub fn remove_extended_verifier(env: Env, selector: BytesN<8>) -> Result<(), VerifierError> {
+        let key = DataKey::ExtendedVerifier(selector.clone());
+        let verifier_address: Option<VerifierEntry> = env.storage().persistent().get(&key);
+
+        if verifier_address.is_none() {
+            return Err(VerifierError::SelectorUnknown);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&key, &VerifierEntry::Tombstone);
+        Self::invalidate_cached_verifier(&env, selector.clone().into());
+        Self::record_audit(
+            &env,
+            AuditAction::Removed,
+            selector.into(),
+            Self::active_verifier(verifier_address),
+        );
+
+        Ok(())
+    }
+
+    /// Returns the raw verifier entry for an 8-byte versioned selector.
+    pub fn extended_verifiers(env: Env, selector: BytesN<8>) -> Option<VerifierEntry> {
+        Self::read_verifier_entry(&env, &DataKey::ExtendedVerifier(selector))
+    }
+
+    /// Sets the cost class `estimate_verify` reports for a selector.
+    #[only_owner]
+    pub fn set_verify_cost(
+        env: Env,
+        selector: BytesN<4>,
+        cost: VerifyCost,
+    ) -> Result<(), VerifierError> {
+        Self::get_verifier(&env, &selector)?;
+        env.storage()
+            .persistent()
+            .set(&DataKey::VerifyCost(selector), &cost);
+        Ok(())
+    }
+
+    /// Returns the cost class of verifying a seal with the selector, as a
+    /// `VerifyCost` discriminant, so callers can size transactions without
+    /// simulating them.
+    pub fn estimate_verify(env: Env, selector: BytesN<4>) -> Result<u32, VerifierError> {
+        Self::get_verifier(&env, &selector)?;
+        let cost = Self::read_verify_cost(&env, &DataKey::VerifyCost(selector))
+            .unwrap_or(VerifyCost::Unknown);
+        Ok(cost as u32)
+    }
+
+    /// Reads a selector's cost class, refreshing its TTL like verifier
+    /// entries.
+    fn read_verify_cost(env: &Env, key: &DataKey) -> Option<VerifyCost> {
+        env.storage().persistent().get(key).inspect(|_| {
+            env.storage().persistent().extend_ttl(
+                key,
+                VERIFIER_TTL_THRESHOLD,
+                VERIFIER_EXTEND_AMOUNT,
+            );
+        })
+    }
+
+    /// Returns the number of entries in the registry audit log.
+    pub fn audit_log_len(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::AuditLen)
+            .unwrap_or(0)
+    }
+
+    /// Returns up to `limit` audit log entries starting at index `start`,
+    /// oldest first.
+    pub fn audit_log(env: Env, start: u32, limit: u32) -> Vec<AuditEntry> {
+        let end = start
+            .saturating_add(limit)
+            .min(Self::audit_log_len(env.clone()));
+        let mut entries = Vec::new(&env);
+        for index in start..end {
+            let key = DataKey::Audit(index);
+            if let Some(entry) = env.storage().persistent().get(&key) {
+                env.storage().persistent().extend_ttl(
+                    &key,
+                    VERIFIER_TTL_THRESHOLD,
+                    VERIFIER_EXTEND_AMOUNT,
+                );
+                entries.push_back(entry);
+            }
+        }
+        entries
+    }
+
+    /// Turns seal routing by 8-byte versioned selectors on or off.
+    ///
+    /// While off, only the first 4 bytes of a seal are used, as before.
+    #[only_owner]
+    pub fn set_extended_selectors(env: Env, enabled: bool) {
+        env.storage()
+            .instance()
+            .set(&DataKey::ExtendedSelectors, &enabled);
+    }
+
+    /// Returns whether seals are routed by 8-byte versioned selectors.
+    pub fn extended_selectors(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::ExtendedSelectors)
+            .unwrap_or(false)
+    }
+
+    /// Pins a selector's verifier in the instance cache, so routing its seals
+    /// skips decoding the persistent entry.
+    ///
+    /// Meant for the few most used selectors: at most `MAX_CACHED_SELECTORS`
+    /// (8) can be pinned, and the cache is loaded with the instance on every
+    /// call.
+    ///
+    /// # Errors
+    ///
+    /// - [`VerifierError::SelectorUnknown`] - The selector is not registered
+    /// - [`VerifierError::SelectorRemoved`] - The selector was removed
+    /// - [`VerifierError::VerifierCacheFull`] - Every cache slot is pinned
+    #[only_owner]
+    pub fn pin_verifier(env: Env, selector: BytesN<4>) -> Result<(), VerifierError> {
+        let key = DataKey::Verifier(selector.clone());
+        Self::pin(&env, selector.into(), &key)
+    }
+
+    /// Pins an 8-byte versioned selector's verifier in the instance cache; see
+    /// [`Self::pin_verifier`].
+    #[only_owner]
+    pub fn pin_extended_verifier(env: Env, selector: BytesN<8>) -> Result<(), VerifierError> {
+        let key = DataKey::ExtendedVerifier(selector.clone());
+        Self::pin(&env, selector.into(), &key)
+    }
+
+    /// Unpins a selector, freeing its cache slot.
+    #[only_owner]
+    pub fn unpin_verifier(env: Env, selector: BytesN<4>) {
+        Self::invalidate_cached_verifier(&env, selector.into());
+    }
+
+    /// Unpins an 8-byte versioned selector, freeing its cache slot.
+    #[only_owner]
+    pub fn unpin_extended_verifier(env: Env, selector: BytesN<8>) {
+        Self::invalidate_cached_verifier(&env, selector.into());
+    }
+
+    /// Unpins every selector.
+    #[only_owner]
+    pub fn clear_verifier_cache(env: Env) {
+        env.storage().instance().remove(&DataKey::VerifierCache);
+    }
+
+    /// Forwards a call to the selector's verifier and returns its result.
+    ///
+    /// Reaches verifier-specific functions (emergency stops, parameters,
+    /// batch modes) through the router's stable address. The verifier sees
+    /// the router as the invoker, so router-owned verifiers can be managed
+    /// this way. Errors raised by the verifier abort the call.
+    #[only_owner]
+    pub fn call_verifier(
+        env: Env,
+        selector: BytesN<4>,
+        function: Symbol,
+        args: Vec<Val>,
+    ) -> Result<Val, VerifierError> {
+        let verifier = Self::get_verifier(&env, &selector)?;
+        Ok(env.invoke_contract(&verifier, &function, args))
+    }
+
+    /// Returns the verifier for the seal prefix.
+    ///
+    /// With extended selectors on, a registered 8-byte selector wins;
+    /// otherwise the first 4 bytes are looked up as a legacy selector.
+    fn get_verifier_for_seal(env: &Env, seal: &Bytes) -> Result<Address, VerifierError> {
+        let selector = selector_from_seal(seal)?;
+        if seal.len() >= 8 && Self::extended_selectors(env.clone()) {
+            let extended: BytesN<8> = seal.slice(0..8).try_into().unwrap();
+            let key = DataKey::ExtendedVerifier(extended.clone());
+            match Self::lookup_verifier_entry(env, extended.into(), &key) {
+                Some(VerifierEntry::Tombstone) => return Err(VerifierError::SelectorRemoved),
+                Some(VerifierEntry::Active(address)) => return Ok(address),
+                None => {}
+            }
+        }
+        Self::get_verifier(env, &selector)
+    }
+
     /// Returns the verifier for a selector.
     fn get_verifier(env: &Env, selector: &BytesN<4>) -> Result<Address, VerifierError> {
         let key = DataKey::Verifier(selector.clone());
-        let verifier_address: Option<VerifierEntry> = Self::read_verifier_entry(env, &key);
+        let verifier_address: Option<VerifierEntry> =
+            Self::lookup_verifier_entry(env, selector.clone().into(), &key);
 
         match verifier_address {
             Some(VerifierEntry::Tombstone) => Err(VerifierError::SelectorRemoved),
@@ -114,8 +519,7 @@ impl RiscZeroVerifierRouterInterface for RiscZeroVerifierRouter {
 
     /// Returns the verifier for the selector stored in the seal prefix.
     fn get_verifier_from_seal(env: Env, seal: Bytes) -> Result<Address, VerifierError> {
-        let selector = selector_from_seal(&seal)?;
-        Self::get_verifier(&env, &selector)
+        Self::get_verifier_for_seal(&env, &seal)
     }
 
     /// Verifies a receipt from its components.
@@ -125,8 +529,7 @@ impl RiscZeroVerifierRouterInterface for RiscZeroVerifierRouter {
         image_id: BytesN<32>,
         journal: BytesN<32>,
     ) -> Result<(), VerifierError> {
-        let selector = selector_from_seal(&seal)?;
-        let verifier = Self::get_verifier(&env, &selector)?;
+        let verifier = Self::get_verifier_for_seal(&env, &seal)?;
         let verifier = RiscZeroVerifierClient::new(&env, &verifier);
         verifier.verify(&seal, &image_id, &journal);
         Ok(())
@@ -134,8 +537,7 @@ impl RiscZeroVerifierRouterInterface for RiscZeroVerifierRouter {
 
     /// Verifies receipt integrity using the selector's verifier.
     fn verify_integrity(env: Env, receipt: Receipt) -> Result<(), VerifierError> {
-        let selector = selector_from_seal(&receipt.seal)?;
-        let verifier = Self::get_verifier(&env, &selector)?;
+        let verifier = Self::get_verifier_for_seal(&env, &receipt.seal)?;
         let verifier = RiscZeroVerifierClient::new(&env, &verifier);
         verifier.verify_integrity(&receipt);
         Ok(())
@@ -150,5 +552,10 @@ fn selector_from_seal(seal: &Bytes) -> Result<BytesN<4>, VerifierError> {
     Ok(seal.slice(0..4).try_into().unwrap())
 }
 
+/// Returns the 4-byte family of a versioned selector.
+fn family_of(selector: &BytesN<8>) -> BytesN<4> {
+    Bytes::from(selector).slice(0..4).try_into().unwrap()
+}
+
 #[contractimpl(contracttrait)]
 impl Ownable for RiscZeroVerifierRouter {}
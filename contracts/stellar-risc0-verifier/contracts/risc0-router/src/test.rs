@@ -2,7 +2,7 @@ use super::*;
 use risc0_interface::{Receipt, ReceiptClaim};
 use soroban_sdk::{
     Address, Bytes, BytesN, Env, IntoVal, Symbol, contract, contractimpl, symbol_short,
-    testutils::Address as _,
+    testutils::{Address as _, Ledger as _, storage::Persistent as _},
 };
 
 // =============================================================================
@@ -657,3 +657,441 @@ fn test_remove_verifier_requires_admin_auth() {
     // Should trap on admin.require_auth().
     client.remove_verifier(&selector);
 }
+
+// =============================================================================
+// Extended Selector Tests
+// =============================================================================
+
+fn create_extended_selector(env: &Env, bytes: [u8; 8]) -> BytesN<8> {
+    BytesN::from_array(env, &bytes)
+}
+
+fn create_seal_with_prefix(env: &Env, prefix: &[u8]) -> Bytes {
+    let mut seal_bytes = prefix.to_vec();
+    seal_bytes.extend_from_slice(&[0u8; 32]);
+    Bytes::from_slice(env, &seal_bytes)
+}
+
+#[test]
+fn test_extended_selectors_are_off_by_default() {
+    let (env, _admin, client) = setup_env();
+
+    let legacy = env.register(mock_verifier::MockVerifier, ());
+    let versioned = Address::generate(&env);
+    client.add_verifier(&create_selector(&env, [0x01, 0x02, 0x03, 0x04]), &legacy);
+    client.add_extended_verifier(
+        &create_extended_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]),
+        &versioned,
+    );
+
+    assert!(!client.extended_selectors());
+    let seal = create_seal_with_prefix(&env, &[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]);
+    let result = client.try_get_verifier_from_seal(&seal);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorUnknown
+    );
+}
+
+#[test]
+fn test_extended_selectors_route_by_version_with_legacy_fallback() {
+    let (env, _admin, client) = setup_env();
+    client.set_extended_selectors(&true);
+
+    let legacy = Address::generate(&env);
+    let v1 = env.register(mock_verifier::MockVerifier, ());
+    let v2 = env.register(mock_verifier::MockVerifier, ());
+    let v1_selector = create_extended_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]);
+    let v2_selector = create_extended_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 2]);
+    client.add_verifier(&create_selector(&env, [0x01, 0x02, 0x03, 0x04]), &legacy);
+    client.add_extended_verifier(&v1_selector, &v1);
+    client.add_extended_verifier(&v2_selector, &v2);
+
+    let from_seal =
+        |prefix: &[u8]| client.get_verifier_from_seal(&create_seal_with_prefix(&env, prefix));
+    assert_eq!(from_seal(&[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]), v1);
+    assert_eq!(from_seal(&[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 2]), v2);
+    // Legacy seals still resolve by their first 4 bytes, whatever follows.
+    assert_eq!(from_seal(&[0x01, 0x02, 0x03, 0x04, 0, 0, 0, 1]), legacy);
+    assert_eq!(
+        client.try_get_verifier_from_seal(&Bytes::from_slice(&env, &[0x01, 0x02, 0x03, 0x04])),
+        Ok(Ok(legacy))
+    );
+    // An unregistered version of a family is not routed anywhere.
+    let result = client.try_get_verifier_from_seal(&create_seal_with_prefix(
+        &env,
+        &[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 3],
+    ));
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorUnknown
+    );
+
+    let image_id = BytesN::from_array(&env, &[0u8; 32]);
+    let journal_digest = BytesN::from_array(&env, &[1u8; 32]);
+    let seal = create_seal_with_prefix(&env, &[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 2]);
+    client.verify(&seal, &image_id, &journal_digest);
+    assert!(!mock_verifier::MockVerifierClient::new(&env, &v1).was_called());
+    assert_eq!(
+        mock_verifier::MockVerifierClient::new(&env, &v2)
+            .get_verified_receipt()
+            .unwrap()
+            .seal,
+        seal
+    );
+
+    client.remove_extended_verifier(&v1_selector);
+    assert_eq!(
+        client.extended_verifiers(&v1_selector),
+        Some(VerifierEntry::Tombstone)
+    );
+    let result = client.try_get_verifier_from_seal(&create_seal_with_prefix(
+        &env,
+        &[0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1],
+    ));
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+    let result = client.try_add_extended_verifier(&v1_selector, &v1);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+}
+
+#[test]
+fn test_extended_families_do_not_collide_with_legacy_selectors() {
+    let (env, _admin, client) = setup_env();
+
+    let verifier = Address::generate(&env);
+    client.add_verifier(&create_selector(&env, [0x01, 0x02, 0x03, 0x04]), &verifier);
+    let result = client.try_add_extended_verifier(
+        &create_extended_selector(&env, [0x01, 0x02, 0x03, 0x04, 0, 0, 0, 1]),
+        &verifier,
+    );
+    assert_eq!(unwrap_verifier_error(result), VerifierError::SelectorInUse);
+
+    client.add_extended_verifier(
+        &create_extended_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]),
+        &verifier,
+    );
+    let result =
+        client.try_add_verifier(&create_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD]), &verifier);
+    assert_eq!(unwrap_verifier_error(result), VerifierError::SelectorInUse);
+
+    let result = client.try_remove_extended_verifier(&create_extended_selector(
+        &env,
+        [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 2],
+    ));
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorUnknown
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_set_extended_selectors_requires_admin_auth() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(RiscZeroVerifierRouter, (admin.clone(),));
+    let client = RiscZeroVerifierRouterClient::new(&env, &contract_id);
+
+    // Should trap on admin.require_auth().
+    client.set_extended_selectors(&true);
+}
+
+// =============================================================================
+// Verifier Cache Tests
+// =============================================================================
+
+fn cached_selectors(env: &Env, client: &RiscZeroVerifierRouterClient<'static>) -> u32 {
+    env.as_contract(&client.address, || {
+        RiscZeroVerifierRouter::verifier_cache(env).len()
+    })
+}
+
+#[test]
+fn test_pinned_verifiers_are_cached_and_unpinned_on_remove() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, _selector_b, verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    let seal_a = create_seal_with_selector(&env, &selector_a);
+
+    // Routing alone does not fill the cache.
+    assert_eq!(client.get_verifier_from_seal(&seal_a), verifier_a);
+    assert_eq!(cached_selectors(&env, &client), 0);
+
+    client.pin_verifier(&selector_a);
+    assert_eq!(cached_selectors(&env, &client), 1);
+    assert_eq!(client.get_verifier_from_seal(&seal_a), verifier_a);
+
+    client.remove_verifier(&selector_a);
+    assert_eq!(cached_selectors(&env, &client), 0);
+    let result = client.try_get_verifier_from_seal(&seal_a);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+
+    let result = client.try_pin_verifier(&selector_a);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+    let result = client.try_pin_verifier(&create_selector(&env, [0xFF; 4]));
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorUnknown
+    );
+    assert_eq!(cached_selectors(&env, &client), 0);
+}
+
+#[test]
+fn test_verifier_cache_is_bounded_and_can_be_cleared() {
+    let (env, _admin, client) = setup_env();
+
+    let selector = |i: u32| create_selector(&env, (i + 1).to_be_bytes());
+    for i in 0..=MAX_CACHED_SELECTORS {
+        client.add_verifier(&selector(i), &Address::generate(&env));
+    }
+    for i in 0..MAX_CACHED_SELECTORS {
+        client.pin_verifier(&selector(i));
+    }
+    assert_eq!(cached_selectors(&env, &client), MAX_CACHED_SELECTORS);
+
+    let last = selector(MAX_CACHED_SELECTORS);
+    let result = client.try_pin_verifier(&last);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::VerifierCacheFull
+    );
+    // Pinning again is a no-op, and unpinned selectors still route.
+    client.pin_verifier(&selector(0));
+    assert!(client.try_get_verifier_by_selector(&last).is_ok());
+
+    client.unpin_verifier(&selector(0));
+    client.pin_verifier(&last);
+    assert_eq!(cached_selectors(&env, &client), MAX_CACHED_SELECTORS);
+
+    client.clear_verifier_cache();
+    assert_eq!(cached_selectors(&env, &client), 0);
+}
+
+#[test]
+fn test_cache_hits_extend_the_entry_ttl() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, _selector_b, verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    client.pin_verifier(&selector_a);
+
+    let key = DataKey::Verifier(selector_a.clone());
+    let entry_ttl =
+        || env.as_contract(&client.address, || env.storage().persistent().get_ttl(&key));
+    assert_eq!(entry_ttl(), VERIFIER_EXTEND_AMOUNT);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 2 * DAY_IN_LEDGERS);
+    assert!(entry_ttl() < VERIFIER_TTL_THRESHOLD);
+
+    assert_eq!(client.get_verifier_by_selector(&selector_a), verifier_a);
+    assert_eq!(entry_ttl(), VERIFIER_EXTEND_AMOUNT);
+}
+
+#[test]
+fn test_extended_verifiers_are_cached_separately() {
+    let (env, _admin, client) = setup_env();
+    client.set_extended_selectors(&true);
+
+    let selector = create_extended_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD, 0, 0, 0, 1]);
+    let verifier = Address::generate(&env);
+    client.add_extended_verifier(&selector, &verifier);
+
+    let seal = create_seal_with_prefix(&env, &selector.to_array());
+    client.pin_extended_verifier(&selector);
+    assert_eq!(cached_selectors(&env, &client), 1);
+    assert_eq!(client.get_verifier_from_seal(&seal), verifier);
+
+    client.unpin_extended_verifier(&selector);
+    assert_eq!(cached_selectors(&env, &client), 0);
+    client.pin_extended_verifier(&selector);
+
+    client.remove_extended_verifier(&selector);
+    assert_eq!(cached_selectors(&env, &client), 0);
+    let result = client.try_get_verifier_from_seal(&seal);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+}
+
+// =============================================================================
+// Verifier Passthrough Tests
+// =============================================================================
+
+#[test]
+fn test_call_verifier_forwards_to_the_selector_verifier() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, selector_b, verifier_a, verifier_b) = setup_two_verifiers(&env, &client);
+    let mock_a = mock_verifier::MockVerifierClient::new(&env, &verifier_a);
+
+    let result = client.call_verifier(
+        &selector_a,
+        &Symbol::new(&env, "set_should_fail"),
+        &soroban_sdk::vec![&env, true.into_val(&env)],
+    );
+    assert!(result.is_void());
+
+    let seal_a = create_seal_with_selector(&env, &selector_a);
+    let image_id = BytesN::from_array(&env, &[0u8; 32]);
+    let journal_digest = BytesN::from_array(&env, &[1u8; 32]);
+    let result = client.try_verify(&seal_a, &image_id, &journal_digest);
+    assert_eq!(unwrap_verifier_error(result), VerifierError::InvalidProof);
+    assert!(!mock_a.was_called());
+
+    // Only the selector's verifier was reconfigured.
+    client.verify(
+        &create_seal_with_selector(&env, &selector_b),
+        &image_id,
+        &journal_digest,
+    );
+    let called: bool = client
+        .call_verifier(
+            &selector_b,
+            &Symbol::new(&env, "was_called"),
+            &soroban_sdk::vec![&env],
+        )
+        .into_val(&env);
+    assert!(called);
+    assert!(mock_verifier::MockVerifierClient::new(&env, &verifier_b).was_called());
+}
+
+#[test]
+fn test_call_verifier_rejects_unknown_and_removed_selectors() {
+    let (env, _admin, client) = setup_env();
+
+    let (_selector_a, selector_b, _verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    client.remove_verifier(&selector_b);
+    let function = Symbol::new(&env, "was_called");
+
+    let result = client.try_call_verifier(&selector_b, &function, &soroban_sdk::vec![&env]);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorRemoved
+    );
+    let unknown = create_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD]);
+    let result = client.try_call_verifier(&unknown, &function, &soroban_sdk::vec![&env]);
+    assert_eq!(
+        unwrap_verifier_error(result),
+        VerifierError::SelectorUnknown
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_call_verifier_requires_admin_auth() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, _selector_b, _verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    env.set_auths(&[]);
+
+    // Should trap on admin.require_auth().
+    client.call_verifier(
+        &selector_a,
+        &Symbol::new(&env, "set_should_fail"),
+        &soroban_sdk::vec![&env, true.into_val(&env)],
+    );
+}
+
+// =============================================================================
+// Audit Log Tests
+// =============================================================================
+
+#[test]
+fn test_audit_log_records_registry_changes_in_order() {
+    let (env, admin, client) = setup_env();
+    assert_eq!(client.audit_log_len(), 0);
+
+    let (selector_a, selector_b, verifier_a, verifier_b) = setup_two_verifiers(&env, &client);
+    client.remove_verifier(&selector_a);
+    let extended = create_extended_selector(&env, [0x50, 0x60, 0x70, 0x80, 0, 0, 0, 1]);
+    let verifier_c = Address::generate(&env);
+    client.add_extended_verifier(&extended, &verifier_c);
+    // Failed changes are not logged.
+    let _ = client.try_add_verifier(&selector_b, &verifier_c);
+
+    let entry = |action, selector: Bytes, verifier: &Address| AuditEntry {
+        action,
+        selector,
+        verifier: Some(verifier.clone()),
+        owner: admin.clone(),
+        ledger: env.ledger().sequence(),
+    };
+    assert_eq!(client.audit_log_len(), 4);
+    assert_eq!(
+        client.audit_log(&0, &10),
+        soroban_sdk::vec![
+            &env,
+            entry(AuditAction::Added, selector_a.clone().into(), &verifier_a),
+            entry(AuditAction::Added, selector_b.into(), &verifier_b),
+            entry(AuditAction::Removed, selector_a.into(), &verifier_a),
+            entry(AuditAction::Added, extended.into(), &verifier_c),
+        ]
+    );
+    assert_eq!(client.audit_log(&1, &2).len(), 2);
+    assert_eq!(
+        client.audit_log(&3, &2).get(0).map(|entry| entry.verifier),
+        Some(Some(verifier_c))
+    );
+    assert_eq!(client.audit_log(&4, &2).len(), 0);
+    assert_eq!(client.audit_log(&u32::MAX, &u32::MAX).len(), 0);
+}
+
+// =============================================================================
+// Verify Cost Tests
+// =============================================================================
+
+#[test]
+fn test_estimate_verify_reports_the_configured_cost_class() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, selector_b, _verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    assert_eq!(
+        client.estimate_verify(&selector_a),
+        VerifyCost::Unknown as u32
+    );
+
+    client.set_verify_cost(&selector_a, &VerifyCost::Groth16);
+    client.set_verify_cost(&selector_b, &VerifyCost::Mock);
+    assert_eq!(
+        client.estimate_verify(&selector_a),
+        VerifyCost::Groth16 as u32
+    );
+    assert_eq!(client.estimate_verify(&selector_b), VerifyCost::Mock as u32);
+
+    client.remove_verifier(&selector_a);
+    assert_eq!(
+        unwrap_verifier_error(client.try_estimate_verify(&selector_a)),
+        VerifierError::SelectorRemoved
+    );
+    let unknown = create_selector(&env, [0xAA, 0xBB, 0xCC, 0xDD]);
+    assert_eq!(
+        unwrap_verifier_error(client.try_set_verify_cost(&unknown, &VerifyCost::Mock)),
+        VerifierError::SelectorUnknown
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_set_verify_cost_requires_admin_auth() {
+    let (env, _admin, client) = setup_env();
+
+    let (selector_a, _selector_b, _verifier_a, _verifier_b) = setup_two_verifiers(&env, &client);
+    env.set_auths(&[]);
+
+    // Should trap on admin.require_auth().
+    client.set_verify_cost(&selector_a, &VerifyCost::Groth16);
+}
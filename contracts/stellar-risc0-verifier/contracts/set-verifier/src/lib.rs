@@ -0,0 +1,281 @@
+#![no_std]
+
+use risc0_interface::{
+    Receipt, ReceiptClaim, RiscZeroVerifierClient, RiscZeroVerifierInterface, VerifierError,
+};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, TryFromVal, Val, Vec, contract, contractimpl, contracttype,
+};
+
+#[cfg(test)]
+mod test;
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+const ROOT_EXTEND_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+const ROOT_TTL_THRESHOLD: u32 = ROOT_EXTEND_AMOUNT - DAY_IN_LEDGERS;
+
+const SELECTOR_SIZE: u32 = 4;
+const PATH_LEN_SIZE: u32 = 4;
+const DIGEST_SIZE: u32 = 32;
+
+/// Tag of the verifier parameters struct whose digest prefix is the selector.
+const PARAMETERS_TAG: &[u8] = b"risc0.SetInclusionReceiptVerifierParameters";
+
+/// Domain separator prepended to a claim digest before it is hashed into a leaf, so a leaf
+/// can never be confused with an inner node of the tree.
+const LEAF_TAG: &[u8] = b"LEAF_TAG";
+
+#[contracttype]
+enum DataKey {
+    /// Verifier (or router) used to check root seals.
+    Verifier,
+    /// Image ID of the set builder guest program.
+    ImageId,
+    /// Selector derived from the set builder image ID.
+    Selector,
+    /// Marker for a Merkle root whose seal has already been verified.
+    VerifiedRoot(BytesN<32>),
+}
+
+/// Decoded set-inclusion seal.
+///
+/// The wire format is:
+///
+/// ```text
+/// selector (4) || path_len (u32 BE) || path (32 * path_len) || root_seal
+/// ```
+///
+/// `root_seal` may be empty when the root has already been verified by this contract.
+struct SetInclusionSeal {
+    selector: BytesN<4>,
+    path: Vec<BytesN<32>>,
+    root_seal: Bytes,
+}
+
+impl TryFrom<&Bytes> for SetInclusionSeal {
+    type Error = VerifierError;
+
+    fn try_from(value: &Bytes) -> Result<Self, Self::Error> {
+        let header_size = SELECTOR_SIZE + PATH_LEN_SIZE;
+        if value.len() < header_size {
+            return Err(VerifierError::MalformedSeal);
+        }
+
+        let selector = value
+            .slice(0..SELECTOR_SIZE)
+            .try_into()
+            .map_err(|_| VerifierError::MalformedSeal)?;
+
+        let mut path_len = [0u8; PATH_LEN_SIZE as usize];
+        value
+            .slice(SELECTOR_SIZE..header_size)
+            .copy_into_slice(&mut path_len);
+        let path_len = u32::from_be_bytes(path_len);
+
+        let path_end = path_len
+            .checked_mul(DIGEST_SIZE)
+            .and_then(|size| size.checked_add(header_size))
+            .filter(|end| *end <= value.len())
+            .ok_or(VerifierError::MalformedSeal)?;
+
+        let mut path = Vec::new(value.env());
+        let mut offset = header_size;
+        while offset < path_end {
+            let node = value
+                .slice(offset..offset + DIGEST_SIZE)
+                .try_into()
+                .map_err(|_| VerifierError::MalformedSeal)?;
+            path.push_back(node);
+            offset += DIGEST_SIZE;
+        }
+
+        Ok(Self {
+            selector,
+            path,
+            root_seal: value.slice(path_end..),
+        })
+    }
+}
+
+/// Set-inclusion verifier for RISC Zero receipts, mirroring risc0's `RiscZeroSetVerifier`.
+///
+/// A set builder guest aggregates many claim digests into a Merkle tree and commits
+/// `image_id || root` to its journal. The root's seal is verified once through the configured
+/// Groth16 verifier (or router) and cached; every claim in the set is then verified by
+/// recomputing the root from its Merkle path, which costs a handful of keccak256 hashes
+/// instead of a pairing check.
+///
+/// Leaves are `keccak256(LEAF_TAG || claim_digest)` and inner nodes hash the two children in
+/// ascending order (`keccak256(min || max)`), so paths carry no left/right flags.
+#[contract]
+pub struct RiscZeroSetVerifier;
+
+#[contractimpl]
+impl RiscZeroSetVerifier {
+    /// Initializes the set verifier.
+    ///
+    /// - `verifier`: Contract used to verify root seals (Groth16 verifier or router)
+    /// - `image_id`: Image ID of the set builder guest program
+    ///
+    /// The selector is derived from `image_id` as the first four bytes of the
+    /// `risc0.SetInclusionReceiptVerifierParameters` tagged struct digest.
+    pub fn __constructor(env: Env, verifier: Address, image_id: BytesN<32>) {
+        let selector = compute_selector(&env, &image_id);
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Verifier, &verifier);
+        storage.set(&DataKey::ImageId, &image_id);
+        storage.set(&DataKey::Selector, &selector);
+    }
+
+    /// Returns the set verifier's selector.
+    pub fn selector(env: Env) -> BytesN<4> {
+        read_instance(&env, &DataKey::Selector)
+    }
+
+    /// Returns the image ID of the set builder guest program.
+    pub fn image_id(env: Env) -> BytesN<32> {
+        read_instance(&env, &DataKey::ImageId)
+    }
+
+    /// Returns the verifier used for root seals.
+    pub fn verifier(env: Env) -> Address {
+        read_instance(&env, &DataKey::Verifier)
+    }
+
+    /// Returns whether `root` has already been verified and cached.
+    pub fn is_root_verified(env: Env, root: BytesN<32>) -> bool {
+        is_root_verified(&env, &root)
+    }
+
+    /// Verifies the seal of a Merkle root produced by the set builder and caches the root.
+    ///
+    /// Anyone may submit a root: the seal must prove a set builder execution whose journal
+    /// is `image_id || root`.
+    pub fn submit_merkle_root(
+        env: Env,
+        root: BytesN<32>,
+        seal: Bytes,
+    ) -> Result<(), VerifierError> {
+        verify_root(&env, &root, &seal)
+    }
+
+    /// Computes the Merkle root for `claim_digest` given its inclusion path.
+    pub fn merkle_root(env: Env, claim_digest: BytesN<32>, path: Vec<BytesN<32>>) -> BytesN<32> {
+        merkle_path_root(&env, &claim_digest, &path)
+    }
+}
+
+#[contractimpl]
+impl RiscZeroVerifierInterface for RiscZeroSetVerifier {
+    type Proof = ();
+
+    fn verify(
+        env: Env,
+        seal: Bytes,
+        image_id: BytesN<32>,
+        journal: BytesN<32>,
+    ) -> Result<(), VerifierError> {
+        let claim = ReceiptClaim::new(&env, image_id, journal);
+        let receipt = Receipt {
+            seal,
+            claim_digest: claim.digest(&env),
+        };
+        Self::verify_integrity(env, receipt)
+    }
+
+    fn verify_integrity(env: Env, receipt: Receipt) -> Result<(), VerifierError> {
+        let seal = SetInclusionSeal::try_from(&receipt.seal)?;
+
+        if seal.selector != read_instance::<BytesN<4>>(&env, &DataKey::Selector) {
+            return Err(VerifierError::InvalidSelector);
+        }
+
+        let root = merkle_path_root(&env, &receipt.claim_digest, &seal.path);
+        if is_root_verified(&env, &root) {
+            return Ok(());
+        }
+
+        if seal.root_seal.is_empty() {
+            return Err(VerifierError::InvalidProof);
+        }
+
+        verify_root(&env, &root, &seal.root_seal)
+    }
+}
+
+fn read_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> V {
+    env.storage()
+        .instance()
+        .get(key)
+        .expect("set verifier is constructed with its configuration")
+}
+
+fn is_root_verified(env: &Env, root: &BytesN<32>) -> bool {
+    let key = DataKey::VerifiedRoot(root.clone());
+    let verified = env.storage().persistent().has(&key);
+    if verified {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, ROOT_TTL_THRESHOLD, ROOT_EXTEND_AMOUNT);
+    }
+    verified
+}
+
+/// Verifies `seal` as a set builder receipt committing to `root` and caches the root.
+fn verify_root(env: &Env, root: &BytesN<32>, seal: &Bytes) -> Result<(), VerifierError> {
+    let image_id: BytesN<32> = read_instance(env, &DataKey::ImageId);
+    let verifier: Address = read_instance(env, &DataKey::Verifier);
+
+    let mut journal = Bytes::from_array(env, &image_id.to_array());
+    journal.append(&Bytes::from_array(env, &root.to_array()));
+    let journal_digest: BytesN<32> = env.crypto().sha256(&journal).into();
+
+    let verifier = RiscZeroVerifierClient::new(env, &verifier);
+    match verifier.try_verify(seal, &image_id, &journal_digest) {
+        Ok(Ok(())) => {}
+        Err(Ok(error)) => return Err(error),
+        _ => return Err(VerifierError::InvalidProof),
+    }
+
+    let key = DataKey::VerifiedRoot(root.clone());
+    env.storage().persistent().set(&key, &());
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ROOT_TTL_THRESHOLD, ROOT_EXTEND_AMOUNT);
+
+    Ok(())
+}
+
+/// Folds the inclusion path over the leaf for `claim_digest`.
+fn merkle_path_root(env: &Env, claim_digest: &BytesN<32>, path: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut leaf = Bytes::from_slice(env, LEAF_TAG);
+    leaf.append(&Bytes::from_array(env, &claim_digest.to_array()));
+    let leaf: BytesN<32> = env.crypto().keccak256(&leaf).into();
+
+    path.iter().fold(leaf, |node, sibling| {
+        commutative_keccak256(env, &node, &sibling)
+    })
+}
+
+/// Hashes two nodes in ascending byte order.
+fn commutative_keccak256(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (a, b) = (a.to_array(), b.to_array());
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+
+    let mut data = Bytes::from_array(env, &first);
+    data.append(&Bytes::from_array(env, &second));
+    env.crypto().keccak256(&data).into()
+}
+
+/// Computes the selector as the digest prefix of
+/// `tagged_struct("risc0.SetInclusionReceiptVerifierParameters", [image_id])`.
+fn compute_selector(env: &Env, image_id: &BytesN<32>) -> BytesN<4> {
+    let tag_digest = env.crypto().sha256(&Bytes::from_slice(env, PARAMETERS_TAG));
+
+    let mut data = Bytes::from_array(env, &tag_digest.to_array());
+    data.append(&Bytes::from_array(env, &image_id.to_array()));
+    data.append(&Bytes::from_array(env, &1u16.to_le_bytes()));
+
+    let digest = env.crypto().sha256(&data).to_array();
+    BytesN::from_array(env, &[digest[0], digest[1], digest[2], digest[3]])
+}
@@ -0,0 +1,257 @@
+extern crate std;
+
+use std::vec::Vec as StdVec;
+
+use soroban_sdk::{Address, Bytes, BytesN, Env, Vec, testutils::Address as _};
+
+use crate::{LEAF_TAG, RiscZeroSetVerifier, RiscZeroSetVerifierClient};
+use mock_verifier::{RiscZeroMockVerifier, RiscZeroMockVerifierClient};
+use risc0_interface::{Receipt, VerifierError};
+
+const SET_BUILDER_IMAGE_ID: [u8; 32] = [0x5E; 32];
+
+struct Setup {
+    env: Env,
+    client: RiscZeroSetVerifierClient<'static>,
+    mock: RiscZeroMockVerifierClient<'static>,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    let mock_selector = BytesN::from_array(&env, &[0x11, 0x22, 0x33, 0x44]);
+    let mock_id = env.register(
+        RiscZeroMockVerifier,
+        (mock_selector, Address::generate(&env)),
+    );
+    let image_id = BytesN::from_array(&env, &SET_BUILDER_IMAGE_ID);
+    let contract_id = env.register(RiscZeroSetVerifier, (mock_id.clone(), image_id));
+
+    Setup {
+        client: RiscZeroSetVerifierClient::new(&env, &contract_id),
+        mock: RiscZeroMockVerifierClient::new(&env, &mock_id),
+        env,
+    }
+}
+
+fn leaf(env: &Env, claim_digest: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_slice(env, LEAF_TAG);
+    data.append(&Bytes::from_array(env, &claim_digest.to_array()));
+    env.crypto().keccak256(&data).into()
+}
+
+fn node(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (a, b) = (a.to_array(), b.to_array());
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &first);
+    data.append(&Bytes::from_array(env, &second));
+    env.crypto().keccak256(&data).into()
+}
+
+/// Builds a four-leaf tree and returns the claims, their paths and the root.
+fn build_tree(env: &Env) -> (StdVec<BytesN<32>>, StdVec<Vec<BytesN<32>>>, BytesN<32>) {
+    let claims: StdVec<BytesN<32>> = (1u8..=4)
+        .map(|i| BytesN::from_array(env, &[i; 32]))
+        .collect();
+    let leaves: StdVec<BytesN<32>> = claims.iter().map(|c| leaf(env, c)).collect();
+    let left = node(env, &leaves[0], &leaves[1]);
+    let right = node(env, &leaves[2], &leaves[3]);
+    let root = node(env, &left, &right);
+
+    let paths = std::vec![
+        Vec::from_array(env, [leaves[1].clone(), right.clone()]),
+        Vec::from_array(env, [leaves[0].clone(), right.clone()]),
+        Vec::from_array(env, [leaves[3].clone(), left.clone()]),
+        Vec::from_array(env, [leaves[2].clone(), left.clone()]),
+    ];
+
+    (claims, paths, root)
+}
+
+/// Mock seal for the set builder receipt committing to `root`.
+fn root_seal(setup: &Setup, root: &BytesN<32>) -> Bytes {
+    let env = &setup.env;
+    let mut journal = Bytes::from_array(env, &SET_BUILDER_IMAGE_ID);
+    journal.append(&Bytes::from_array(env, &root.to_array()));
+    let journal_digest = env.crypto().sha256(&journal).into();
+    let image_id = BytesN::from_array(env, &SET_BUILDER_IMAGE_ID);
+    setup.mock.mock_prove(&image_id, &journal_digest).seal
+}
+
+fn encode_seal(setup: &Setup, path: &Vec<BytesN<32>>, root_seal: &Bytes) -> Bytes {
+    let env = &setup.env;
+    let mut seal = Bytes::from_array(env, &setup.client.selector().to_array());
+    seal.append(&Bytes::from_array(env, &path.len().to_be_bytes()));
+    for sibling in path.iter() {
+        seal.append(&Bytes::from_array(env, &sibling.to_array()));
+    }
+    seal.append(root_seal);
+    seal
+}
+
+#[test]
+fn test_selector_is_derived_from_image_id() {
+    let setup = setup();
+    // Digest prefix of tagged_struct("risc0.SetInclusionReceiptVerifierParameters", [image_id])
+    let mut expected = Bytes::from_array(
+        &setup.env,
+        &setup
+            .env
+            .crypto()
+            .sha256(&Bytes::from_slice(
+                &setup.env,
+                b"risc0.SetInclusionReceiptVerifierParameters",
+            ))
+            .to_array(),
+    );
+    expected.append(&Bytes::from_array(&setup.env, &SET_BUILDER_IMAGE_ID));
+    expected.append(&Bytes::from_array(&setup.env, &[0x01, 0x00]));
+    let digest = setup.env.crypto().sha256(&expected).to_array();
+
+    assert_eq!(setup.client.selector().to_array(), digest[..4]);
+}
+
+#[test]
+fn test_merkle_root_matches_tree() {
+    let setup = setup();
+    let (claims, paths, root) = build_tree(&setup.env);
+
+    for (claim, path) in claims.iter().zip(paths.iter()) {
+        assert_eq!(setup.client.merkle_root(claim, path), root);
+    }
+}
+
+#[test]
+fn test_verify_integrity_with_root_seal_caches_root() {
+    let setup = setup();
+    let (claims, paths, root) = build_tree(&setup.env);
+    let root_seal = root_seal(&setup, &root);
+
+    assert!(!setup.client.is_root_verified(&root));
+
+    let receipt = Receipt {
+        seal: encode_seal(&setup, &paths[0], &root_seal),
+        claim_digest: claims[0].clone(),
+    };
+    setup.client.verify_integrity(&receipt);
+    assert!(setup.client.is_root_verified(&root));
+
+    // Every other claim in the set now verifies with the path alone.
+    let empty = Bytes::new(&setup.env);
+    for (claim, path) in claims.iter().zip(paths.iter()).skip(1) {
+        let receipt = Receipt {
+            seal: encode_seal(&setup, path, &empty),
+            claim_digest: claim.clone(),
+        };
+        setup.client.verify_integrity(&receipt);
+    }
+}
+
+#[test]
+fn test_submit_merkle_root() {
+    let setup = setup();
+    let (claims, paths, root) = build_tree(&setup.env);
+
+    setup
+        .client
+        .submit_merkle_root(&root, &root_seal(&setup, &root));
+    assert!(setup.client.is_root_verified(&root));
+
+    let receipt = Receipt {
+        seal: encode_seal(&setup, &paths[2], &Bytes::new(&setup.env)),
+        claim_digest: claims[2].clone(),
+    };
+    setup.client.verify_integrity(&receipt);
+}
+
+#[test]
+fn test_unverified_root_without_seal_is_rejected() {
+    let setup = setup();
+    let (claims, paths, _root) = build_tree(&setup.env);
+
+    let receipt = Receipt {
+        seal: encode_seal(&setup, &paths[0], &Bytes::new(&setup.env)),
+        claim_digest: claims[0].clone(),
+    };
+    let Err(Ok(VerifierError::InvalidProof)) = setup.client.try_verify_integrity(&receipt) else {
+        panic!("expected InvalidProof");
+    };
+}
+
+#[test]
+fn test_wrong_path_is_rejected() {
+    let setup = setup();
+    let (claims, paths, root) = build_tree(&setup.env);
+    setup
+        .client
+        .submit_merkle_root(&root, &root_seal(&setup, &root));
+
+    // Claim 0 with claim 2's path lands on a different, unverified root.
+    let receipt = Receipt {
+        seal: encode_seal(&setup, &paths[2], &Bytes::new(&setup.env)),
+        claim_digest: claims[0].clone(),
+    };
+    let Err(Ok(VerifierError::InvalidProof)) = setup.client.try_verify_integrity(&receipt) else {
+        panic!("expected InvalidProof");
+    };
+}
+
+#[test]
+fn test_root_seal_for_other_root_is_rejected() {
+    let setup = setup();
+    let (claims, paths, _root) = build_tree(&setup.env);
+    let other_root = BytesN::from_array(&setup.env, &[0xEE; 32]);
+
+    let receipt = Receipt {
+        seal: encode_seal(&setup, &paths[0], &root_seal(&setup, &other_root)),
+        claim_digest: claims[0].clone(),
+    };
+    let Err(Ok(VerifierError::InvalidProof)) = setup.client.try_verify_integrity(&receipt) else {
+        panic!("expected InvalidProof");
+    };
+}
+
+#[test]
+fn test_invalid_selector() {
+    let setup = setup();
+    let (claims, paths, root) = build_tree(&setup.env);
+
+    let mut seal = encode_seal(&setup, &paths[0], &root_seal(&setup, &root));
+    seal.set(0, seal.get(0).unwrap() ^ 0xFF);
+    let receipt = Receipt {
+        seal,
+        claim_digest: claims[0].clone(),
+    };
+    let Err(Ok(VerifierError::InvalidSelector)) = setup.client.try_verify_integrity(&receipt)
+    else {
+        panic!("expected InvalidSelector");
+    };
+}
+
+#[test]
+fn test_malformed_seals() {
+    let setup = setup();
+    let claim_digest = BytesN::from_array(&setup.env, &[0x01; 32]);
+    let selector = setup.client.selector().to_array();
+
+    let mut truncated_path = Bytes::from_array(&setup.env, &selector);
+    truncated_path.append(&Bytes::from_array(&setup.env, &2u32.to_be_bytes()));
+    truncated_path.append(&Bytes::from_array(&setup.env, &[0u8; 32]));
+
+    let mut huge_path = Bytes::from_array(&setup.env, &selector);
+    huge_path.append(&Bytes::from_array(&setup.env, &u32::MAX.to_be_bytes()));
+
+    for seal in [
+        Bytes::from_array(&setup.env, &selector),
+        truncated_path,
+        huge_path,
+    ] {
+        let receipt = Receipt {
+            seal,
+            claim_digest: claim_digest.clone(),
+        };
+        let Err(Ok(VerifierError::MalformedSeal)) = setup.client.try_verify_integrity(&receipt)
+        else {
+            panic!("expected MalformedSeal");
+        };
+    }
+}
@@ -0,0 +1,47 @@
+//! Seals of any length and content through `Groth16Seal::try_from`, via the Groth16
+//! verifier's `check_seal`.
+//!
+//! Decoding must never panic, a seal of the wrong length must always be reported as
+//! `InvalidSealLength`, and the selector check must only ever replace success: swapping in
+//! the verifier's own selector cannot turn one point error into another.
+
+#![no_main]
+
+use groth16_verifier::RiscZeroGroth16Verifier;
+use libfuzzer_sys::fuzz_target;
+use risc0_interface::VerifierError;
+use soroban_sdk::{Bytes, Env};
+
+/// `selector || a || b || c`.
+const SEAL_SIZE: usize = 260;
+
+fuzz_target!(|seal: &[u8]| {
+    let env = Env::default();
+    let actual = RiscZeroGroth16Verifier::check_seal(Bytes::from_slice(&env, seal));
+
+    if seal.len() != SEAL_SIZE {
+        assert_eq!(actual, Err(VerifierError::InvalidSealLength));
+        return;
+    }
+    assert!(
+        matches!(
+            actual,
+            Ok(())
+                | Err(VerifierError::InvalidSelector
+                    | VerifierError::PointNotOnCurve
+                    | VerifierError::PointNotInSubgroup)
+        ),
+        "unexpected error {actual:?}"
+    );
+
+    // The same proof behind the verifier's own selector.
+    let selector = RiscZeroGroth16Verifier::selector(env.clone()).to_array();
+    let prefixed = [&selector[..], &seal[4..]].concat();
+    let with_selector = RiscZeroGroth16Verifier::check_seal(Bytes::from_slice(&env, &prefixed));
+    assert_ne!(with_selector, Err(VerifierError::InvalidSelector));
+    if seal[..4] == selector {
+        assert_eq!(actual, with_selector);
+    } else {
+        assert_eq!(actual, with_selector.and(Err(VerifierError::InvalidSelector)));
+    }
+});
@@ -0,0 +1,47 @@
+//! Receipts of any seal through the mock verifier's `verify_integrity`.
+//!
+//! The input is the claim digest (first 32 bytes) followed by the seal. The mock must accept
+//! exactly the seals `selector || claim_digest` for its configured selector or the dev-mode
+//! one, and report every other seal with the error for the first check it fails.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mock_verifier::{DEV_MODE_SELECTOR, RiscZeroMockVerifier, RiscZeroMockVerifierClient};
+use risc0_interface::{Receipt, VerifierError};
+use soroban_sdk::{Address, Bytes, BytesN, Env, testutils::Address as _};
+
+const SELECTOR: [u8; 4] = [0x11, 0x22, 0x33, 0x44];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((claim_digest, seal)) = data.split_first_chunk::<32>() else {
+        return;
+    };
+
+    let env = Env::default();
+    let contract_id = env.register(
+        RiscZeroMockVerifier,
+        (BytesN::from_array(&env, &SELECTOR), Address::generate(&env)),
+    );
+    let client = RiscZeroMockVerifierClient::new(&env, &contract_id);
+
+    let expected = match seal.split_first_chunk::<4>() {
+        None => Err(VerifierError::MalformedSeal),
+        Some((selector, _)) if *selector != SELECTOR && *selector != DEV_MODE_SELECTOR => {
+            Err(VerifierError::InvalidSelector)
+        }
+        Some((_, rest)) if rest != claim_digest => Err(VerifierError::InvalidProof),
+        Some(_) => Ok(()),
+    };
+
+    let receipt = Receipt {
+        seal: Bytes::from_slice(&env, seal),
+        claim_digest: BytesN::from_array(&env, claim_digest),
+    };
+    let actual = match client.try_verify_integrity(&receipt) {
+        Ok(Ok(())) => Ok(()),
+        Err(Ok(error)) => Err(error),
+        other => panic!("verify_integrity did not return a VerifierError: {other:?}"),
+    };
+    assert_eq!(actual, expected);
+});
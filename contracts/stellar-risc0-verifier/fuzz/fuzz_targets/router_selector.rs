@@ -0,0 +1,45 @@
+//! Seals of any length and content through the router's `selector_from_seal`, via
+//! `get_verifier_from_seal`.
+//!
+//! Every input must come back as a contract error or a verifier address, never a host panic,
+//! and the error must depend only on the seal's first four bytes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use risc0_interface::VerifierError;
+use risc0_router::{RiscZeroVerifierRouter, RiscZeroVerifierRouterClient};
+use soroban_sdk::{Address, Bytes, BytesN, Env, testutils::Address as _};
+
+/// Selector routed to a verifier.
+const ACTIVE: [u8; 4] = [0x73, 0xc4, 0x57, 0xba];
+/// Selector that was routed and then removed.
+const REMOVED: [u8; 4] = [0xff; 4];
+
+fuzz_target!(|seal: &[u8]| {
+    let env = Env::default();
+    env.mock_all_auths();
+    let router = env.register(RiscZeroVerifierRouter, (Address::generate(&env),));
+    let client = RiscZeroVerifierRouterClient::new(&env, &router);
+    let verifier = Address::generate(&env);
+    client.add_verifier(&BytesN::from_array(&env, &ACTIVE), &verifier);
+    client.add_verifier(
+        &BytesN::from_array(&env, &REMOVED),
+        &Address::generate(&env),
+    );
+    client.remove_verifier(&BytesN::from_array(&env, &REMOVED));
+
+    let expected = match seal.get(..4) {
+        None => Err(VerifierError::MalformedSeal),
+        Some(selector) if selector == ACTIVE => Ok(verifier),
+        Some(selector) if selector == REMOVED => Err(VerifierError::SelectorRemoved),
+        Some(_) => Err(VerifierError::SelectorUnknown),
+    };
+
+    let actual = match client.try_get_verifier_from_seal(&Bytes::from_slice(&env, seal)) {
+        Ok(Ok(address)) => Ok(address),
+        Err(Ok(error)) => Err(error),
+        other => panic!("get_verifier_from_seal did not return a VerifierError: {other:?}"),
+    };
+    assert_eq!(actual, expected);
+});
@@ -0,0 +1,298 @@
+//! # Stellar RISC Zero SDK
+//!
+//! Off-chain helpers that turn a RISC Zero receipt into the arguments the Soroban verifier
+//! contracts take.
+//!
+//! ## Overview
+//!
+//! A caller of `verify` passes the seal (selector included), the image ID and the SHA-256
+//! digest of the journal; a caller of `verify_integrity` passes a `Receipt` whose claim digest
+//! it computed itself. [`PreparedReceipt`] computes all of these once, the same way the
+//! `risc0-interface` crate does on-chain:
+//!
+//! - [`journal_digest()`]: SHA-256 of the journal bytes
+//! - [`claim_digest()`]: digest of the `ReceiptClaim` for a successful run
+//! - [`PreparedReceipt::receipt_xdr()`]: the `Receipt` contract type as `ScVal` XDR
+//!
+//! With the `risc0` feature, [`PreparedReceipt::from_risc0`] takes a `risc0_zkvm::Receipt`
+//! directly and encodes the seal the way `risc0_ethereum_contracts::encode_seal` does.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use stellar_risc0_sdk::PreparedReceipt;
+//!
+//! let prepared = PreparedReceipt::groth16(selector, &proof, image_id, journal)?;
+//! let args = prepared.verify_args()?;
+//! let receipt = prepared.receipt_xdr()?;
+//! ```
+
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    BytesM, Limits, ScBytes, ScMap, ScMapEntry, ScSymbol, ScVal, StringM, WriteXdr,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Length of the selector that prefixes every seal.
+pub const SELECTOR_LEN: usize = 4;
+
+/// Length of a Groth16 proof (`a || b || c`) without its selector.
+pub const GROTH16_PROOF_LEN: usize = 256;
+
+/// Selector that `risc0_ethereum_contracts::encode_seal` writes for `risc0_zkvm` dev-mode
+/// (fake) receipts, followed by the claim digest. The mock verifier accepts it.
+pub const DEV_MODE_SELECTOR: [u8; SELECTOR_LEN] = [0xFF; SELECTOR_LEN];
+
+/// Pre-computed SHA-256("risc0.Output") tag digest.
+const OUTPUT_TAG_DIGEST: [u8; 32] = [
+    0x77, 0xea, 0xfe, 0xb3, 0x66, 0xa7, 0x8b, 0x47, 0x74, 0x7d, 0xe0, 0xd7, 0xbb, 0x17, 0x62, 0x84,
+    0x08, 0x5f, 0xf5, 0x56, 0x48, 0x87, 0x00, 0x9a, 0x5b, 0xe6, 0x3d, 0xa3, 0x2d, 0x35, 0x59, 0xd4,
+];
+
+/// Pre-computed SHA-256("risc0.ReceiptClaim") tag digest.
+const RECEIPT_CLAIM_TAG_DIGEST: [u8; 32] = [
+    0xcb, 0x1f, 0xef, 0xcd, 0x1f, 0x2d, 0x9a, 0x64, 0x97, 0x5c, 0xbb, 0xbf, 0x6e, 0x16, 0x1e, 0x29,
+    0x14, 0x43, 0x4b, 0x0c, 0xbb, 0x99, 0x60, 0xb8, 0x4d, 0xf5, 0xd7, 0x17, 0xe8, 0x6b, 0x48, 0xaf,
+];
+
+/// Fixed post-state digest for a halted execution.
+const POST_STATE_DIGEST_HALTED: [u8; 32] = [
+    0xa3, 0xac, 0xc2, 0x71, 0x17, 0x41, 0x89, 0x96, 0x34, 0x0b, 0x84, 0xe5, 0xa9, 0x0f, 0x3e, 0xf4,
+    0xc4, 0x9d, 0x22, 0xc7, 0x9e, 0x44, 0xaa, 0xd8, 0x22, 0xec, 0x9c, 0x31, 0x3e, 0x1e, 0xb8, 0xe2,
+];
+
+/// Why a receipt could not be prepared.
+#[derive(Debug)]
+pub enum Error {
+    /// The seal is shorter than its selector.
+    SealTooShort(usize),
+    /// A Groth16 proof that is not [`GROTH16_PROOF_LEN`] bytes.
+    ProofLength(usize),
+    /// A value does not fit its XDR type.
+    Xdr(stellar_xdr::curr::Error),
+    /// A receipt kind the verifier contracts cannot check (composite or succinct).
+    #[cfg(feature = "risc0")]
+    UnsupportedReceipt,
+    /// The receipt's claim could not be read.
+    #[cfg(feature = "risc0")]
+    Claim(String),
+    /// The receipt proves a claim other than a successful run of `image_id` that committed its
+    /// journal, e.g. a non-zero exit code.
+    #[cfg(feature = "risc0")]
+    ClaimMismatch,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SealTooShort(len) => write!(f, "seal of {len} bytes has no selector"),
+            Error::ProofLength(len) => {
+                write!(
+                    f,
+                    "Groth16 proof is {len} bytes, expected {GROTH16_PROOF_LEN}"
+                )
+            }
+            Error::Xdr(error) => write!(f, "XDR encoding failed: {error}"),
+            #[cfg(feature = "risc0")]
+            Error::UnsupportedReceipt => {
+                write!(
+                    f,
+                    "only Groth16 and fake receipts can be verified on Soroban"
+                )
+            }
+            #[cfg(feature = "risc0")]
+            Error::Claim(error) => write!(f, "receipt claim is unavailable: {error}"),
+            #[cfg(feature = "risc0")]
+            Error::ClaimMismatch => {
+                write!(
+                    f,
+                    "receipt claim is not a successful run committing its journal"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<stellar_xdr::curr::Error> for Error {
+    fn from(error: stellar_xdr::curr::Error) -> Self {
+        Error::Xdr(error)
+    }
+}
+
+/// SHA-256 digest of `journal`, as `verify` takes it.
+pub fn journal_digest(journal: &[u8]) -> [u8; 32] {
+    Sha256::digest(journal).into()
+}
+
+/// Digest of the claim that `image_id` halted with exit code 0 after committing a journal
+/// with digest `journal_digest`.
+///
+/// Matches `ReceiptClaim::new(env, image_id, journal_digest).digest(env)` in
+/// `risc0-interface`: no committed input and no assumptions.
+pub fn claim_digest(image_id: &[u8; 32], journal_digest: &[u8; 32]) -> [u8; 32] {
+    let output: [u8; 32] = Sha256::new()
+        .chain_update(OUTPUT_TAG_DIGEST)
+        .chain_update(journal_digest)
+        .chain_update([0u8; 32])
+        .chain_update([0x02, 0x00])
+        .finalize()
+        .into();
+    Sha256::new()
+        .chain_update(RECEIPT_CLAIM_TAG_DIGEST)
+        .chain_update([0u8; 32])
+        .chain_update(image_id)
+        .chain_update(POST_STATE_DIGEST_HALTED)
+        .chain_update(output)
+        // Halted system exit code and user exit code 0, each as `(code << 24).to_be_bytes()`
+        .chain_update([0u8; 8])
+        .chain_update([0x04, 0x00])
+        .finalize()
+        .into()
+}
+
+/// A receipt with everything the verifier contracts need computed from it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedReceipt {
+    /// The seal with its selector, as `verify` and `Receipt::seal` take it.
+    pub seal: Vec<u8>,
+    /// Image ID of the guest program.
+    pub image_id: [u8; 32],
+    /// The journal bytes, not their digest.
+    pub journal: Vec<u8>,
+    /// SHA-256 digest of [`Self::journal`].
+    pub journal_digest: [u8; 32],
+    /// Digest of the claim the seal proves, as `Receipt::claim_digest` takes it.
+    pub claim_digest: [u8; 32],
+}
+
+impl PreparedReceipt {
+    /// Prepares a receipt whose seal is already encoded, selector included.
+    pub fn new(seal: Vec<u8>, image_id: [u8; 32], journal: Vec<u8>) -> Result<Self, Error> {
+        if seal.len() < SELECTOR_LEN {
+            return Err(Error::SealTooShort(seal.len()));
+        }
+        let journal_digest = journal_digest(&journal);
+        Ok(Self {
+            seal,
+            image_id,
+            claim_digest: claim_digest(&image_id, &journal_digest),
+            journal,
+            journal_digest,
+        })
+    }
+
+    /// Prepares a Groth16 receipt from the verifier's selector and the raw `a || b || c`
+    /// proof.
+    pub fn groth16(
+        selector: [u8; SELECTOR_LEN],
+        proof: &[u8],
+        image_id: [u8; 32],
+        journal: Vec<u8>,
+    ) -> Result<Self, Error> {
+        if proof.len() != GROTH16_PROOF_LEN {
+            return Err(Error::ProofLength(proof.len()));
+        }
+        Self::new([&selector[..], proof].concat(), image_id, journal)
+    }
+
+    /// Prepares a dev-mode receipt for the mock verifier: [`DEV_MODE_SELECTOR`] followed by
+    /// the claim digest.
+    pub fn dev(image_id: [u8; 32], journal: Vec<u8>) -> Self {
+        let journal_digest = journal_digest(&journal);
+        let claim_digest = claim_digest(&image_id, &journal_digest);
+        Self {
+            seal: [&DEV_MODE_SELECTOR[..], &claim_digest].concat(),
+            image_id,
+            journal,
+            journal_digest,
+            claim_digest,
+        }
+    }
+
+    /// Prepares a receipt straight out of a `risc0_zkvm` prover.
+    ///
+    /// Groth16 receipts are prefixed with the first four bytes of their verifier parameters
+    /// digest and fake (dev-mode) receipts are encoded as by [`Self::dev`], matching
+    /// `risc0_ethereum_contracts::encode_seal`. Fails unless the receipt claims a successful
+    /// run of `image_id` that committed its journal, since that is the only claim the
+    /// contracts derive.
+    #[cfg(feature = "risc0")]
+    pub fn from_risc0(
+        receipt: &risc0_zkvm::Receipt,
+        image_id: impl Into<risc0_zkvm::sha::Digest>,
+    ) -> Result<Self, Error> {
+        use risc0_zkvm::{InnerReceipt, sha::Digestible};
+
+        let image_id: [u8; 32] = image_id.into().into();
+        let journal = receipt.journal.bytes.clone();
+        let prepared = match &receipt.inner {
+            InnerReceipt::Groth16(inner) => {
+                let mut selector = [0u8; SELECTOR_LEN];
+                selector.copy_from_slice(&inner.verifier_parameters.as_bytes()[..SELECTOR_LEN]);
+                Self::groth16(selector, &inner.seal, image_id, journal)?
+            }
+            InnerReceipt::Fake(_) => Self::dev(image_id, journal),
+            _ => return Err(Error::UnsupportedReceipt),
+        };
+        let claim: [u8; 32] = receipt
+            .claim()
+            .map_err(|error| Error::Claim(error.to_string()))?
+            .digest::<risc0_zkvm::sha::Impl>()
+            .into();
+        if claim != prepared.claim_digest {
+            return Err(Error::ClaimMismatch);
+        }
+        Ok(prepared)
+    }
+
+    /// The selector the router dispatches on.
+    pub fn selector(&self) -> [u8; SELECTOR_LEN] {
+        let mut selector = [0u8; SELECTOR_LEN];
+        selector.copy_from_slice(&self.seal[..SELECTOR_LEN]);
+        selector
+    }
+
+    /// Arguments of `verify(seal, image_id, journal_digest)`, in order.
+    pub fn verify_args(&self) -> Result<[ScVal; 3], Error> {
+        Ok([
+            bytes_val(&self.seal)?,
+            bytes_val(&self.image_id)?,
+            bytes_val(&self.journal_digest)?,
+        ])
+    }
+
+    /// The `Receipt` contract type that `verify_integrity` takes.
+    pub fn receipt(&self) -> Result<ScVal, Error> {
+        // Contract types are maps with their fields sorted by name.
+        let entries = vec![
+            ScMapEntry {
+                key: symbol_val("claim_digest")?,
+                val: bytes_val(&self.claim_digest)?,
+            },
+            ScMapEntry {
+                key: symbol_val("seal")?,
+                val: bytes_val(&self.seal)?,
+            },
+        ];
+        Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+    }
+
+    /// [`Self::receipt`] as XDR, as `Receipt::from_xdr` and `stellar contract invoke` take it.
+    pub fn receipt_xdr(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.receipt()?.to_xdr(Limits::none())?)
+    }
+}
+
+fn bytes_val(bytes: &[u8]) -> Result<ScVal, Error> {
+    let bytes: BytesM = bytes.to_vec().try_into()?;
+    Ok(ScVal::Bytes(ScBytes(bytes)))
+}
+
+fn symbol_val(name: &str) -> Result<ScVal, Error> {
+    let name: StringM<32> = name.try_into()?;
+    Ok(ScVal::Symbol(ScSymbol(name)))
+}
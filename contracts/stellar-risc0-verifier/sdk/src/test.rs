@@ -0,0 +1,219 @@
+use groth16_verifier::{RiscZeroGroth16Verifier, RiscZeroGroth16VerifierClient};
+use mock_verifier::{RiscZeroMockVerifier, RiscZeroMockVerifierClient};
+use risc0_interface::{Receipt, ReceiptClaim};
+use serde::Deserialize;
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env,
+    testutils::Address as _,
+    xdr::{FromXdr, ToXdr},
+};
+use stellar_xdr::curr::{Limits, WriteXdr};
+
+use crate::{
+    DEV_MODE_SELECTOR, Error, GROTH16_PROOF_LEN, PreparedReceipt, claim_digest, journal_digest,
+};
+
+/// Recorded receipt from the Groth16 verifier's `fixtures/receipts.json`.
+#[derive(Deserialize)]
+struct ReceiptFixture {
+    name: String,
+    image_id: String,
+    journal: String,
+    #[serde(default)]
+    exit_code: u32,
+    seal: String,
+}
+
+impl ReceiptFixture {
+    /// Fixtures of guests that halted with exit code zero, the only claim a
+    /// `PreparedReceipt` builds.
+    fn all() -> Vec<Self> {
+        let fixtures: Vec<Self> = serde_json::from_str(include_str!(
+            "../../contracts/groth16-verifier/fixtures/receipts.json"
+        ))
+        .expect("receipts.json is valid");
+        fixtures
+            .into_iter()
+            .filter(|fixture| fixture.exit_code == 0)
+            .collect()
+    }
+
+    fn prepare(&self) -> PreparedReceipt {
+        let image_id = hex::decode(&self.image_id)
+            .expect("image_id is hex")
+            .try_into()
+            .expect("image_id is 32 bytes");
+        PreparedReceipt::new(
+            hex::decode(&self.seal).expect("seal is hex"),
+            image_id,
+            hex::decode(&self.journal).expect("journal is hex"),
+        )
+        .expect("fixture seals have a selector")
+    }
+}
+
+/// The `Receipt` the contracts build for `prepared`, with their own digest computation.
+fn contract_receipt(env: &Env, prepared: &PreparedReceipt) -> Receipt {
+    let journal_digest = env
+        .crypto()
+        .sha256(&Bytes::from_slice(env, &prepared.journal));
+    let claim = ReceiptClaim::new(
+        env,
+        BytesN::from_array(env, &prepared.image_id),
+        journal_digest.into(),
+    );
+    Receipt {
+        seal: Bytes::from_slice(env, &prepared.seal),
+        claim_digest: claim.digest(env),
+    }
+}
+
+#[test]
+fn test_digests_match_the_interface() {
+    let env = Env::default();
+
+    for fixture in ReceiptFixture::all() {
+        let prepared = fixture.prepare();
+        let expected = contract_receipt(&env, &prepared);
+
+        assert_eq!(
+            prepared.journal_digest,
+            env.crypto()
+                .sha256(&Bytes::from_slice(&env, &prepared.journal))
+                .to_array(),
+            "fixture {}",
+            fixture.name
+        );
+        assert_eq!(
+            prepared.claim_digest,
+            expected.claim_digest.to_array(),
+            "fixture {}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn test_receipt_xdr_matches_the_contract_type() {
+    let env = Env::default();
+
+    for fixture in ReceiptFixture::all() {
+        let prepared = fixture.prepare();
+        let expected = contract_receipt(&env, &prepared).to_xdr(&env);
+        let xdr = prepared.receipt_xdr().expect("receipt encodes");
+
+        assert_eq!(
+            Bytes::from_slice(&env, &xdr),
+            expected,
+            "fixture {}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn test_verify_args_match_the_contract_values() {
+    let env = Env::default();
+    let prepared = ReceiptFixture::all()[0].prepare();
+    let [seal, image_id, journal_digest] = prepared.verify_args().expect("args encode");
+
+    let encoded = |value: stellar_xdr::curr::ScVal| {
+        Bytes::from_slice(
+            &env,
+            &WriteXdr::to_xdr(&value, Limits::none()).expect("value encodes"),
+        )
+    };
+    assert_eq!(
+        encoded(seal),
+        Bytes::from_slice(&env, &prepared.seal).to_xdr(&env)
+    );
+    assert_eq!(
+        encoded(image_id),
+        BytesN::from_array(&env, &prepared.image_id).to_xdr(&env)
+    );
+    assert_eq!(
+        encoded(journal_digest),
+        BytesN::from_array(&env, &prepared.journal_digest).to_xdr(&env)
+    );
+}
+
+#[test]
+fn test_prepared_fixtures_verify_on_the_groth16_verifier() {
+    let env = Env::default();
+    let contract_id = env.register(RiscZeroGroth16Verifier, ());
+    let client = RiscZeroGroth16VerifierClient::new(&env, &contract_id);
+
+    for fixture in ReceiptFixture::all() {
+        let prepared = fixture.prepare();
+        let xdr = prepared.receipt_xdr().expect("receipt encodes");
+        let receipt =
+            Receipt::from_xdr(&env, &Bytes::from_slice(&env, &xdr)).expect("receipt decodes");
+
+        client.verify_integrity(&receipt);
+        client.verify(
+            &Bytes::from_slice(&env, &prepared.seal),
+            &BytesN::from_array(&env, &prepared.image_id),
+            &BytesN::from_array(&env, &prepared.journal_digest),
+        );
+    }
+}
+
+#[test]
+fn test_groth16_prefixes_the_selector() {
+    let fixture = ReceiptFixture::all()[0].prepare();
+    let (selector, proof) = fixture.seal.split_at(4);
+
+    let prepared = PreparedReceipt::groth16(
+        selector.try_into().expect("selector is 4 bytes"),
+        proof,
+        fixture.image_id,
+        fixture.journal.clone(),
+    )
+    .expect("proof is 256 bytes");
+
+    assert_eq!(prepared, fixture);
+    assert_eq!(prepared.selector(), selector);
+}
+
+#[test]
+fn test_dev_receipt_verifies_on_the_mock_verifier() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let selector = BytesN::from_array(&env, &[0x11, 0x22, 0x33, 0x44]);
+    let contract_id = env.register(RiscZeroMockVerifier, (selector, Address::generate(&env)));
+    let client = RiscZeroMockVerifierClient::new(&env, &contract_id);
+
+    let prepared = PreparedReceipt::dev([0x42; 32], b"journal".to_vec());
+    assert_eq!(prepared.selector(), DEV_MODE_SELECTOR);
+    assert_eq!(&prepared.seal[4..], &prepared.claim_digest);
+
+    client.verify(
+        &Bytes::from_slice(&env, &prepared.seal),
+        &BytesN::from_array(&env, &prepared.image_id),
+        &BytesN::from_array(&env, &prepared.journal_digest),
+    );
+}
+
+#[test]
+fn test_free_functions_match_prepared_receipts() {
+    let prepared = PreparedReceipt::dev([0x07; 32], vec![1, 2, 3]);
+    assert_eq!(journal_digest(&[1, 2, 3]), prepared.journal_digest);
+    assert_eq!(
+        claim_digest(&[0x07; 32], &prepared.journal_digest),
+        prepared.claim_digest
+    );
+}
+
+#[test]
+fn test_rejects_malformed_seals() {
+    assert!(matches!(
+        PreparedReceipt::new(vec![0xFF; 3], [0; 32], vec![]),
+        Err(Error::SealTooShort(3))
+    ));
+    for len in [0, GROTH16_PROOF_LEN - 1, GROTH16_PROOF_LEN + 1] {
+        assert!(matches!(
+            PreparedReceipt::groth16([0; 4], &vec![0; len], [0; 32], vec![]),
+            Err(Error::ProofLength(l)) if l == len
+        ));
+    }
+}